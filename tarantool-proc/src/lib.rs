@@ -989,6 +989,219 @@ mod msgpack {
     }
 }
 
+mod space {
+    use darling::util::Flag;
+    use darling::{FromDeriveInput, FromField};
+    use proc_macro2::TokenStream;
+    use quote::{format_ident, quote};
+    use syn::{Ident, Path, Type};
+
+    #[derive(FromDeriveInput)]
+    #[darling(attributes(space), supports(struct_named))]
+    pub struct Args {
+        pub ident: Ident,
+        pub vis: syn::Visibility,
+        pub generics: syn::Generics,
+        pub data: darling::ast::Data<(), FieldArgs>,
+        /// Name of the space.
+        pub name: String,
+        /// Path to tarantool crate.
+        pub tarantool: Option<String>,
+    }
+
+    #[derive(FromField)]
+    #[darling(attributes(space))]
+    pub struct FieldArgs {
+        pub ident: Option<Ident>,
+        pub ty: Type,
+        /// The field is a part of the primary key.
+        pub primary_key: Flag,
+        /// Names of the non-unique secondary indexes the field is a part of.
+        #[darling(multiple)]
+        pub index: Vec<syn::LitStr>,
+        /// Names of the unique secondary indexes the field is a part of.
+        #[darling(multiple)]
+        pub unique_index: Vec<syn::LitStr>,
+        /// Overrides the field type which is otherwise deduced from the
+        /// field's rust type.
+        pub field_type: Option<String>,
+        /// Marks the field as nullable, only used together with `field_type`.
+        pub is_nullable: Flag,
+    }
+
+    struct IndexInfo<'a> {
+        name: String,
+        unique: bool,
+        parts: Vec<(&'a Ident, &'a Type, TokenStream)>,
+    }
+
+    pub fn expand(args: &Args) -> Result<TokenStream, syn::Error> {
+        let tarantool_crate: Path = args
+            .tarantool
+            .as_deref()
+            .map(syn::parse_str)
+            .transpose()?
+            .unwrap_or_else(super::default_tarantool_crate_path);
+        let ident = &args.ident;
+        let vis = &args.vis;
+        let space_name = &args.name;
+
+        if !args.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                ident.span(),
+                "generic structs are not supported by #[derive(Space)]",
+            ));
+        }
+
+        let fields = args
+            .data
+            .as_ref()
+            .take_struct()
+            .expect("only named structs are supported");
+
+        let mut format = Vec::with_capacity(fields.len());
+        let mut primary = IndexInfo {
+            name: "primary".into(),
+            unique: true,
+            parts: vec![],
+        };
+        let mut secondary: Vec<IndexInfo> = vec![];
+        for field in fields.fields {
+            let field_ident = field
+                .ident
+                .as_ref()
+                .expect("only named structs are supported");
+            let field_name = field_ident.to_string();
+            let ty = &field.ty;
+            let (field_type, is_nullable) = if let Some(field_type) = &field.field_type {
+                let mut variant = field_type.clone();
+                if let Some(first) = variant.get_mut(0..1) {
+                    first.make_ascii_uppercase();
+                }
+                let variant = Ident::new(&variant, field_ident.span());
+                let is_nullable = field.is_nullable.is_present();
+                (
+                    quote! { #tarantool_crate::space::FieldType::#variant },
+                    quote! { #is_nullable },
+                )
+            } else {
+                (
+                    quote! { <#ty as #tarantool_crate::space::typed::KnownFieldType>::FIELD_TYPE },
+                    quote! { <#ty as #tarantool_crate::space::typed::KnownFieldType>::IS_NULLABLE },
+                )
+            };
+
+            format.push(quote! {
+                #tarantool_crate::space::Field {
+                    name: ::std::string::String::from(#field_name),
+                    field_type: #field_type,
+                    is_nullable: #is_nullable,
+                }
+            });
+
+            if field.primary_key.is_present() {
+                primary.parts.push((field_ident, ty, is_nullable.clone()));
+            }
+
+            let indexes = field.index.iter().map(|name| (name, false));
+            let unique_indexes = field.unique_index.iter().map(|name| (name, true));
+            for (lit, unique) in indexes.chain(unique_indexes) {
+                let name = lit.value();
+                if name == primary.name {
+                    return Err(syn::Error::new(
+                        field_ident.span(),
+                        format!("index name '{name}' is reserved for the primary key"),
+                    ));
+                }
+                // The name is used for the index's associated constant
+                if syn::parse_str::<Ident>(&name.to_uppercase()).is_err() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        format!("index name '{name}' must be a valid rust identifier"),
+                    ));
+                }
+                let part = (field_ident, ty, is_nullable.clone());
+                match secondary.iter_mut().find(|index| index.name == name) {
+                    Some(index) if index.unique != unique => {
+                        return Err(syn::Error::new(
+                            field_ident.span(),
+                            format!("index '{name}' is specified as both unique and non-unique"),
+                        ));
+                    }
+                    Some(index) => index.parts.push(part),
+                    None => secondary.push(IndexInfo {
+                        name,
+                        unique,
+                        parts: vec![part],
+                    }),
+                }
+            }
+        }
+
+        if primary.parts.is_empty() {
+            return Err(syn::Error::new(
+                ident.span(),
+                "at least one field must be marked with #[space(primary_key)]",
+            ));
+        }
+
+        let index_defs = std::iter::once(&primary).chain(&secondary).map(|index| {
+            let IndexInfo {
+                name,
+                unique,
+                parts,
+            } = index;
+            let parts = parts.iter().map(|(field_ident, _, is_nullable)| {
+                let field_name = field_ident.to_string();
+                quote! {
+                    #tarantool_crate::index::Part::field(::std::string::String::from(#field_name))
+                        .is_nullable(#is_nullable)
+                }
+            });
+            quote! {
+                #tarantool_crate::space::typed::IndexDef {
+                    name: #name,
+                    unique: #unique,
+                    parts: ::std::vec![#(#parts),*],
+                }
+            }
+        });
+
+        let mut index_specs = Vec::with_capacity(secondary.len());
+        for index in &secondary {
+            let name = &index.name;
+            let const_name = format_ident!("{}", name.to_uppercase());
+            let key_types = index.parts.iter().map(|(_, ty, _)| ty);
+            let doc = format!("Secondary index `{name}` of space `{space_name}`.");
+            index_specs.push(quote! {
+                #[doc = #doc]
+                #vis const #const_name: #tarantool_crate::space::typed::IndexSpec<Self, (#(#key_types,)*)> =
+                    #tarantool_crate::space::typed::IndexSpec::new(#name);
+            });
+        }
+
+        let primary_key_types = primary.parts.iter().map(|(_, ty, _)| ty);
+        Ok(quote! {
+            impl #tarantool_crate::space::typed::Row for #ident {
+                const SPACE_NAME: &'static str = #space_name;
+                type PrimaryKey = (#(#primary_key_types,)*);
+
+                fn format() -> ::std::vec::Vec<#tarantool_crate::space::Field> {
+                    ::std::vec![#(#format),*]
+                }
+
+                fn indexes() -> ::std::vec::Vec<#tarantool_crate::space::typed::IndexDef> {
+                    ::std::vec![#(#index_defs),*]
+                }
+            }
+
+            impl #ident {
+                #(#index_specs)*
+            }
+        })
+    }
+}
+
 /// Utility function to get a span range of the attributes.
 fn attrs_span<'a>(attrs: impl IntoIterator<Item = &'a Attribute>) -> SpanRange {
     SpanRange::from_tokens(
@@ -1121,6 +1334,22 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+/// Macro to automatically derive `tarantool::space::typed::Row`
+/// Deriving this trait describes the space schema (format and indexes) for
+/// values of this struct, so that they can be used with
+/// `tarantool::space::typed::TypedSpace`.
+///
+/// For more information see `tarantool::space::typed`
+#[proc_macro_derive(Space, attributes(space))]
+pub fn derive_space(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let args = match <space::Args as darling::FromDeriveInput>::from_derive_input(&input) {
+        Ok(args) => args,
+        Err(e) => return e.write_errors().into(),
+    };
+    unwrap_or_compile_error!(space::expand(&args)).into()
+}
+
 /// Create a tarantool stored procedure.
 ///
 /// See `tarantool::proc` doc-comments in tarantool crate for details.
@@ -1,9 +1,11 @@
-#![cfg(feature = "picodata")]
-
+#[cfg(feature = "picodata")]
 use std::ffi::CString;
 
+#[cfg(feature = "picodata")]
 use crate::error;
+#[cfg(feature = "picodata")]
 use crate::ffi::tarantool as ffi;
+#[cfg(feature = "picodata")]
 use crate::space::SpaceId;
 
 /// This is a direct translation of `box_privilege_type` enum from `user_def.h`
@@ -46,6 +48,7 @@ pub enum PrivType {
 /// This function is a wrapper around similarly named one in tarantool.
 /// It allows to run access check for the current user against
 /// specified space and access type. Most relevant access types are read and write.
+#[cfg(feature = "picodata")]
 pub fn box_access_check_space(space_id: SpaceId, user_access: PrivType) -> crate::Result<()> {
     let ret = unsafe { ffi::box_access_check_space(space_id, user_access as u16) };
     if ret == -1 {
@@ -80,9 +83,28 @@ pub enum SchemaObjectType {
 }
 
 impl SchemaObjectType {
-    fn is_entity(&self) -> bool {
+    /// Returns `true` if this is an entity type, i.e. it refers to all objects
+    /// of the given type, e.g. [`Self::EntitySpace`] means all spaces.
+    #[inline(always)]
+    pub fn is_entity(&self) -> bool {
         *self as u32 > SchemaObjectType::ObjectTypeMax as u32
     }
+
+    /// Returns the name of the object type as stored in the `_priv` system
+    /// space. Entity types have the same names as the corresponding object
+    /// types.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Universe => "universe",
+            Self::Space | Self::EntitySpace => "space",
+            Self::Function | Self::EntityFunction => "function",
+            Self::User | Self::EntityUser => "user",
+            Self::Role | Self::EntityRole => "role",
+            Self::Sequence | Self::EntitySequence => "sequence",
+            Self::Collation | Self::EntityCollation => "collation",
+            Self::Unknown | Self::ObjectTypeMax | Self::EntityTypeMax => "unknown",
+        }
+    }
 }
 
 /// This function allows to perform various permission checks externally.
@@ -107,6 +129,7 @@ impl SchemaObjectType {
 ///
 /// So given these limitations box_access_check_ddl guards against
 /// invalid combinations that lead to UB by panicking instead.
+#[cfg(feature = "picodata")]
 pub fn box_access_check_ddl(
     object_name: &str,
     object_id: u32,
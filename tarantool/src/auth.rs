@@ -66,6 +66,24 @@ mod picodata {
     }
 }
 
+#[cfg(not(feature = "picodata"))]
+mod vanilla {
+    use super::AuthMethod;
+    use sha1::{Digest as Sha1Digest, Sha1};
+
+    /// Ported from `box.schema.user.password`: for 'chap-sha1' the auth data
+    /// is `base64(sha1(sha1(password)))`.
+    pub(super) fn auth_data_prepare(method: &AuthMethod, _user: &str, password: &str) -> String {
+        match method {
+            AuthMethod::ChapSha1 => {
+                let step_1 = Sha1::digest(password.as_bytes());
+                let step_2 = Sha1::digest(&step_1);
+                base64::encode(step_2)
+            }
+        }
+    }
+}
+
 pub struct AuthData(String);
 
 impl AuthData {
@@ -75,6 +93,12 @@ impl AuthData {
         Self(data)
     }
 
+    #[cfg(not(feature = "picodata"))]
+    pub fn new(method: &AuthMethod, user: &str, password: &str) -> Self {
+        let data = vanilla::auth_data_prepare(method, user, password);
+        Self(data)
+    }
+
     pub fn into_string(self) -> String {
         self.0
     }
@@ -92,3 +116,15 @@ impl AuthDef {
         Self { method, data }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "picodata"))]
+    #[test]
+    fn chap_sha1_auth_data() {
+        use super::{AuthData, AuthMethod};
+        // Same as `box.schema.user.password('password')`.
+        let data = AuthData::new(&AuthMethod::ChapSha1, "user", "password");
+        assert_eq!(data.into_string(), "JHDAwG3uQv0WGLuZAFrcouydHhk=");
+    }
+}
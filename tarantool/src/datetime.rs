@@ -1,6 +1,7 @@
 use crate::ffi::datetime as ffi;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::fmt::Display;
 use time::{Duration, UtcOffset};
 
@@ -21,10 +22,17 @@ pub enum Error {
 /// If `has_datetime` return `false`, using functions from this module
 /// may result in a **panic**.
 ///
+/// A `Datetime` can have a named timezone (e.g. `Europe/Moscow`), which is
+/// stored as the timezone index, see [`Datetime::tzindex`] & [`Datetime::tz`].
+/// The timezone is preserved when the value is passed to and from tarantool,
+/// but it doesn't affect comparison, which only takes the moment in time into
+/// account.
+///
 /// [`tarantool::ffi::has_datetime`]: crate::ffi::has_datetime
-#[derive(Debug, Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Debug, Copy, Clone)]
 pub struct Datetime {
     inner: Inner,
+    tzindex: i16,
 }
 
 impl Datetime {
@@ -38,6 +46,94 @@ impl Datetime {
         self.into()
     }
 
+    /// Index of the named timezone of the datetime or `0` if it only has
+    /// an offset. See also [`Datetime::tz`].
+    #[inline(always)]
+    pub fn tzindex(&self) -> i16 {
+        self.tzindex
+    }
+
+    /// Returns a copy of `self` with the timezone index set to `tzindex`.
+    ///
+    /// **Note** that the offset of the datetime isn't changed, so it's up to
+    /// the caller to make sure it matches the timezone. Use
+    /// [`Datetime::parse`] to get a datetime with a named timezone from
+    /// a string.
+    #[inline(always)]
+    pub fn with_tzindex(self, tzindex: i16) -> Self {
+        Self { tzindex, ..self }
+    }
+
+    /// Name of the timezone of the datetime, e.g. `"Europe/Moscow"`. Returns
+    /// `None` if the datetime only has an offset.
+    ///
+    /// Same as `dt.tz` in lua.
+    #[inline]
+    pub fn tz(&self) -> Option<String> {
+        if self.tzindex == 0 {
+            return None;
+        }
+        tz_name(self.tzindex)
+    }
+
+    /// Parse a datetime from a string in ISO 8601 format, e.g.
+    /// `"2023-11-11T10:11:12.10142+0500"` or
+    /// `"2023-11-11T10:11:12 Europe/Moscow"`.
+    ///
+    /// Same as `datetime.parse(s)` in lua.
+    #[inline(always)]
+    pub fn parse(s: &str) -> crate::Result<Self> {
+        Self::parse_with_format(s, Format::Iso8601)
+    }
+
+    /// Parse a datetime from a string in the given `format`.
+    ///
+    /// Same as `datetime.parse(s, { format = format })` in lua.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::datetime::{Datetime, Format};
+    ///
+    /// let dt = Datetime::parse_with_format("2023-11-11T10:11:12Z", Format::Rfc3339).unwrap();
+    /// let dt = Datetime::parse_with_format("11.11.2023", Format::Strftime("%d.%m.%Y")).unwrap();
+    /// ```
+    pub fn parse_with_format(s: &str, format: Format) -> crate::Result<Self> {
+        let dt = crate::global_lua()
+            .eval_with(
+                "local s, format = ...
+                return (require('datetime').parse(s, { format = format }))",
+                (s, format.as_str()),
+            )
+            .map_err(tlua::LuaError::from)?;
+        Ok(dt)
+    }
+
+    /// Format the datetime according to the strftime-like `format`, e.g.
+    /// `"%Y-%m-%d %H:%M:%S %Z"`.
+    ///
+    /// Same as `dt:format(format)` in lua.
+    pub fn format(&self, format: &str) -> crate::Result<String> {
+        let res = crate::global_lua()
+            .eval_with(
+                "local dt, format = ...
+                return dt:format(format)",
+                (self, format),
+            )
+            .map_err(tlua::LuaError::from)?;
+        Ok(res)
+    }
+
+    /// Format the datetime the same way tarantool does by default, e.g.
+    /// `"2023-11-11T10:11:12.10142+0500"` or
+    /// `"2023-11-11T10:11:12 Europe/Moscow"`.
+    ///
+    /// Same as `tostring(dt)` in lua.
+    pub fn to_tarantool_string(&self) -> String {
+        crate::global_lua()
+            .eval_with("return tostring(...)", self)
+            .expect("tostring for datetime never fails")
+    }
+
     /// Convert an array of bytes (internal tarantool msgpack ext)
     /// in the little endian order into a `DateTime`.
     #[inline(always)]
@@ -47,14 +143,17 @@ impl Datetime {
 
         let mut nsec_bytes: [u8; 4] = [0; 4];
         let mut tzoffest_bytes: [u8; 2] = [0; 2];
+        let mut tzindex_bytes: [u8; 2] = [0; 2];
         if bytes.len() == 16 {
             nsec_bytes.copy_from_slice(&bytes[8..12]);
             tzoffest_bytes.copy_from_slice(&bytes[12..14]);
+            tzindex_bytes.copy_from_slice(&bytes[14..16]);
         }
 
         let secs = i64::from_le_bytes(sec_bytes);
         let nsecs = u32::from_le_bytes(nsec_bytes);
         let tzoffset: i32 = i16::from_le_bytes(tzoffest_bytes).into();
+        let tzindex = i16::from_le_bytes(tzindex_bytes);
 
         let utc_offset =
             UtcOffset::from_whole_seconds(tzoffset * 60).map_err(Error::WrongUtcOffset)?;
@@ -63,7 +162,7 @@ impl Datetime {
             .to_offset(utc_offset)
             + Duration::nanoseconds(nsecs as i64);
 
-        Ok(dt.into())
+        Ok(Self::from(dt).with_tzindex(tzindex))
     }
 
     /// Return an array of bytes (internal tarantool msgpack ext) in the little endian order.
@@ -74,6 +173,7 @@ impl Datetime {
         buf[0..8].copy_from_slice(&self.inner.unix_timestamp().to_le_bytes());
         buf[8..12].copy_from_slice(&self.inner.nanosecond().to_le_bytes());
         buf[12..14].copy_from_slice(&self.inner.offset().whole_minutes().to_le_bytes());
+        buf[14..16].copy_from_slice(&self.tzindex.to_le_bytes());
 
         buf
     }
@@ -87,7 +187,7 @@ impl Datetime {
             .to_offset(utc_offset)
             + Duration::nanoseconds(inner.nsec as i64);
 
-        Ok(dt.into())
+        Ok(Self::from(dt).with_tzindex(inner.tzindex))
     }
 
     #[inline(always)]
@@ -96,7 +196,7 @@ impl Datetime {
             epoch: self.inner.unix_timestamp() as f64,
             nsec: self.inner.nanosecond() as i32,
             tzoffset: self.inner.offset().whole_minutes(),
-            tzindex: 0,
+            tzindex: self.tzindex,
         }
     }
 }
@@ -104,7 +204,7 @@ impl Datetime {
 impl From<Inner> for Datetime {
     #[inline(always)]
     fn from(inner: Inner) -> Self {
-        Self { inner }
+        Self { inner, tzindex: 0 }
     }
 }
 
@@ -121,6 +221,441 @@ impl Display for Datetime {
     }
 }
 
+impl PartialEq for Datetime {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl Eq for Datetime {}
+
+impl PartialOrd for Datetime {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Datetime {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.inner.cmp(&other.inner)
+    }
+}
+
+impl std::hash::Hash for Datetime {
+    #[inline(always)]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+/// Timezones & formats
+////////////////////////////////////////////////////////////////////////////////
+
+/// Format of a datetime string, see [`Datetime::parse_with_format`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Format<'a> {
+    /// ISO 8601, e.g. `"2023-11-11T10:11:12.10142+0500"`. Also accepts
+    /// timezone names, e.g. `"2023-11-11T10:11:12 Europe/Moscow"`.
+    ///
+    /// Same as `format = 'iso8601'` in lua.
+    Iso8601,
+    /// RFC 3339, e.g. `"2023-11-11T10:11:12.10142+05:00"`.
+    ///
+    /// Same as `format = 'rfc3339'` in lua.
+    Rfc3339,
+    /// A strftime-like pattern, e.g. `"%Y-%m-%d %H:%M:%S %z"`.
+    Strftime(&'a str),
+}
+
+impl Format<'_> {
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Iso8601 => "iso8601",
+            Self::Rfc3339 => "rfc3339",
+            Self::Strftime(format) => format,
+        }
+    }
+}
+
+/// Returns the index of the timezone with the given `name`, e.g.
+/// `"Europe/Moscow"` or `"MSK"`. Returns `None` if there's no such timezone.
+///
+/// The index is what's stored in a [`Datetime`] with a named timezone, see
+/// [`Datetime::tzindex`].
+pub fn tz_index(name: &str) -> Option<i16> {
+    crate::global_lua()
+        .eval_with(
+            "local ok, dt = pcall(require('datetime').new, { tz = ... })
+            if ok then return dt.tzindex end",
+            name,
+        )
+        .ok()
+        .flatten()
+}
+
+/// Returns the name of the timezone with the given `index`. Returns `None` if
+/// there's no such timezone.
+///
+/// See also [`tz_index`].
+pub fn tz_name(index: i16) -> Option<String> {
+    if index == 0 {
+        return None;
+    }
+    crate::global_lua()
+        .eval_with(
+            "local dt = require('ffi').new('struct datetime', { tzindex = ... })
+            local ok, tz = pcall(function() return dt.tz end)
+            if ok and tz ~= nil and tz ~= '' then return tz end",
+            index,
+        )
+        .ok()
+        .flatten()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+/// Interval
+////////////////////////////////////////////////////////////////////////////////
+
+/// Adjustment mode for the day of month used when years or months of an
+/// [`Interval`] are added to a [`Datetime`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Adjust {
+    /// The day is truncated to the end of month if needed,
+    /// e.g. `2024-01-31 + 1 month = 2024-02-29`.
+    ///
+    /// Same as `adjust = 'none'` in lua.
+    #[default]
+    None,
+    /// Same as [`Adjust::None`], but if the day was the last day of month, it
+    /// snaps to the end of the resulting month,
+    /// e.g. `2024-02-29 + 1 month = 2024-03-31`.
+    ///
+    /// Same as `adjust = 'last'` in lua.
+    Last,
+    /// The days which don't fit into the resulting month overflow into the
+    /// next one, e.g. `2024-01-31 + 1 month = 2024-03-02`.
+    ///
+    /// Same as `adjust = 'excess'` in lua.
+    Excess,
+}
+
+impl Adjust {
+    #[inline(always)]
+    fn from_ffi(adjust: i32) -> Option<Self> {
+        match adjust {
+            ffi::DT_LIMIT => Some(Self::None),
+            ffi::DT_SNAP => Some(Self::Last),
+            ffi::DT_EXCESS => Some(Self::Excess),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn as_ffi(&self) -> i32 {
+        match self {
+            Self::None => ffi::DT_LIMIT,
+            Self::Last => ffi::DT_SNAP,
+            Self::Excess => ffi::DT_EXCESS,
+        }
+    }
+}
+
+/// A datetime interval, the rust counterpart of tarantool's
+/// `datetime.interval`. It's stored in msgpack as the `MP_INTERVAL` extension
+/// and can be used in fields of type [`FieldType::Interval`].
+///
+/// Intervals can be added to or subtracted from a [`Datetime`] and are
+/// returned when subtracting one datetime from another.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::datetime::{Adjust, Datetime, Interval};
+/// # fn now() -> Datetime { unimplemented!() }
+///
+/// let next_month = Interval {
+///     month: 1,
+///     adjust: Adjust::Last,
+///     ..Default::default()
+/// };
+/// let deadline = now() + next_month;
+/// ```
+///
+/// [`FieldType::Interval`]: crate::space::FieldType::Interval
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub year: i32,
+    pub month: i32,
+    pub week: i32,
+    pub day: i64,
+    pub hour: i64,
+    pub min: i64,
+    pub sec: i64,
+    pub nsec: i32,
+    /// Specifies how the day of month is adjusted when years or months are
+    /// added.
+    pub adjust: Adjust,
+}
+
+/// Keys of the fields of `MP_INTERVAL` msgpack extension, see
+/// `enum interval_fields` in tarantool sources.
+mod interval_field {
+    pub const YEAR: u8 = 0;
+    pub const MONTH: u8 = 1;
+    pub const WEEK: u8 = 2;
+    pub const DAY: u8 = 3;
+    pub const HOUR: u8 = 4;
+    pub const MINUTE: u8 = 5;
+    pub const SECOND: u8 = 6;
+    pub const NANOSECOND: u8 = 7;
+    pub const ADJUST: u8 = 8;
+}
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+impl Interval {
+    /// Return the payload of the `MP_INTERVAL` msgpack extension representing
+    /// `self`. Only the non zero fields are encoded.
+    fn to_ext_data(self) -> Vec<u8> {
+        use interval_field::*;
+
+        let mut fields = vec![
+            (YEAR, self.year as i64),
+            (MONTH, self.month as i64),
+            (WEEK, self.week as i64),
+            (DAY, self.day),
+            (HOUR, self.hour),
+            (MINUTE, self.min),
+            (SECOND, self.sec),
+            (NANOSECOND, self.nsec as i64),
+        ];
+        fields.retain(|&(_, value)| value != 0);
+        if self.adjust != Adjust::None {
+            fields.push((ADJUST, self.adjust.as_ffi() as i64));
+        }
+
+        let mut data = Vec::with_capacity(1 + fields.len() * 10);
+        data.push(fields.len() as u8);
+        for (field, value) in fields {
+            data.push(field);
+            // Writing to a vector never fails
+            if value < 0 {
+                rmp::encode::write_sint(&mut data, value).unwrap();
+            } else {
+                rmp::encode::write_uint(&mut data, value as u64).unwrap();
+            }
+        }
+        data
+    }
+
+    /// Decode an `Interval` from the payload of the `MP_INTERVAL` msgpack
+    /// extension.
+    fn from_ext_data(mut data: &[u8]) -> Result<Self, String> {
+        use interval_field::*;
+
+        fn narrow(value: i64, field: &str) -> Result<i32, String> {
+            i32::try_from(value).map_err(|_| format!("interval {field} is out of range: {value}"))
+        }
+
+        let mut res = Self::default();
+        let (&count, tail) = data.split_first().ok_or("not enough data")?;
+        data = tail;
+        for _ in 0..count {
+            let (&field, tail) = data.split_first().ok_or("not enough data")?;
+            data = tail;
+            let value: i64 = rmp::decode::read_int(&mut data).map_err(|e| e.to_string())?;
+            match field {
+                YEAR => res.year = narrow(value, "year")?,
+                MONTH => res.month = narrow(value, "month")?,
+                WEEK => res.week = narrow(value, "week")?,
+                DAY => res.day = value,
+                HOUR => res.hour = value,
+                MINUTE => res.min = value,
+                SECOND => res.sec = value,
+                NANOSECOND => res.nsec = narrow(value, "nsec")?,
+                ADJUST => {
+                    res.adjust = Adjust::from_ffi(value as _)
+                        .ok_or_else(|| format!("unknown interval adjust mode {value}"))?;
+                }
+                _ => return Err(format!("unknown interval field {field}")),
+            }
+        }
+        Ok(res)
+    }
+
+    #[inline]
+    fn from_ffi_interval(inner: ffi::interval) -> Result<Self, String> {
+        Ok(Self {
+            year: inner.year,
+            month: inner.month,
+            week: inner.week,
+            day: inner.day as _,
+            hour: inner.hour as _,
+            min: inner.min as _,
+            sec: inner.sec as _,
+            nsec: inner.nsec,
+            adjust: Adjust::from_ffi(inner.adjust)
+                .ok_or_else(|| format!("unknown interval adjust mode {}", inner.adjust))?,
+        })
+    }
+
+    #[inline]
+    fn as_ffi_interval(&self) -> ffi::interval {
+        ffi::interval {
+            sec: self.sec as _,
+            min: self.min as _,
+            hour: self.hour as _,
+            day: self.day as _,
+            month: self.month,
+            year: self.year,
+            week: self.week,
+            nsec: self.nsec,
+            adjust: self.adjust.as_ffi(),
+        }
+    }
+}
+
+impl Datetime {
+    /// Add `interval` to `self`. Returns `None` if the result is out of the
+    /// supported range.
+    ///
+    /// Same as tarantool, years and months are added first preserving the day
+    /// of month (if possible, see [`Adjust`]), then weeks and days are added
+    /// preserving the local time of day and then the rest of the fields.
+    ///
+    /// If `self` has a named timezone (see [`Datetime::tz`]) the result is
+    /// computed by tarantool via `datetime_increment_by`, because the offset
+    /// of the result depends on the timezone's rules, e.g. daylight saving
+    /// time transitions.
+    #[inline(always)]
+    pub fn checked_add(self, interval: Interval) -> Option<Self> {
+        self.increment_by(1, &interval)
+    }
+
+    /// Subtract `interval` from `self`. Returns `None` if the result is out of
+    /// the supported range.
+    ///
+    /// See also [`Datetime::checked_add`].
+    #[inline(always)]
+    pub fn checked_sub(self, interval: Interval) -> Option<Self> {
+        self.increment_by(-1, &interval)
+    }
+
+    fn increment_by(self, direction: i64, ival: &Interval) -> Option<Self> {
+        if self.tzindex != 0 {
+            let mut res = self.as_ffi_dt();
+            let ival = ival.as_ffi_interval();
+            // SAFETY: both pointers point to valid values
+            let rc = unsafe { ffi::datetime_increment_by(&mut res, direction as _, &ival) };
+            if rc != 0 {
+                return None;
+            }
+            return Self::from_ffi_dt(res).ok();
+        }
+
+        let mut date = self.inner.date();
+        if ival.year != 0 {
+            let months = direction * 12 * ival.year as i64;
+            date = add_months(date, months, ival.adjust)?;
+        }
+        if ival.month != 0 {
+            date = add_months(date, direction * ival.month as i64, ival.adjust)?;
+        }
+        let days = (ival.week as i64 * 7).checked_add(ival.day)?;
+        if days != 0 {
+            let secs = days.checked_mul(direction * SECS_PER_DAY)?;
+            date = date.checked_add(Duration::seconds(secs))?;
+        }
+
+        let secs = ival
+            .hour
+            .checked_mul(60 * 60)?
+            .checked_add(ival.min.checked_mul(60)?)?
+            .checked_add(ival.sec)?
+            .checked_mul(direction)?;
+        let nsecs = direction * ival.nsec as i64;
+        let duration = Duration::seconds(secs).checked_add(Duration::nanoseconds(nsecs))?;
+
+        let res = time::PrimitiveDateTime::new(date, self.inner.time())
+            .assume_offset(self.inner.offset())
+            .checked_add(duration)?;
+        Some(Self::from(res).with_tzindex(self.tzindex))
+    }
+}
+
+/// Add `delta` months to `date` adjusting the day of month according to
+/// `adjust`.
+fn add_months(date: time::Date, delta: i64, adjust: Adjust) -> Option<time::Date> {
+    let (year, month, day) = date.to_calendar_date();
+    let months = (year as i64 * 12 + month as i64 - 1).checked_add(delta)?;
+    let new_year = i32::try_from(months.div_euclid(12)).ok()?;
+    let new_month = time::Month::try_from((months.rem_euclid(12) + 1) as u8).ok()?;
+    let last_day = time::util::days_in_year_month(new_year, new_month);
+    let new_day = match adjust {
+        Adjust::Excess if day > last_day => {
+            let first = time::Date::from_calendar_date(new_year, new_month, 1).ok()?;
+            return first.checked_add(Duration::days(day as i64 - 1));
+        }
+        Adjust::Last if day == time::util::days_in_year_month(year, month) => last_day,
+        _ => day.min(last_day),
+    };
+    time::Date::from_calendar_date(new_year, new_month, new_day).ok()
+}
+
+impl std::ops::Add<Interval> for Datetime {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result is out of the supported range, see
+    /// [`Datetime::checked_add`] for a non-panicking version.
+    #[inline]
+    fn add(self, interval: Interval) -> Self {
+        self.checked_add(interval)
+            .expect("overflow when adding interval to datetime")
+    }
+}
+
+impl std::ops::Sub<Interval> for Datetime {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if the result is out of the supported range, see
+    /// [`Datetime::checked_sub`] for a non-panicking version.
+    #[inline]
+    fn sub(self, interval: Interval) -> Self {
+        self.checked_sub(interval)
+            .expect("overflow when subtracting interval from datetime")
+    }
+}
+
+impl std::ops::Sub for Datetime {
+    type Output = Interval;
+
+    /// Returns the interval between two datetimes expressed in days, hours,
+    /// minutes, seconds and nanoseconds, so that `rhs + (self - rhs) == self`.
+    fn sub(self, rhs: Self) -> Interval {
+        let diff = self.inner - rhs.inner;
+        let mut secs = diff.whole_seconds();
+        let mut nsec = diff.subsec_nanoseconds();
+        if nsec < 0 {
+            secs -= 1;
+            nsec += 1_000_000_000;
+        }
+        Interval {
+            day: secs / SECS_PER_DAY,
+            hour: secs % SECS_PER_DAY / (60 * 60),
+            min: secs % (60 * 60) / 60,
+            sec: secs % 60,
+            nsec,
+            ..Default::default()
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 /// Tuple
 ////////////////////////////////////////////////////////////////////////////////
@@ -134,10 +669,7 @@ impl serde::Serialize for Datetime {
         struct _ExtStruct<'a>((i8, &'a serde_bytes::Bytes));
 
         let data = self.as_bytes_tt();
-        let mut data = data.as_slice();
-        if data[8..] == [0, 0, 0, 0, 0, 0, 0, 0] {
-            data = &data[..8];
-        }
+        let data = ext_data(&data);
         _ExtStruct((ffi::MP_DATETIME, serde_bytes::Bytes::new(data))).serialize(serializer)
     }
 }
@@ -159,16 +691,113 @@ impl<'de> serde::Deserialize<'de> for Datetime {
             )));
         }
 
-        let data = bytes.as_slice();
+        Self::from_ext_data(bytes.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl crate::msgpack::Encode for Datetime {
+    #[inline]
+    fn encode(
+        &self,
+        w: &mut impl std::io::Write,
+        _context: &crate::msgpack::Context,
+    ) -> Result<(), crate::msgpack::EncodeError> {
+        let data = self.as_bytes_tt();
+        crate::msgpack::write_ext(w, ffi::MP_DATETIME, ext_data(&data))
+    }
+}
+
+impl<'de> crate::msgpack::Decode<'de> for Datetime {
+    #[inline]
+    fn decode(
+        r: &mut &'de [u8],
+        _context: &crate::msgpack::Context,
+    ) -> Result<Self, crate::msgpack::DecodeError> {
+        let data = crate::msgpack::read_ext::<Self>(r, ffi::MP_DATETIME)?;
+        Self::from_ext_data(data).map_err(crate::msgpack::DecodeError::new::<Self>)
+    }
+}
+
+impl Datetime {
+    /// Decode a `Datetime` from the payload of the `MP_DATETIME` msgpack
+    /// extension.
+    fn from_ext_data(data: &[u8]) -> Result<Self, String> {
         if data.len() != 8 && data.len() != 16 {
-            return Err(serde::de::Error::custom(format!(
+            return Err(format!(
                 "Unexpected number of bytes for Datetime: expected 8 or 16, got {}",
                 data.len()
+            ));
+        }
+
+        Self::from_bytes_tt(data).map_err(|_| "Error decoding msgpack bytes".into())
+    }
+}
+
+/// Returns the payload of the `MP_DATETIME` msgpack extension given the result
+/// of [`Datetime::as_bytes_tt`]. The trailing fields are omitted if they're
+/// all zero, same as tarantool does.
+#[inline(always)]
+fn ext_data(bytes: &[u8; 16]) -> &[u8] {
+    if bytes[8..] == [0, 0, 0, 0, 0, 0, 0, 0] {
+        &bytes[..8]
+    } else {
+        bytes
+    }
+}
+
+impl serde::Serialize for Interval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct _ExtStruct((i8, serde_bytes::ByteBuf));
+
+        let data = self.to_ext_data();
+        _ExtStruct((ffi::MP_INTERVAL, serde_bytes::ByteBuf::from(data))).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Interval {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct _ExtStruct((i8, serde_bytes::ByteBuf));
+
+        let _ExtStruct((kind, bytes)) = serde::Deserialize::deserialize(deserializer)?;
+
+        if kind != ffi::MP_INTERVAL {
+            return Err(serde::de::Error::custom(format!(
+                "Expected Interval, found msgpack ext #{}",
+                kind
             )));
         }
 
-        Self::from_bytes_tt(data)
-            .map_err(|_| serde::de::Error::custom("Error decoding msgpack bytes"))
+        Self::from_ext_data(bytes.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl crate::msgpack::Encode for Interval {
+    #[inline]
+    fn encode(
+        &self,
+        w: &mut impl std::io::Write,
+        _context: &crate::msgpack::Context,
+    ) -> Result<(), crate::msgpack::EncodeError> {
+        crate::msgpack::write_ext(w, ffi::MP_INTERVAL, &self.to_ext_data())
+    }
+}
+
+impl<'de> crate::msgpack::Decode<'de> for Interval {
+    #[inline]
+    fn decode(
+        r: &mut &'de [u8],
+        _context: &crate::msgpack::Context,
+    ) -> Result<Self, crate::msgpack::DecodeError> {
+        let data = crate::msgpack::read_ext::<Self>(r, ffi::MP_INTERVAL)?;
+        Self::from_ext_data(data).map_err(crate::msgpack::DecodeError::new::<Self>)
     }
 }
 
@@ -236,6 +865,64 @@ impl<L: tlua::AsLua> tlua::PushInto<L> for Datetime {
 
 impl<L: tlua::AsLua> tlua::PushOneInto<L> for Datetime {}
 
+static CTID_INTERVAL: Lazy<u32> = Lazy::new(|| {
+    if !crate::ffi::has_datetime() {
+        panic!("datetime is not supported in current tarantool version")
+    }
+    use tlua::AsLua;
+    let lua = crate::global_lua();
+    unsafe { tlua::ffi::luaL_ctypeid(lua.as_lua(), crate::c_ptr!("struct interval")) }
+});
+
+unsafe impl tlua::AsCData for ffi::interval {
+    fn ctypeid() -> tlua::ffi::CTypeID {
+        *CTID_INTERVAL
+    }
+}
+
+impl<L> tlua::LuaRead<L> for Interval
+where
+    L: tlua::AsLua,
+{
+    fn lua_read_at_position(lua: L, index: std::num::NonZeroI32) -> tlua::ReadResult<Self, L> {
+        let res = tlua::LuaRead::lua_read_at_position(&lua, index);
+        let tlua::CData(interval) = crate::unwrap_ok_or!(res,
+            Err((_, e)) => {
+                return Err((lua, e));
+            }
+        );
+        match Self::from_ffi_interval(interval) {
+            Ok(v) => Ok(v),
+            Err(err) => {
+                let e = tlua::WrongType::info("reading tarantool datetime interval")
+                    .expected_type::<Self>()
+                    .actual(format!("interval failing to convert: {}", err));
+                Err((lua, e))
+            }
+        }
+    }
+}
+
+impl<L: tlua::AsLua> tlua::Push<L> for Interval {
+    type Err = tlua::Void;
+
+    fn push_to_lua(&self, lua: L) -> Result<tlua::PushGuard<L>, (Self::Err, L)> {
+        Ok(lua.push_one(tlua::CData(self.as_ffi_interval())))
+    }
+}
+
+impl<L: tlua::AsLua> tlua::PushOne<L> for Interval {}
+
+impl<L: tlua::AsLua> tlua::PushInto<L> for Interval {
+    type Err = tlua::Void;
+
+    fn push_into_lua(self, lua: L) -> Result<tlua::PushGuard<L>, (Self::Err, L)> {
+        Ok(lua.push_one(tlua::CData(self.as_ffi_interval())))
+    }
+}
+
+impl<L: tlua::AsLua> tlua::PushOneInto<L> for Interval {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +953,158 @@ mod tests {
         let expected: Datetime = datetime!(2023-11-11 0:00:0.0000 -0).into();
         assert_eq!(only_date, expected);
     }
+
+    #[test]
+    fn tzindex() {
+        let datetime = Datetime::from(datetime!(2023-11-11 2:03:19 +3)).with_tzindex(947);
+        let data = crate::msgpack::encode(&datetime);
+        let expected = b"\xd8\x04\xb7\xb6\x4e\x65\x00\x00\x00\x00\x00\x00\x00\x00\xb4\x00\xb3\x03";
+        assert_eq!(data, expected);
+        assert_eq!(rmp_serde::to_vec(&datetime).unwrap(), expected);
+
+        let decoded: Datetime = crate::msgpack::decode(expected).unwrap();
+        assert_eq!(decoded.tzindex(), 947);
+        let decoded: Datetime = rmp_serde::from_slice(expected).unwrap();
+        assert_eq!(decoded.tzindex(), 947);
+        assert_eq!(decoded.as_ffi_dt().tzindex, 947);
+        assert_eq!(decoded.into_inner(), datetime!(2023-11-11 2:03:19 +3));
+
+        // The timezone doesn't affect comparison
+        assert_eq!(decoded, decoded.with_tzindex(0));
+    }
+
+    #[test]
+    fn interval_encoding() {
+        let interval = Interval {
+            year: 1,
+            month: -1,
+            adjust: Adjust::Excess,
+            ..Default::default()
+        };
+        let data = rmp_serde::to_vec(&interval).unwrap();
+        let expected = b"\xc7\x07\x06\x03\x00\x01\x01\xff\x08\x00";
+        assert_eq!(data, expected);
+        assert_eq!(crate::msgpack::encode(&interval), expected);
+        assert_eq!(
+            rmp_serde::from_slice::<Interval>(expected).unwrap(),
+            interval
+        );
+        assert_eq!(
+            crate::msgpack::decode::<Interval>(expected).unwrap(),
+            interval
+        );
+
+        let interval = Interval {
+            day: 300,
+            nsec: -1,
+            ..Default::default()
+        };
+        let data = crate::msgpack::encode(&interval);
+        let expected = b"\xc7\x07\x06\x02\x03\xcd\x01\x2c\x07\xff";
+        assert_eq!(data, expected);
+        assert_eq!(crate::msgpack::decode::<Interval>(&data).unwrap(), interval);
+
+        let empty = crate::msgpack::encode(&Interval::default());
+        assert_eq!(empty, b"\xd4\x06\x00");
+        assert_eq!(
+            crate::msgpack::decode::<Interval>(&empty).unwrap(),
+            Interval::default()
+        );
+    }
+
+    #[test]
+    fn add_months() {
+        let months = |month, adjust| Interval {
+            month,
+            adjust,
+            ..Default::default()
+        };
+        let dt = |dt: Inner| Datetime::from(dt);
+
+        let jan_31 = dt(datetime!(2024-01-31 12:30 +3));
+        #[rustfmt::skip]
+        {
+            assert_eq!(jan_31 + months(1, Adjust::None), dt(datetime!(2024-02-29 12:30 +3)));
+            assert_eq!(jan_31 + months(1, Adjust::Last), dt(datetime!(2024-02-29 12:30 +3)));
+            assert_eq!(jan_31 + months(1, Adjust::Excess), dt(datetime!(2024-03-02 12:30 +3)));
+            assert_eq!(jan_31 - months(2, Adjust::None), dt(datetime!(2023-11-30 12:30 +3)));
+            assert_eq!(jan_31 + months(13, Adjust::None), dt(datetime!(2025-02-28 12:30 +3)));
+        };
+
+        let feb_29 = dt(datetime!(2024-02-29 0:00 UTC));
+        #[rustfmt::skip]
+        {
+            assert_eq!(feb_29 + months(1, Adjust::None), dt(datetime!(2024-03-29 0:00 UTC)));
+            assert_eq!(feb_29 + months(1, Adjust::Last), dt(datetime!(2024-03-31 0:00 UTC)));
+            assert_eq!(feb_29 + months(1, Adjust::Excess), dt(datetime!(2024-03-29 0:00 UTC)));
+            assert_eq!(feb_29 - months(1, Adjust::Last), dt(datetime!(2024-01-31 0:00 UTC)));
+        };
+
+        let year = |adjust| Interval {
+            year: 1,
+            adjust,
+            ..Default::default()
+        };
+        assert_eq!(
+            feb_29 + year(Adjust::None),
+            dt(datetime!(2025-02-28 0:00 UTC))
+        );
+        assert_eq!(
+            feb_29 + year(Adjust::Excess),
+            dt(datetime!(2025-03-01 0:00 UTC))
+        );
+    }
+
+    #[test]
+    fn add_time() {
+        let dt: Datetime = datetime!(2024-03-30 23:59:59.5 +1).into();
+        let interval = Interval {
+            week: 1,
+            day: 1,
+            hour: 1,
+            min: 1,
+            sec: 1,
+            nsec: 600_000_000,
+            ..Default::default()
+        };
+        let expected: Datetime = datetime!(2024-04-08 01:01:01.1 +1).into();
+        assert_eq!(dt + interval, expected);
+        assert_eq!(expected - interval, dt);
+
+        let max: Datetime = datetime!(9999-12-31 0:00 UTC).into();
+        let day = Interval {
+            day: 1,
+            ..Default::default()
+        };
+        assert_eq!(max.checked_add(day), None);
+        let huge = Interval {
+            hour: i64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(max.checked_sub(huge), None);
+    }
+
+    #[test]
+    fn sub_datetimes() {
+        let lhs: Datetime = datetime!(2024-03-01 10:00:00.25 +3).into();
+        let rhs: Datetime = datetime!(2024-02-28 12:30:00.5 UTC).into();
+        let diff = lhs - rhs;
+        assert_eq!(
+            diff,
+            Interval {
+                day: 1,
+                hour: 18,
+                min: 29,
+                sec: 59,
+                nsec: 750_000_000,
+                ..Default::default()
+            }
+        );
+        assert_eq!(rhs + diff, lhs);
+
+        let diff = rhs - lhs;
+        assert_eq!(lhs + diff, rhs);
+    }
 }
 
 #[cfg(feature = "internal_test")]
@@ -432,6 +432,33 @@ mod tarantool_decimal {
     impl_try_into_int! {i64 isize => ffi::decimal_to_int64}
     impl_try_into_int! {u64 usize => ffi::decimal_to_uint64}
 
+    impl Decimal {
+        /// Return the payload of the `MP_DECIMAL` msgpack extension
+        /// representing `self`.
+        pub(crate) fn to_ext_data(self) -> Vec<u8> {
+            unsafe {
+                let len = ffi::decimal_len(&self.inner) as usize;
+                let mut data = Vec::<u8>::with_capacity(len);
+                ffi::decimal_pack(data.as_mut_ptr() as _, &self.inner);
+                data.set_len(len);
+                data
+            }
+        }
+
+        /// Decode a decimal from the payload of the `MP_DECIMAL` msgpack
+        /// extension.
+        pub(crate) fn from_ext_data(data: &[u8]) -> Result<Self, String> {
+            let data_p = &mut data.as_ptr().cast();
+            let mut dec = std::mem::MaybeUninit::uninit();
+            let res = unsafe { ffi::decimal_unpack(data_p, data.len() as _, dec.as_mut_ptr()) };
+            if res.is_null() {
+                Err("Decimal out of range or corrupt".into())
+            } else {
+                unsafe { Ok(Self::from_raw(dec.assume_init())) }
+            }
+        }
+    }
+
     impl serde::Serialize for Decimal {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -440,13 +467,7 @@ mod tarantool_decimal {
             #[derive(Serialize)]
             struct _ExtStruct((i8, serde_bytes::ByteBuf));
 
-            let data = unsafe {
-                let len = ffi::decimal_len(&self.inner) as usize;
-                let mut data = Vec::<u8>::with_capacity(len);
-                ffi::decimal_pack(data.as_mut_ptr() as _, &self.inner);
-                data.set_len(len);
-                data
-            };
+            let data = self.to_ext_data();
             _ExtStruct((ffi::MP_DECIMAL, serde_bytes::ByteBuf::from(data))).serialize(serializer)
         }
     }
@@ -461,16 +482,7 @@ mod tarantool_decimal {
 
             match serde::Deserialize::deserialize(deserializer)? {
                 _ExtStruct((ffi::MP_DECIMAL, bytes)) => {
-                    let data = bytes.into_vec();
-                    let data_p = &mut data.as_ptr().cast();
-                    let mut dec = std::mem::MaybeUninit::uninit();
-                    let res =
-                        unsafe { ffi::decimal_unpack(data_p, data.len() as _, dec.as_mut_ptr()) };
-                    if res.is_null() {
-                        Err(serde::de::Error::custom("Decimal out of range or corrupt"))
-                    } else {
-                        unsafe { Ok(Self::from_raw(dec.assume_init())) }
-                    }
+                    Self::from_ext_data(&bytes).map_err(serde::de::Error::custom)
                 }
                 _ExtStruct((kind, _)) => Err(serde::de::Error::custom(format!(
                     "Expected Decimal, found msgpack ext #{}",
@@ -1136,6 +1148,30 @@ mod standalone_decimal {
         usize => try_into_usize
     }
 
+    impl Decimal {
+        /// Return the payload of the `MP_DECIMAL` msgpack extension
+        /// representing `self`.
+        pub(crate) fn to_ext_data(self) -> Vec<u8> {
+            let mut data = vec![];
+            let (bcd, scale) = self.inner.clone().to_packed_bcd().unwrap();
+            rmp::encode::write_sint(&mut data, scale as i64).unwrap();
+            data.extend(bcd);
+            data
+        }
+
+        /// Decode a decimal from the payload of the `MP_DECIMAL` msgpack
+        /// extension.
+        pub(crate) fn from_ext_data(mut data: &[u8]) -> Result<Self, String> {
+            let scale = rmp::decode::read_int(&mut data)
+                .map_err(|e| format!("Failed to unpack decimal: {e}"))?;
+            let bcd = data;
+            DecimalImpl::from_packed_bcd(bcd, scale)
+                .map_err(|e| format!("Failed to unpack decimal: {e}"))?
+                .try_into()
+                .map_err(|e| format!("Failed to unpack decimal: {e}"))
+        }
+    }
+
     impl serde::Serialize for Decimal {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
@@ -1144,13 +1180,7 @@ mod standalone_decimal {
             #[derive(serde::Serialize)]
             struct _ExtStruct((i8, serde_bytes::ByteBuf));
 
-            let data = {
-                let mut data = vec![];
-                let (bcd, scale) = self.inner.clone().to_packed_bcd().unwrap();
-                rmp::encode::write_sint(&mut data, scale as i64).unwrap();
-                data.extend(bcd);
-                data
-            };
+            let data = self.to_ext_data();
             _ExtStruct((ffi::MP_DECIMAL, serde_bytes::ByteBuf::from(data))).serialize(serializer)
         }
     }
@@ -1160,19 +1190,12 @@ mod standalone_decimal {
         where
             D: serde::Deserializer<'de>,
         {
-            use serde::de::Error;
             #[derive(serde::Deserialize)]
             struct _ExtStruct((i8, serde_bytes::ByteBuf));
 
             match serde::Deserialize::deserialize(deserializer)? {
                 _ExtStruct((ffi::MP_DECIMAL, bytes)) => {
-                    let mut data = bytes.as_slice();
-                    let scale = rmp::decode::read_int(&mut data).unwrap();
-                    let bcd = data;
-                    DecimalImpl::from_packed_bcd(bcd, scale)
-                        .map_err(|e| Error::custom(format!("Failed to unpack decimal: {e}")))?
-                        .try_into()
-                        .map_err(|e| Error::custom(format!("Failed to unpack decimal: {e}")))
+                    Self::from_ext_data(&bytes).map_err(serde::de::Error::custom)
                 }
                 _ExtStruct((kind, _)) => Err(serde::de::Error::custom(format!(
                     "Expected Decimal, found msgpack ext #{}",
@@ -1254,6 +1277,32 @@ macro_rules! impl_cmp_int {
 
 impl_cmp_int! {i8 i16 i32 i64 isize u8 u16 u32 u64 usize}
 
+////////////////////////////////////////////////////////////////////////////////
+/// Msgpack
+////////////////////////////////////////////////////////////////////////////////
+
+impl crate::msgpack::Encode for Decimal {
+    #[inline]
+    fn encode(
+        &self,
+        w: &mut impl std::io::Write,
+        _context: &crate::msgpack::Context,
+    ) -> Result<(), crate::msgpack::EncodeError> {
+        crate::msgpack::write_ext(w, ffi::MP_DECIMAL, &self.to_ext_data())
+    }
+}
+
+impl<'de> crate::msgpack::Decode<'de> for Decimal {
+    #[inline]
+    fn decode(
+        r: &mut &'de [u8],
+        _context: &crate::msgpack::Context,
+    ) -> Result<Self, crate::msgpack::DecodeError> {
+        let data = crate::msgpack::read_ext::<Self>(r, ffi::MP_DECIMAL)?;
+        Self::from_ext_data(data).map_err(crate::msgpack::DecodeError::new::<Self>)
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 /// Lua
 ////////////////////////////////////////////////////////////////////////////////
@@ -1411,6 +1460,31 @@ mod tests {
         assert_eq!(d.to_string(), "-8.11");
     }
 
+    #[crate::test(tarantool = "crate")]
+    pub fn msgpack_round_trip() {
+        #[derive(Debug, PartialEq, crate::msgpack::Encode, crate::msgpack::Decode)]
+        #[encode(tarantool = "crate")]
+        struct Record {
+            id: u32,
+            amount: Decimal,
+        }
+
+        let record = Record {
+            id: 1,
+            amount: decimal!(-8.11),
+        };
+        let data = crate::msgpack::encode(&record);
+
+        let t: Tuple = crate::lua_state()
+            .eval("return box.tuple.new(1, require('decimal').new('-8.11'))")
+            .unwrap();
+        assert_eq!(data, t.to_vec());
+        assert_eq!(crate::msgpack::decode::<Record>(&data).unwrap(), record);
+
+        let (_, amount): (u32, Decimal) = Tuple::try_from_slice(&data).unwrap().decode().unwrap();
+        assert_eq!(amount, record.amount);
+    }
+
     #[crate::test(tarantool = "crate")]
     fn from_string() {
         let d: Decimal = "-81.1e-1".parse().unwrap();
@@ -196,6 +196,8 @@ where
         match e {
             TransactionError::FailedToCommit(e) => e.into(),
             TransactionError::FailedToRollback(e) => e.into(),
+            TransactionError::FailedToCreateSavepoint(e) => e.into(),
+            TransactionError::FailedToBegin(e) => e.into(),
             TransactionError::RolledBack(e) => e.into(),
             TransactionError::AlreadyStarted => BoxError::new(
                 TarantoolErrorCode::ActiveTransaction,
@@ -237,7 +239,7 @@ impl From<std::string::FromUtf8Error> for Error {
 ///
 /// Can also be used in user code to return structured error info from stored
 /// procedures.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct BoxError {
     pub(crate) code: u32,
     pub(crate) message: Option<Box<str>>,
@@ -291,6 +293,28 @@ impl BoxError {
         }
     }
 
+    /// Set the error type, e.g. "ClientError" or a custom error type name.
+    #[inline(always)]
+    pub fn with_error_type(mut self, error_type: impl Into<String>) -> Self {
+        self.error_type = Some(error_type.into().into_boxed_str());
+        self
+    }
+
+    /// Set the error which caused this one.
+    #[inline(always)]
+    pub fn with_cause(mut self, cause: BoxError) -> Self {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+
+    /// Add a custom field to the error's map of additional fields.
+    #[inline(always)]
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<rmpv::Value>) -> Self {
+        self.fields
+            .insert(key.into().into_boxed_str(), value.into());
+        self
+    }
+
     /// Tries to get the information about the last API call error. If error was not set
     /// returns `Ok(())`
     #[inline]
@@ -433,6 +457,89 @@ impl From<BoxError> for Error {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// BoxError msgpack
+////////////////////////////////////////////////////////////////////////////////
+
+impl BoxError {
+    /// Returns the payload of the `MP_ERROR` msgpack extension, i.e. the
+    /// error stack consisting of `self` and all of its causes.
+    fn to_ext_data(&self) -> std::result::Result<Vec<u8>, String> {
+        let mut data = Vec::with_capacity(128);
+        crate::network::protocol::codec::encode_extended_error(&mut data, self)
+            .map_err(|e| e.to_string())?;
+        Ok(data)
+    }
+
+    /// Constructs an error from the payload of the `MP_ERROR` msgpack
+    /// extension. The first entry of the error stack becomes the resulting
+    /// error and the rest become its [`cause`](Self::cause) chain.
+    fn from_ext_data(mut data: &[u8]) -> std::result::Result<Self, String> {
+        crate::network::protocol::codec::decode_extended_error(&mut data)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "error stack is empty".into())
+    }
+}
+
+impl serde::Serialize for BoxError {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(serde::Serialize)]
+        struct _ExtStruct((i8, serde_bytes::ByteBuf));
+
+        let data = self.to_ext_data().map_err(serde::ser::Error::custom)?;
+        _ExtStruct((ffi::MP_ERROR, serde_bytes::ByteBuf::from(data))).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BoxError {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct _ExtStruct((i8, serde_bytes::ByteBuf));
+
+        let _ExtStruct((kind, bytes)) = serde::Deserialize::deserialize(deserializer)?;
+
+        if kind != ffi::MP_ERROR {
+            return Err(serde::de::Error::custom(format!(
+                "Expected Error, found msgpack ext #{}",
+                kind
+            )));
+        }
+
+        Self::from_ext_data(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+impl crate::msgpack::Encode for BoxError {
+    #[inline]
+    fn encode(
+        &self,
+        w: &mut impl std::io::Write,
+        _context: &crate::msgpack::Context,
+    ) -> std::result::Result<(), crate::msgpack::EncodeError> {
+        let data = self
+            .to_ext_data()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        crate::msgpack::write_ext(w, ffi::MP_ERROR, &data)
+    }
+}
+
+impl<'de> crate::msgpack::Decode<'de> for BoxError {
+    #[inline]
+    fn decode(
+        r: &mut &'de [u8],
+        _context: &crate::msgpack::Context,
+    ) -> std::result::Result<Self, crate::msgpack::DecodeError> {
+        let data = crate::msgpack::read_ext::<Self>(r, ffi::MP_ERROR)?;
+        Self::from_ext_data(data).map_err(crate::msgpack::DecodeError::new::<Self>)
+    }
+}
+
 /// # Safety
 /// Only safe to be called from `tx` thread. Also `ptr` must point at a valid
 /// instance of `ffi::BoxError`.
@@ -1187,4 +1294,59 @@ mod tests {
         // This used to crash before the fix
         assert_eq!(e.error_type(), "ClientError");
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn box_error_msgpack_round_trip() {
+        let e = BoxError::with_location(69105_u32, "too many leaves", "tree.rs", 13)
+            .with_error_type("TreeError")
+            .with_field("leaves", 420)
+            .with_field("tree", "oak")
+            .with_cause(
+                BoxError::with_location(TarantoolErrorCode::Unknown, "wind", "air.rs", 37)
+                    .with_error_type("ClientError"),
+            );
+
+        let data = crate::msgpack::encode(&e);
+        assert_eq!(data[0], 0xc7); // ext 8
+        assert_eq!(data[2] as i8, ffi::MP_ERROR);
+        assert_eq!(crate::msgpack::decode::<BoxError>(&data).unwrap(), e);
+
+        let data = rmp_serde::to_vec(&e).unwrap();
+        assert_eq!(rmp_serde::from_slice::<BoxError>(&data).unwrap(), e);
+
+        let tuple = crate::tuple::Tuple::new(&(1, &e)).unwrap();
+        let (_, decoded): (u32, BoxError) = tuple.decode().unwrap();
+        assert_eq!(decoded, e);
+        assert_eq!(decoded.cause().unwrap().message(), "wind");
+        assert_eq!(decoded.fields()["tree"], rmpv::Value::from("oak"));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn box_error_decode_from_lua() {
+        let tlua::AnyLuaString(data) = crate::lua_state()
+            .eval(
+                "local e = box.error.new({ code = 69105, reason = 'too many leaves' })
+                e:set_prev(box.error.new({ type = 'MyError', reason = 'wind' }))
+                return require('msgpack').encode(e)",
+            )
+            .unwrap();
+        let e: BoxError = crate::msgpack::decode(&data).unwrap();
+        assert_eq!(e.error_code(), 69105);
+        assert_eq!(e.message(), "too many leaves");
+        assert_eq!(e.error_type(), "ClientError");
+        let cause = e.cause().unwrap();
+        assert_eq!(cause.message(), "wind");
+        assert!(cause.cause().is_none());
+
+        let (code, message, prev): (u32, String, String) = crate::lua_state()
+            .eval_with(
+                "local e = require('msgpack').decode(...)
+                return e.code, e.message, e.prev.message",
+                tlua::AnyLuaString(crate::msgpack::encode(&e)),
+            )
+            .unwrap();
+        assert_eq!(code, 69105);
+        assert_eq!(message, "too many leaves");
+        assert_eq!(prev, "wind");
+    }
 }
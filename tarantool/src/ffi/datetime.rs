@@ -1,4 +1,5 @@
 pub const MP_DATETIME: i8 = 4;
+pub const MP_INTERVAL: i8 = 6;
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -10,6 +11,26 @@ pub struct datetime {
     pub tzindex: i16,
 }
 
+/// Adjustment mode for day in month operations, see `enum dt_adjust_t`.
+pub const DT_EXCESS: i32 = 0;
+pub const DT_LIMIT: i32 = 1;
+pub const DT_SNAP: i32 = 2;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
+pub struct interval {
+    pub sec: f64,
+    pub min: f64,
+    pub hour: f64,
+    pub day: f64,
+    pub month: i32,
+    pub year: i32,
+    pub week: i32,
+    pub nsec: i32,
+    pub adjust: i32,
+}
+
 crate::define_dlsym_reloc! {
     /// Returns the number of bytes required to store a msgpack encoding for `date`.
     pub fn tnt_mp_sizeof_datetime(date: *const datetime) -> u32;
@@ -20,6 +41,18 @@ crate::define_dlsym_reloc! {
     ///
     /// Returns a pointer to the first byte after the encoded data.
     pub fn tnt_mp_encode_datetime(data: *mut u8, date: *const datetime) -> *mut u8;
+
+    /// Adds (if `direction` is `1`) or subtracts (if `direction` is `-1`)
+    /// `ival` to `self` in place. The offset of a datetime with a named
+    /// timezone is adjusted according to the timezone's rules.
+    ///
+    /// Returns `0` on success or a non-zero value if the result is out of the
+    /// supported range.
+    pub fn datetime_increment_by(
+        self_: *mut datetime,
+        direction: std::os::raw::c_int,
+        ival: *const interval,
+    ) -> std::os::raw::c_int;
 }
 
 #[cfg(feature = "internal_test")]
@@ -60,4 +93,50 @@ mod test {
         assert_eq!(offset_of_tzoffset, offset_of!(datetime, tzoffset));
         assert_eq!(offset_of_tzindex, offset_of!(datetime, tzindex));
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn interval_ffi_definition() {
+        if !crate::ffi::has_datetime() {
+            return;
+        }
+
+        let lua = crate::lua_state();
+        let [
+            size_of_interval,
+            offset_of_sec,
+            offset_of_min,
+            offset_of_hour,
+            offset_of_day,
+            offset_of_month,
+            offset_of_year,
+            offset_of_week,
+            offset_of_nsec,
+            offset_of_adjust,
+        ]: [usize; 10] = lua.eval(
+            "local ffi = require 'ffi'
+            return {
+                ffi.sizeof('struct interval'),
+                ffi.offsetof('struct interval', 'sec'),
+                ffi.offsetof('struct interval', 'min'),
+                ffi.offsetof('struct interval', 'hour'),
+                ffi.offsetof('struct interval', 'day'),
+                ffi.offsetof('struct interval', 'month'),
+                ffi.offsetof('struct interval', 'year'),
+                ffi.offsetof('struct interval', 'week'),
+                ffi.offsetof('struct interval', 'nsec'),
+                ffi.offsetof('struct interval', 'adjust'),
+            }",
+        ).unwrap();
+
+        assert_eq!(size_of_interval, std::mem::size_of::<interval>());
+        assert_eq!(offset_of_sec, offset_of!(interval, sec));
+        assert_eq!(offset_of_min, offset_of!(interval, min));
+        assert_eq!(offset_of_hour, offset_of!(interval, hour));
+        assert_eq!(offset_of_day, offset_of!(interval, day));
+        assert_eq!(offset_of_month, offset_of!(interval, month));
+        assert_eq!(offset_of_year, offset_of!(interval, year));
+        assert_eq!(offset_of_week, offset_of!(interval, week));
+        assert_eq!(offset_of_nsec, offset_of!(interval, nsec));
+        assert_eq!(offset_of_adjust, offset_of!(interval, adjust));
+    }
 }
@@ -119,3 +119,38 @@ pub unsafe fn has_fiber_id() -> bool {
     }
     RESULT.unwrap()
 }
+
+/// Check whether the current tarantool executable supports the session ffi
+/// apis: [`box_session_id`], [`box_session_user_id`],
+/// [`box_effective_user_id`] and [`box_session_su`]. Always returns `true`
+/// with the `picodata` feature.
+///
+/// If this function returns `false` then the corresponding apis (e.g. getting
+/// the session id) will use the less efficient implementation based on the
+/// lua interface.
+///
+/// # Safety
+/// This function is only safe to be called from the tx thread.
+///
+/// [`box_session_id`]: crate::ffi::tarantool::box_session_id
+/// [`box_session_user_id`]: crate::ffi::tarantool::box_session_user_id
+/// [`box_effective_user_id`]: crate::ffi::tarantool::box_effective_user_id
+/// [`box_session_su`]: crate::ffi::tarantool::box_session_su
+#[inline]
+pub unsafe fn has_session_api() -> bool {
+    if cfg!(feature = "picodata") {
+        return true;
+    }
+
+    static mut RESULT: Option<bool> = None;
+    if RESULT.is_none() {
+        let symbols = [
+            crate::c_str!("box_session_id"),
+            crate::c_str!("box_session_user_id"),
+            crate::c_str!("box_effective_user_id"),
+            crate::c_str!("box_session_su"),
+        ];
+        RESULT = Some(symbols.iter().all(|s| helper::has_dyn_symbol(s)));
+    }
+    RESULT.unwrap()
+}
@@ -645,6 +645,9 @@ extern "C" {
 }
 
 // Error.
+/// Msgpack extension type id of an error (`MP_ERROR`).
+pub const MP_ERROR: i8 = 3;
+
 #[repr(C)]
 pub struct BoxError {
     _unused: [u8; 0],
@@ -745,12 +748,22 @@ extern "C" {
 }
 
 // Transaction.
+#[repr(C)]
+pub struct BoxTxnSavepoint {
+    _unused: [u8; 0],
+}
+
 extern "C" {
     pub fn box_txn() -> bool;
+    pub fn box_txn_id() -> i64;
     pub fn box_txn_begin() -> c_int;
     pub fn box_txn_commit() -> c_int;
     pub fn box_txn_rollback() -> c_int;
     pub fn box_txn_alloc(size: usize) -> *mut c_void;
+    pub fn box_txn_set_timeout(timeout: f64) -> c_int;
+    pub fn box_txn_set_isolation(level: u32) -> c_int;
+    pub fn box_txn_savepoint() -> *mut BoxTxnSavepoint;
+    pub fn box_txn_rollback_to_savepoint(savepoint: *mut BoxTxnSavepoint) -> c_int;
 }
 
 // Indexes, spaces and tuples.
@@ -1354,6 +1367,18 @@ extern "C" {
 }
 
 // Session.
+#[cfg(not(feature = "picodata"))]
+crate::define_dlsym_reloc! {
+    /// Returns the unique identifier (ID) for the current session.
+    ///
+    /// Not available in older versions of vanilla tarantool, check
+    /// [`crate::ffi::has_session_api`] before calling.
+    pub fn box_session_id() -> u64;
+    pub fn box_session_user_id(uid: *mut u32) -> c_int;
+    pub fn box_effective_user_id() -> u32;
+    pub fn box_session_su(uid: u32) -> c_int;
+}
+
 #[cfg(feature = "picodata")]
 extern "C" {
     /// Returns the unique identifier (ID) for the current session.
@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use crate::error::{Error, TarantoolError, TarantoolErrorCode};
 use crate::ffi::tarantool as ffi;
 use crate::msgpack;
+use crate::schema::function::{FunctionCreateOptions, FunctionLanguage};
 use crate::space::{Space, SpaceId, SystemSpace};
 use crate::tuple::{Encode, ToTupleBuffer, Tuple, TupleBuffer};
 use crate::tuple::{KeyDef, KeyDefPart};
@@ -101,6 +102,7 @@ pub struct Builder<'a> {
     space_id: SpaceId,
     name: &'a str,
     opts: IndexOptions,
+    func: Option<FunctionCreateOptions>,
 }
 
 macro_rules! define_setters {
@@ -123,6 +125,7 @@ impl<'a> Builder<'a> {
             space_id,
             name,
             opts: IndexOptions::default(),
+            func: None,
         }
     }
 
@@ -188,14 +191,120 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Make this a functional index. The keys of the index are computed by
+    /// the persistent function `func_name` with the given `body`, which is
+    /// created together with the index. The function must return a table with
+    /// a key, i.e. the values of the index [`parts`], which are numbered
+    /// from 1.
+    ///
+    /// Use [`func`] instead to reference an existing function.
+    ///
+    /// ```no_run
+    /// use tarantool::{index::FieldType as FT, space::Space};
+    /// use tarantool::schema::function::FunctionLanguage;
+    ///
+    /// Space::find("users").unwrap()
+    ///     .index_builder("by_lower_name")
+    ///     .function(
+    ///         "users_lower_name",
+    ///         FunctionLanguage::Lua,
+    ///         "function(tuple) return {tuple[2]:lower()} end",
+    ///     )
+    ///     .part((1, FT::String))
+    ///     .create();
+    /// ```
+    ///
+    /// [`parts`]: Self::parts
+    /// [`func`]: Self::func
+    #[inline]
+    pub fn function(
+        self,
+        func_name: impl Into<String>,
+        language: FunctionLanguage,
+        body: impl Into<String>,
+    ) -> Self {
+        self.function_impl(func_name.into(), language, body.into(), false)
+    }
+
+    /// Same as [`function`] but the function must return an array of keys,
+    /// each of which is added to the index.
+    ///
+    /// [`function`]: Self::function
+    #[inline]
+    pub fn multikey_function(
+        self,
+        func_name: impl Into<String>,
+        language: FunctionLanguage,
+        body: impl Into<String>,
+    ) -> Self {
+        self.function_impl(func_name.into(), language, body.into(), true)
+    }
+
+    fn function_impl(
+        mut self,
+        func_name: String,
+        language: FunctionLanguage,
+        body: String,
+        is_multikey: bool,
+    ) -> Self {
+        self.func = Some(FunctionCreateOptions {
+            language,
+            body: Some(body),
+            is_deterministic: true,
+            is_sandboxed: true,
+            is_multikey,
+            ..Default::default()
+        });
+        self.opts.func = Some(func_name);
+        self
+    }
+
     /// Create a new index using the current options.
-    #[inline(always)]
+    ///
+    /// The options are validated before any changes are made to the schema.
+    /// If the index is [functional], the function is created first and is
+    /// dropped if creating the index fails.
+    ///
+    /// [functional]: Self::function
+    #[inline]
     pub fn create(self) -> crate::Result<Index> {
-        crate::schema::index::create_index(self.space_id, self.name, &self.opts)
+        let Some(func) = self.func else {
+            return crate::schema::index::create_index(self.space_id, self.name, &self.opts);
+        };
+        let func_name = self
+            .opts
+            .func
+            .as_deref()
+            .expect("set together with self.func");
+
+        if self.opts.if_not_exists == Some(true) {
+            let name_idx = SystemSpace::VIndex.as_space().index("name").unwrap();
+            if let Some(t) = name_idx.get(&(self.space_id, self.name))? {
+                return Ok(Index::new(self.space_id, t.field(1)?.unwrap()));
+            }
+        }
+        crate::schema::index::check_index_options(
+            self.space_id,
+            self.name,
+            &self.opts,
+            Some(&func),
+        )?;
+
+        let func_id = crate::schema::function::create_function(func_name, &func)?;
+        let res = crate::schema::index::create_index(self.space_id, self.name, &self.opts);
+        if res.is_err() {
+            // The original error is more important.
+            let _ = crate::schema::function::drop_function(func_id);
+        }
+        res
     }
 
     /// Destructure the builder struct into a tuple of space_id, name and index
     /// options.
+    ///
+    /// Note that the definition of the function set by [`function`] is lost.
+    ///
+    /// [`function`]: Self::function
     #[inline(always)]
     pub fn into_parts(self) -> (u32, &'a str, IndexOptions) {
         (self.space_id, self.name, self.opts)
@@ -229,6 +338,46 @@ pub struct IndexOptions {
     // pub hint: Option<bool>,
 }
 
+/// List of options for [`Index::alter`]. Options which are `None` are not
+/// changed.
+///
+/// For details see [index_object:alter](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_index/alter/).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexAlterOptions {
+    pub name: Option<String>,
+    pub r#type: Option<IndexType>,
+    pub unique: Option<bool>,
+    /// New parts of the index. Same as in [`IndexOptions::parts`] field
+    /// numbers start from 1, and types default to the types of the
+    /// corresponding fields in the space format.
+    pub parts: Option<Vec<Part>>,
+    pub dimension: Option<u32>,
+    pub distance: Option<RtreeIndexDistanceType>,
+    pub bloom_fpr: Option<f32>,
+    pub page_size: Option<u32>,
+    pub range_size: Option<u32>,
+    pub run_count_per_level: Option<u32>,
+    pub run_size_ratio: Option<f32>,
+}
+
+impl From<IndexOptions> for IndexAlterOptions {
+    fn from(opts: IndexOptions) -> Self {
+        Self {
+            name: None,
+            r#type: opts.r#type,
+            unique: opts.unique,
+            parts: opts.parts,
+            dimension: opts.dimension,
+            distance: opts.distance,
+            bloom_fpr: opts.bloom_fpr,
+            page_size: opts.page_size,
+            range_size: opts.range_size,
+            run_count_per_level: opts.run_count_per_level,
+            run_size_ratio: opts.run_size_ratio,
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // SequenceOpt
 ////////////////////////////////////////////////////////////////////////////////
@@ -448,6 +597,47 @@ impl Part {
     pub fn new(fi: impl Into<NumOrStr>, ft: FieldType) -> Self {
         Self::field(fi).field_type(ft)
     }
+
+    /// Make this a multikey part by appending the `[*]` placeholder to the
+    /// part's json path, so that the index has a key for each element of the
+    /// array at that path.
+    ///
+    /// ```no_run
+    /// use tarantool::{index::FieldType as FT, index::Part, space::Space};
+    ///
+    /// // An index over each element of the "tags" array.
+    /// Space::find("posts").unwrap()
+    ///     .index_builder("by_tag")
+    ///     .unique(false)
+    ///     .part(Part::new("tags", FT::String).multikey())
+    ///     .create();
+    ///
+    /// // An index over the "id" field of each map in the "authors" array.
+    /// Space::find("posts").unwrap()
+    ///     .index_builder("by_author")
+    ///     .unique(false)
+    ///     .part(Part::new("authors", FT::Unsigned).multikey().path_append(".id"))
+    ///     .create();
+    /// ```
+    #[inline(always)]
+    pub fn multikey(self) -> Self {
+        self.path_append("[*]")
+    }
+
+    /// Append `path` to the part's json path.
+    #[inline]
+    pub fn path_append(mut self, path: &str) -> Self {
+        self.path.get_or_insert_with(String::new).push_str(path);
+        self
+    }
+
+    /// Returns `true` if the part's json path contains the `[*]` placeholder.
+    #[inline]
+    pub fn is_multikey(&self) -> bool {
+        let in_field = matches!(&self.field, NumOrStr::Str(f) if f.contains("[*]"));
+        let in_path = matches!(&self.path, Some(p) if p.contains("[*]"));
+        in_field || in_path
+    }
 }
 
 impl From<&str> for Part {
@@ -557,6 +747,27 @@ impl Index {
         crate::schema::index::drop_index(self.space_id, self.index_id)
     }
 
+    /// Alter the index.
+    ///
+    /// ```no_run
+    /// use tarantool::index::{FieldType, IndexAlterOptions, Part};
+    /// use tarantool::space::Space;
+    ///
+    /// let space = Space::find("users").unwrap();
+    /// let index = space.index("by_name").unwrap();
+    /// index
+    ///     .alter(&IndexAlterOptions {
+    ///         unique: Some(false),
+    ///         parts: Some(vec![Part::new("name", FieldType::String), Part::field("id")]),
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn alter(&self, opts: &IndexAlterOptions) -> Result<(), Error> {
+        crate::schema::index::alter_index(self.space_id, self.index_id, opts)
+    }
+
     /// Get a tuple from index by the key.
     ///
     /// Please note that this function works much faster than [select](#method.select)
@@ -1185,6 +1396,243 @@ mod tests {
         space.drop().unwrap();
     }
 
+    #[crate::test(tarantool = "crate")]
+    fn index_alter() {
+        let space = Space::builder(&crate::temp_space_name!())
+            .field(("id", space::FieldType::Unsigned))
+            .field(("s", space::FieldType::String))
+            .field(("map", space::FieldType::Map))
+            .create()
+            .unwrap();
+        space.index_builder("pk").create().unwrap();
+        let index = space.index_builder("i").part("s").create().unwrap();
+        let map = BTreeMap::<String, u32>::new();
+        space.insert(&(1, "foo", &map)).unwrap();
+        space.insert(&(2, "foo", &map)).unwrap_err();
+
+        index
+            .alter(&IndexAlterOptions {
+                name: Some("by_s".into()),
+                unique: Some(false),
+                parts: Some(vec![
+                    Part::field("s"),
+                    Part::new("map.key", FieldType::Unsigned).is_nullable(true),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+        space.insert(&(2, "foo", &map)).unwrap();
+        assert_eq!(space.index("by_s").unwrap().id(), index.id());
+        let meta = index.meta().unwrap();
+        assert_eq!(meta.name, "by_s");
+        assert_eq!(
+            meta.opts,
+            BTreeMap::from([("unique".into(), Value::from(false))])
+        );
+        assert_eq!(
+            meta.parts,
+            [
+                Part {
+                    field: 1.into(),
+                    r#type: Some(FieldType::String),
+                    ..Default::default()
+                },
+                Part {
+                    field: 2.into(),
+                    r#type: Some(FieldType::Unsigned),
+                    is_nullable: Some(true),
+                    path: Some(".key".into()),
+                    ..Default::default()
+                },
+            ]
+        );
+
+        // Data doesn't satisfy the unique constraint.
+        let e = index
+            .alter(&IndexAlterOptions {
+                unique: Some(true),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(e.to_string().contains("Duplicate key exists"), "{}", e);
+
+        // Name is already taken.
+        let e = index
+            .alter(&IndexAlterOptions {
+                name: Some("pk".into()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(e.to_string(), "box error: IndexExists: pk");
+
+        // Unknown field.
+        let e = index
+            .alter(&IndexAlterOptions {
+                parts: Some(vec![Part::field("nope")]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(e.to_string().contains("NoSuchFieldNameInSpace"), "{}", e);
+
+        space.drop().unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn multikey_index() {
+        let space = Space::builder(&crate::temp_space_name!())
+            .field(("id", space::FieldType::Unsigned))
+            .field(("tags", space::FieldType::Array))
+            .create()
+            .unwrap();
+
+        let e = space
+            .index_builder("pk")
+            .part(Part::new("tags", FieldType::String).multikey())
+            .create()
+            .unwrap_err();
+        assert!(
+            e.to_string().contains("primary key cannot be multikey"),
+            "{}",
+            e
+        );
+        space.index_builder("pk").part("id").create().unwrap();
+
+        let e = space
+            .index_builder("by_tag")
+            .index_type(IndexType::Hash)
+            .part(Part::new("tags", FieldType::String).multikey())
+            .create()
+            .unwrap_err();
+        assert!(
+            e.to_string().contains("only TREE index can be multikey"),
+            "{}",
+            e
+        );
+
+        let index = space
+            .index_builder("by_tag")
+            .unique(false)
+            .part(Part::new("tags", FieldType::String).multikey())
+            .create()
+            .unwrap();
+        assert_eq!(index.meta().unwrap().parts[0].path.as_deref(), Some("[*]"));
+
+        space.insert(&(1, ["a", "b"])).unwrap();
+        space.insert(&(2, ["b", "c"])).unwrap();
+        let ids: Vec<u32> = index
+            .select(IteratorType::Eq, &("b",))
+            .unwrap()
+            .map(|t| t.field(0).unwrap().unwrap())
+            .collect();
+        assert_eq!(ids, [1, 2]);
+
+        space.drop().unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn functional_index() {
+        use crate::schema::function::{self, FunctionCreateOptions, FunctionLanguage};
+
+        let space = Space::builder(&crate::temp_space_name!())
+            .field(("id", space::FieldType::Unsigned))
+            .field(("name", space::FieldType::String))
+            .create()
+            .unwrap();
+        space.index_builder("pk").create().unwrap();
+        space.insert(&(1, "Foo")).unwrap();
+        space.insert(&(2, "BAR")).unwrap();
+
+        // Existing function which can't be used in an index.
+        let func_name = crate::temp_space_name!();
+        let func_id = function::create_function(
+            &func_name,
+            &FunctionCreateOptions {
+                body: Some("function(tuple) return {tuple[2]} end".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let e = space
+            .index_builder("by_func")
+            .func(func_name.clone())
+            .part((1, FieldType::String))
+            .create()
+            .unwrap_err();
+        assert!(e.to_string().contains("must be deterministic"), "{}", e);
+        function::drop_function(func_id).unwrap();
+
+        // Options are checked before the function is created.
+        let lower = "function(tuple) return {tuple[2]:lower()} end";
+        let e = space
+            .index_builder("by_lower")
+            .index_type(IndexType::Hash)
+            .function(&func_name, FunctionLanguage::Lua, lower)
+            .part((1, FieldType::String))
+            .create()
+            .unwrap_err();
+        assert!(
+            e.to_string().contains("only TREE index can use a function"),
+            "{}",
+            e
+        );
+        assert_eq!(function::function_id(&func_name).unwrap(), None);
+
+        let by_lower = space
+            .index_builder("by_lower")
+            .unique(false)
+            .function(&func_name, FunctionLanguage::Lua, lower)
+            .part((1, FieldType::String))
+            .create()
+            .unwrap();
+        let ids: Vec<u32> = by_lower
+            .select(IteratorType::Eq, &("foo",))
+            .unwrap()
+            .map(|t| t.field(0).unwrap().unwrap())
+            .collect();
+        assert_eq!(ids, [1]);
+
+        let multikey_func_name = crate::temp_space_name!();
+        let by_letter = space
+            .index_builder("by_letter")
+            .unique(false)
+            .multikey_function(
+                &multikey_func_name,
+                FunctionLanguage::Lua,
+                "function(tuple)
+                    local res = {}
+                    for c in tuple[2]:lower():gmatch('.') do
+                        table.insert(res, {c})
+                    end
+                    return res
+                end",
+            )
+            .part((1, FieldType::String))
+            .create()
+            .unwrap();
+        let ids: Vec<u32> = by_letter
+            .select(IteratorType::Eq, &("a",))
+            .unwrap()
+            .map(|t| t.field(0).unwrap().unwrap())
+            .collect();
+        assert_eq!(ids, [2]);
+
+        // The function is dropped if the index can't be created.
+        let bad_func_name = crate::temp_space_name!();
+        space
+            .index_builder("bad")
+            .function(&bad_func_name, FunctionLanguage::Lua, lower)
+            .part((1, FieldType::Unsigned))
+            .create()
+            .unwrap_err();
+        assert_eq!(function::function_id(&bad_func_name).unwrap(), None);
+
+        space.drop().unwrap();
+        for name in [func_name, multikey_func_name] {
+            let id = function::function_id(&name).unwrap().unwrap();
+            function::drop_function(id).unwrap();
+        }
+    }
+
     #[cfg(not(feature = "picodata"))]
     #[crate::test(tarantool = "crate")]
     fn sys_index_metadata() {
@@ -360,6 +360,32 @@ pub mod vclock;
 pub use tarantool_proc::stored_proc as proc;
 pub use tlua;
 
+/// Derive [`space::typed::Row`] for a struct describing a row of a space.
+///
+/// The macro generates the space format, the index definitions and typed
+/// secondary index specifiers, which can be used with
+/// [`space::typed::TypedSpace`].
+///
+/// ```no_run
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, tarantool::Space)]
+/// #[space(name = "books")]
+/// struct Book {
+///     #[space(primary_key)]
+///     id: u64,
+///     #[space(index = "by_author")]
+///     author: String,
+///     title: String,
+/// }
+///
+/// let books = tarantool::space::typed::TypedSpace::<Book>::create().unwrap();
+/// let by_author = books.index(Book::BY_AUTHOR).unwrap();
+/// ```
+///
+/// See [`space::typed`] for details.
+pub use tarantool_proc::Space;
+
 /// A re-export of [linkme] crate used inside #[`[tarantool::test]`]
 /// and #[`[tarantool::proc]`] macro attributes.
 pub use linkme;
@@ -902,6 +902,48 @@ impl Encode for serde_json::Map<String, serde_json::Value> {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// MP_EXT
+////////////////////////////////////////////////////////////////////////////////
+
+/// Encodes a msgpack extension value (`MP_EXT`) of type `type_id` with
+/// payload `data`.
+///
+/// This is used to implement [`Encode`] for types like
+/// [`Decimal`](crate::decimal::Decimal), [`Uuid`](crate::uuid::Uuid) or
+/// [`Datetime`](crate::datetime::Datetime).
+#[inline]
+pub fn write_ext(w: &mut impl Write, type_id: i8, data: &[u8]) -> Result<(), EncodeError> {
+    rmp::encode::write_ext_meta(w, data.len() as _, type_id)?;
+    w.write_all(data)?;
+    Ok(())
+}
+
+/// Decodes a msgpack extension value (`MP_EXT`) of type `type_id` and returns
+/// its payload. `DecodedTy` is the type being decoded and is used for error
+/// reporting.
+///
+/// This is used to implement [`Decode`] for types like
+/// [`Decimal`](crate::decimal::Decimal), [`Uuid`](crate::uuid::Uuid) or
+/// [`Datetime`](crate::datetime::Datetime).
+#[inline]
+pub fn read_ext<'de, DecodedTy>(r: &mut &'de [u8], type_id: i8) -> Result<&'de [u8], DecodeError> {
+    let meta = rmp::decode::read_ext_meta(r).map_err(DecodeError::from_vre::<DecodedTy>)?;
+    if meta.typeid != type_id {
+        return Err(DecodeError::new::<DecodedTy>(format!(
+            "expected msgpack ext #{type_id}, found msgpack ext #{}",
+            meta.typeid
+        )));
+    }
+    let size = meta.size as usize;
+    if r.len() < size {
+        return Err(DecodeError::new::<DecodedTy>("not enough data"));
+    }
+    let (data, tail) = r.split_at(size);
+    *r = tail;
+    Ok(data)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // tests
 ////////////////////////////////////////////////////////////////////////////////
@@ -2223,4 +2265,41 @@ mod tests {
         assert_eq!(decode::<u32>(b"\xce\xff\xff\xff\xff").unwrap(), u32::MAX);
         assert_eq!(decode::<u64>(b"\xcf\xff\xff\xff\xff\xff\xff\xff\xff").unwrap(), u64::MAX);
     }
+
+    #[test]
+    fn encode_ext() {
+        let uuid = crate::uuid::Uuid::parse_str("30de7784-33e2-4393-a8cd-b67534db2432").unwrap();
+        let data = encode(&uuid);
+        assert_eq!(data, rmp_serde::to_vec(&uuid).unwrap());
+        assert_eq!(decode::<crate::uuid::Uuid>(&data).unwrap(), uuid);
+
+        let datetime: crate::datetime::Datetime =
+            time_macros::datetime!(2023-11-11 2:03:19.35421 -3).into();
+        let data = encode(&datetime);
+        assert_eq!(data, rmp_serde::to_vec(&datetime).unwrap());
+        assert_eq!(
+            decode::<crate::datetime::Datetime>(&data).unwrap(),
+            datetime
+        );
+
+        // Trailing data is left unread
+        let mut data = encode(&(uuid, datetime));
+        data.push(0xc0);
+        let mut r = &data[1..];
+        crate::uuid::Uuid::decode(&mut r, &Context::DEFAULT).unwrap();
+        crate::datetime::Datetime::decode(&mut r, &Context::DEFAULT).unwrap();
+        assert_eq!(r, b"\xc0");
+
+        let err = decode::<crate::datetime::Datetime>(&encode(&uuid)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::datetime::Datetime: expected msgpack ext #4, found msgpack ext #2"
+        );
+
+        let err = decode::<crate::uuid::Uuid>(b"\xd4\x02\x00").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed decoding tarantool::uuid::Uuid: Not enough bytes for UUID: expected 16, got 1"
+        );
+    }
 }
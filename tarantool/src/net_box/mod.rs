@@ -213,8 +213,10 @@ impl Conn {
     where
         P: ToTupleBuffer + ?Sized,
     {
-        self.inner
-            .request(&protocol::Execute { sql, bind_params }, options)
+        let result = self
+            .inner
+            .request(&protocol::Execute { sql, bind_params }, options)?;
+        result.raw_rows().map(Tuple::try_from_slice).collect()
     }
 }
 
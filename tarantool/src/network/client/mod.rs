@@ -31,21 +31,43 @@
 //! and push bytes that it gets from the network.
 //!
 //! On creation the client spawns sender and receiver worker threads. Which in turn
-//! use coio based [`TcpStream`] as the transport layer.
+//! use coio based [`TcpStream`] or [`UnixStream`] as the transport layer.
+//! With the `tls` feature the tcp connection can also be wrapped in a TLS
+//! session, see the `tls` module.
+//!
+//! [`UnixStream`]: unix::UnixStream
 
+pub mod pool;
+pub mod prepared;
 pub mod reconnect;
+pub mod schema;
+pub mod space;
+pub mod stream;
 pub mod tcp;
-
-use std::collections::HashMap;
+#[cfg(feature = "tls")]
+pub mod tls;
+mod transport;
+pub mod unix;
+pub mod uri;
+pub mod watch;
+
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
 
+use self::prepared::PreparedStatement;
+use self::schema::Schema;
+use self::space::RemoteSpace;
+use self::stream::Stream;
+#[cfg(doc)]
 use self::tcp::TcpStream;
+use self::transport::Transport;
+use self::uri::Uri;
+use self::watch::Watcher;
 
-use super::protocol::api::{Call, Eval, Execute, Ping, Request};
-use super::protocol::{self, Protocol, SyncIndex};
+use super::protocol::api::{Call, Eval, Execute, Ping, Prepare, Request};
+use super::protocol::{self, Protocol, ProtocolError, ProtocolFeature, ProtocolInfo, SyncIndex};
 use crate::error;
 use crate::error::BoxError;
 use crate::fiber;
@@ -53,6 +75,7 @@ use crate::fiber::r#async::oneshot;
 use crate::fiber::r#async::IntoOnDrop as _;
 use crate::fiber::FiberId;
 use crate::fiber::NoYieldsRefCell;
+use crate::sql::SqlResult;
 use crate::tuple::{ToTupleBuffer, Tuple};
 use crate::unwrap_ok_or;
 
@@ -71,7 +94,8 @@ pub enum ClientError {
     #[error("{0}")]
     ConnectionClosed(Arc<crate::error::Error>),
 
-    /// Error happened during encoding of the request.
+    /// Error happened during encoding of the request or the request is not
+    /// supported by the peer, see [`Client::require_feature`].
     ///
     /// The error is wrapped in a [`Arc`], because some libraries require
     /// error types to implement [`Sync`], which isn't implemented for [`Rc`].
@@ -127,26 +151,50 @@ impl State {
 struct ClientInner {
     protocol: Protocol,
     awaiting_response: HashMap<SyncIndex, oneshot::Sender<Result<(), Arc<error::Error>>>>,
+    /// Requests which were sent without waiting for a response, see
+    /// [`Client::send_detached`]. Responses to these are dropped as soon as
+    /// they're received.
+    detached_requests: HashSet<SyncIndex>,
+    /// Id of the last stream created via [`Client::stream`].
+    last_stream_id: u64,
+    /// Keys watched via [`Client::watch`].
+    watchers: watch::Registry,
+    /// If `true` the watchers are closed when the connection is closed.
+    /// Otherwise the watchers may be attached to another connection later,
+    /// see [`reconnect::Client`].
+    owns_watchers: bool,
+    /// Schema cached by [`Client::schema`].
+    schema: Rc<fiber::r#async::Mutex<Option<Rc<Schema>>>>,
     state: State,
-    /// The same tcp stream sender & receiver fibers a working with. Only stored
+    /// The same stream sender & receiver fibers a working with. Only stored
     /// here for closing.
-    stream: TcpStream,
+    stream: Transport,
     sender_fiber_id: Option<FiberId>,
     receiver_fiber_id: Option<FiberId>,
     clients_count: usize,
 }
 
 impl ClientInner {
-    pub fn new(config: protocol::Config, stream: TcpStream) -> Self {
+    pub fn new(
+        config: protocol::Config,
+        stream: Transport,
+        watchers: watch::Registry,
+        owns_watchers: bool,
+    ) -> Self {
         #[cfg(feature = "picodata")]
-        if config.auth_method == crate::auth::AuthMethod::Ldap {
+        if config.auth_method == crate::auth::AuthMethod::Ldap && !stream.is_tls() {
             crate::say_warn!(
-                "You're using the 'ldap' authentication method, which implies sending the password UNENCRYPTED over the TCP connection. TLS is not yet implemented for IPROTO connections so make sure your communication channel is secure by other means."
+                "You're using the 'ldap' authentication method, which implies sending the password UNENCRYPTED over the connection. Consider enabling TLS via `Config::tls` or make sure your communication channel is secure by other means."
             )
         }
         Self {
             protocol: Protocol::with_config(config),
             awaiting_response: HashMap::new(),
+            detached_requests: HashSet::new(),
+            last_stream_id: 0,
+            watchers,
+            owns_watchers,
+            schema: Default::default(),
             state: State::Alive,
             stream,
             sender_fiber_id: None,
@@ -200,21 +248,64 @@ impl Client {
         port: u16,
         config: protocol::Config,
     ) -> Result<Self, ClientError> {
-        let timeout = config.connect_timeout.unwrap_or(Duration::MAX);
-        let stream = TcpStream::connect_timeout(url, port, timeout)
+        Self::connect_uri_with_config(&Uri::tcp(url, port), config).await
+    }
+
+    /// Creates a new client and tries to establish connection to the
+    /// instance at `uri`, e.g. `unix/:/path/to.sock` or
+    /// `user:password@host:port`.
+    ///
+    /// See [`uri`] for the supported formats.
+    ///
+    /// # Errors
+    /// Error is returned if an attempt to connect failed.
+    pub async fn connect_uri(uri: &Uri) -> Result<Self, ClientError> {
+        Self::connect_uri_with_config(uri, Default::default()).await
+    }
+
+    /// Creates a new client and tries to establish connection to the
+    /// instance at `uri`.
+    ///
+    /// Takes explicit `config` in comparison to [`Client::connect_uri`]
+    /// where default values are used. The credentials from `uri` if any take
+    /// precedence over [`protocol::Config::creds`].
+    ///
+    /// # Errors
+    /// Error is returned if an attempt to connect failed.
+    pub async fn connect_uri_with_config(
+        uri: &Uri,
+        config: protocol::Config,
+    ) -> Result<Self, ClientError> {
+        Self::connect_with_watchers(uri, config, Default::default(), true).await
+    }
+
+    /// Creates a new client which delivers notifications for keys watched in
+    /// `watchers` and subscribes to all of them.
+    pub(crate) async fn connect_with_watchers(
+        uri: &Uri,
+        mut config: protocol::Config,
+        watchers: watch::Registry,
+        owns_watchers: bool,
+    ) -> Result<Self, ClientError> {
+        if let Some(creds) = uri.creds() {
+            config.creds = Some(creds);
+        }
+        let stream = Transport::connect(&uri.address, &config)
+            .await
             .map_err(|e| ClientError::ConnectionClosed(Arc::new(e.into())))?;
-        let client = ClientInner::new(config, stream.clone());
+        let client = ClientInner::new(config, stream.clone(), watchers.clone(), owns_watchers);
         let client = Rc::new(NoYieldsRefCell::new(client));
+        watchers.attach(&client);
 
         let receiver_fiber_id = fiber::Builder::new()
             .func_async(receiver(client.clone(), stream.clone()))
-            .name(format!("iproto-in/{url}:{port}"))
+            .name(format!("iproto-in/{}", uri.address))
             .start_non_joinable()
             .unwrap();
 
         let sender_fiber_id = fiber::Builder::new()
             .func_async(sender(client.clone(), stream))
-            .name(format!("iproto-out/{url}:{port}"))
+            .name(format!("iproto-out/{}", uri.address))
             .start_non_joinable()
             .unwrap();
 
@@ -227,6 +318,151 @@ impl Client {
         Ok(Self(client))
     }
 
+    /// Creates a new [`Stream`] on this connection.
+    ///
+    /// Requests sent through the stream are tagged with a stream id unique
+    /// within the connection and are processed by the server sequentially.
+    /// Streams also support interactive transactions, see [`Stream::begin`].
+    #[inline]
+    pub fn stream(&self) -> Stream {
+        let id = {
+            let mut client = self.0.borrow_mut();
+            client.last_stream_id += 1;
+            client.last_stream_id
+        };
+        Stream::new(self.clone(), id)
+    }
+
+    /// Returns protocol version and features supported by the server.
+    ///
+    /// The information is received during the connection handshake. If the
+    /// handshake isn't finished yet, this function sends a PING request and
+    /// waits for the response.
+    ///
+    /// If the server doesn't support the `IPROTO_ID` request (versions before
+    /// 2.10), the returned [`ProtocolInfo::version`] is `0` and
+    /// [`ProtocolInfo::features`] is empty.
+    pub async fn protocol_info(&self) -> Result<ProtocolInfo, ClientError> {
+        if let Some(info) = self.0.borrow().protocol.peer_protocol_info() {
+            return Ok(info.clone());
+        }
+
+        // The response can only be received after the handshake is done
+        self.ping().await?;
+        let info = self
+            .0
+            .borrow()
+            .protocol
+            .peer_protocol_info()
+            .cloned()
+            .expect("handshake is done at this point");
+        Ok(info)
+    }
+
+    /// Returns an error if the server doesn't support the protocol `feature`.
+    ///
+    /// See [`Client::protocol_info`].
+    pub async fn require_feature(&self, feature: ProtocolFeature) -> Result<(), ClientError> {
+        let info = self.protocol_info().await?;
+        if !info.supports(feature) {
+            let e = ProtocolError::FeatureNotSupported(feature);
+            return Err(ClientError::RequestEncode(e.into()));
+        }
+        Ok(())
+    }
+
+    /// Subscribes to updates of `key` broadcast on the server via
+    /// `box.broadcast`.
+    ///
+    /// Returns an error if the server doesn't support watchers, see
+    /// [`ProtocolFeature::Watchers`].
+    ///
+    /// See [`watch`] for details.
+    pub async fn watch(&self, key: &str) -> Result<Watcher, ClientError> {
+        self.require_feature(ProtocolFeature::Watchers).await?;
+        let watchers = self.0.borrow().watchers.clone();
+        watchers.subscribe(key).map_err(ClientError::RequestEncode)
+    }
+
+    /// Prepares an SQL statement on the server, so that it can be executed
+    /// multiple times without parsing it on each execution.
+    ///
+    /// See [`prepared`] for details.
+    pub async fn prepare(&self, sql: &str) -> Result<PreparedStatement, ClientError> {
+        let info = self.send(&Prepare { sql }).await?;
+        Ok(PreparedStatement::new(self.clone(), info))
+    }
+
+    /// Returns the latest schema version received from the server, or `None`
+    /// if no responses were received yet.
+    #[inline]
+    pub fn schema_version(&self) -> Option<u64> {
+        self.0.borrow().protocol.schema_version()
+    }
+
+    /// Returns the schema of the server.
+    ///
+    /// The schema is fetched on the first call and cached. It's fetched again
+    /// once a response with a newer schema version is received.
+    ///
+    /// See [`schema`] for details.
+    pub async fn schema(&self) -> Result<Rc<Schema>, ClientError> {
+        let cache = self.0.borrow().schema.clone();
+        let mut cache = cache.lock().await;
+        if let Some(schema) = &*cache {
+            let latest = self.schema_version().unwrap_or_default();
+            if schema.version() >= latest {
+                return Ok(schema.clone());
+            }
+        }
+        let schema = Rc::new(Schema::fetch(self).await?);
+        *cache = Some(schema.clone());
+        Ok(schema)
+    }
+
+    /// Fetches the schema from the server even if the cached one is up to
+    /// date.
+    ///
+    /// See [`Client::schema`].
+    pub async fn refresh_schema(&self) -> Result<Rc<Schema>, ClientError> {
+        let cache = self.0.borrow().schema.clone();
+        let mut cache = cache.lock().await;
+        let schema = Rc::new(Schema::fetch(self).await?);
+        *cache = Some(schema.clone());
+        Ok(schema)
+    }
+
+    /// Finds the space by name.
+    ///
+    /// Returns `Ok(None)` if the space doesn't exist. See [`space`] for
+    /// details.
+    pub async fn space(&self, name: &str) -> Result<Option<RemoteSpace>, ClientError> {
+        let schema = self.schema().await?;
+        if schema.space(name).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(RemoteSpace::new(self.clone(), name.into())))
+    }
+
+    /// Send [`Request`] without waiting for the response. The response is
+    /// dropped as soon as it's received.
+    ///
+    /// Does nothing if the connection is already closed.
+    pub(crate) fn send_detached<R: Request>(&self, request: &R) -> Result<(), ClientError> {
+        if self.check_state().is_err() {
+            return Ok(());
+        }
+
+        let mut client = self.0.borrow_mut();
+        let sync = client
+            .protocol
+            .send_request(request)
+            .map_err(ClientError::RequestEncode)?;
+        client.detached_requests.insert(sync);
+        maybe_wake_sender(&client);
+        Ok(())
+    }
+
     fn check_state(&self) -> Result<(), Arc<error::Error>> {
         match &self.0.borrow().state {
             State::Alive => Ok(()),
@@ -279,7 +515,11 @@ pub trait AsClient {
     }
 
     /// Execute sql query remotely.
-    async fn execute<T>(&self, sql: &str, bind_params: &T) -> Result<Vec<Tuple>, ClientError>
+    ///
+    /// Returns the decoded result with the column metadata and the rows for
+    /// queries returning data, or the number of affected rows for DML queries.
+    /// See [`SqlResult`].
+    async fn execute<T>(&self, sql: &str, bind_params: &T) -> Result<SqlResult, ClientError>
     where
         T: ToTupleBuffer + ?Sized,
     {
@@ -342,6 +582,9 @@ impl Drop for Client {
             let mut client = self.0.borrow_mut();
             // Stop fibers
             client.state = State::ClosedManually;
+            if client.owns_watchers {
+                client.watchers.close();
+            }
 
             let receiver_fiber_id = client.receiver_fiber_id;
             let sender_fiber_id = client.sender_fiber_id;
@@ -392,6 +635,9 @@ macro_rules! handle_result {
                     let _ = subscription.send(Err(err.clone()));
                 }
                 $client.state = State::ClosedWithError(err);
+                if $client.owns_watchers {
+                    $client.watchers.close();
+                }
                 return;
             }
         }
@@ -399,7 +645,7 @@ macro_rules! handle_result {
 }
 
 /// Sender work loop. Yields on each iteration and during awaits.
-async fn sender(client: Rc<NoYieldsRefCell<ClientInner>>, mut writer: TcpStream) {
+async fn sender(client: Rc<NoYieldsRefCell<ClientInner>>, mut writer: Transport) {
     loop {
         if client.borrow().state.is_closed() || fiber::is_cancelled() {
             return;
@@ -421,7 +667,7 @@ async fn sender(client: Rc<NoYieldsRefCell<ClientInner>>, mut writer: TcpStream)
 // `await`, even though we're explicitly dropping the reference right before
 // awaiting. Thank you clippy, very helpful!
 #[allow(clippy::await_holding_refcell_ref)]
-async fn receiver(client_cell: Rc<NoYieldsRefCell<ClientInner>>, mut reader: TcpStream) {
+async fn receiver(client_cell: Rc<NoYieldsRefCell<ClientInner>>, mut reader: Transport) {
     let mut buf = vec![0_u8; 4096];
     loop {
         let client = client_cell.borrow();
@@ -453,11 +699,17 @@ async fn receiver(client_cell: Rc<NoYieldsRefCell<ClientInner>>, mut reader: Tcp
                 subscription
                     .send(Ok(()))
                     .expect("cannot be closed at this point");
+            } else if client.detached_requests.remove(&sync) {
+                client.protocol.drop_response(sync);
             } else {
                 crate::say_warn!("received unwaited message for {sync:?}");
             }
         }
 
+        for event in client.protocol.take_events() {
+            client.watchers.dispatch(event);
+        }
+
         // Wake sender to handle the greeting we may have just received
         maybe_wake_sender(&client);
     }
@@ -517,6 +769,26 @@ mod tests {
         assert!(matches!(dbg!(err), ClientError::ConnectionClosed(_)))
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn connect_uri() {
+        let uri: Uri = format!("test_user:password@localhost:{}", listen_port())
+            .parse()
+            .unwrap();
+        let client = Client::connect_uri(&uri).await.unwrap();
+        let (user,): (String,) = client
+            .eval("return box.session.user()", &())
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap()
+            .decode()
+            .unwrap();
+        assert_eq!(user, "test_user");
+
+        let uri = Uri::unix("/this/socket/does/not/exist.sock");
+        let err = Client::connect_uri(&uri).await.unwrap_err();
+        assert!(matches!(dbg!(err), ClientError::ConnectionClosed(_)))
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn ping() {
         let client = test_client().await;
@@ -562,6 +834,8 @@ mod tests {
             .await
             .unwrap();
         assert!(result.len() >= 2);
+        let columns: Vec<_> = result.metadata().iter().map(|c| &*c.name).collect();
+        assert_eq!(columns, ["id", "text"]);
 
         let result = client
             .execute(r#"SELECT * FROM "test_s1" WHERE "id" = ?"#, &(6002,))
@@ -571,9 +845,21 @@ mod tests {
 
         assert_eq!(result.len(), 1);
         assert_eq!(
-            result.first().unwrap().decode::<(u64, String)>().unwrap(),
-            (6002, "6002".into())
+            result.decode_rows::<(u64, String)>().unwrap(),
+            [(6002, "6002".into())]
         );
+
+        let result = client
+            .execute(
+                r#"DELETE FROM "test_s1" WHERE "id" >= ? AND "id" <= ?"#,
+                &(6001, 6002),
+            )
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert!(result.metadata().is_empty());
+        assert!(result.is_empty());
+        assert_eq!(result.row_count(), Some(2));
     }
 
     #[crate::test(tarantool = "crate")]
@@ -665,6 +951,83 @@ mod tests {
         client.check_state().unwrap_err();
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn protocol_info() {
+        let client = test_client().await;
+        let info = client
+            .protocol_info()
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        if info.version == 0 {
+            // Server doesn't support IPROTO_ID
+            assert!(info.features.is_empty());
+        } else {
+            assert!(info.supports(ProtocolFeature::Streams));
+            assert!(info.supports(ProtocolFeature::Transactions));
+            client
+                .require_feature(ProtocolFeature::Streams)
+                .await
+                .unwrap();
+        }
+
+        // Returns the same info when the handshake is already done
+        assert_eq!(client.protocol_info().await.unwrap(), info);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn watch() {
+        let client = test_client().await;
+        let info = client.protocol_info().await.unwrap();
+        if !info.supports(ProtocolFeature::Watchers) {
+            let err = client.watch("test.watch").await.unwrap_err();
+            assert!(matches!(err, ClientError::RequestEncode(_)));
+            return;
+        }
+
+        let lua = crate::lua_state();
+        lua.exec("box.broadcast('test.watch', {'one'})").unwrap();
+
+        // The current value is delivered right after the subscription
+        let mut watcher = client.watch("test.watch").await.unwrap();
+        assert_eq!(watcher.key(), "test.watch");
+        watcher
+            .changed()
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        let value: Option<Vec<String>> = watcher.get().unwrap();
+        assert_eq!(value, Some(vec!["one".into()]));
+        assert!(!watcher.has_changed());
+
+        // Watchers of the same key share the subscription
+        let mut other = client.watch("test.watch").await.unwrap();
+        lua.exec("box.broadcast('test.watch', {'one', 'two'})")
+            .unwrap();
+        for w in [&mut watcher, &mut other] {
+            w.changed().timeout(Duration::from_secs(3)).await.unwrap();
+            let value: Option<Vec<String>> = w.get().unwrap();
+            assert_eq!(value, Some(vec!["one".into(), "two".into()]));
+        }
+
+        // Key deletion is delivered as nil
+        lua.exec("box.broadcast('test.watch', nil)").unwrap();
+        watcher
+            .changed()
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap();
+        assert_eq!(watcher.get_raw(), Some(vec![0xc0]));
+
+        // Watchers are closed with the connection
+        drop(client);
+        watcher
+            .changed()
+            .timeout(Duration::from_secs(3))
+            .await
+            .unwrap_err();
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn concurrent_messages_one_fiber() {
         let client = test_client().await;
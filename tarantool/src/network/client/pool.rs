@@ -0,0 +1,583 @@
+//! A pool of connections to several tarantool instances.
+//!
+//! [`Pool`] keeps a number of [`reconnect::Client`] connections to each of the
+//! endpoints and distributes the requests between the healthy ones according
+//! to [`Config::balancing`].
+//!
+//! The connections are checked in the background with a PING request every
+//! [`Config::health_check_interval`]. A connection is considered unhealthy if
+//! the check or any request sent through it fails with
+//! [`ClientError::ConnectionClosed`], in which case it is excluded from the
+//! balancing and is reconnected with an exponential backoff until a check
+//! succeeds.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::network::client::pool::{Config, Pool};
+//! use tarantool::network::client::AsClient as _;
+//!
+//! let mut config = Config::default();
+//! config.connections_per_endpoint = 4;
+//! let uris = ["replica-1:3301", "replica-2:3301"].map(|uri| uri.parse().unwrap());
+//! let pool = Pool::new(uris, config);
+//! pool.ping().await.unwrap();
+//!
+//! for endpoint in pool.status() {
+//!     println!("{}: {:?}", endpoint.uri, endpoint.health);
+//! }
+//! # };
+//! ```
+//!
+//! [`reconnect::Client`]: super::reconnect::Client
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::reconnect;
+use super::uri::Uri;
+use super::{AsClient, ClientError};
+use crate::error::Error;
+use crate::fiber;
+use crate::fiber::r#async::timeout::IntoTimeout as _;
+use crate::fiber::r#async::watch;
+use crate::network::protocol;
+use crate::time::Instant;
+
+/// Strategy of choosing a connection for the next request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Balancing {
+    /// The healthy connections are used in turns.
+    #[default]
+    RoundRobin,
+    /// The healthy connection with the least number of requests in flight is
+    /// used. Ties are resolved in the round robin order.
+    LeastLoaded,
+}
+
+/// Configuration of [`Pool`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Configuration of each of the connections.
+    pub protocol: protocol::Config,
+    /// Number of connections to each of the endpoints.
+    pub connections_per_endpoint: usize,
+    pub balancing: Balancing,
+    /// Interval between health checks of a healthy connection.
+    pub health_check_interval: Duration,
+    /// Time to wait for the response to a health check. The check fails if
+    /// it expires.
+    ///
+    /// It also limits the time spent reconnecting, i.e. it's used as
+    /// [`protocol::Config::connect_timeout`] unless a smaller one is set.
+    pub health_check_timeout: Duration,
+    /// Delay before the first reconnection attempt of an unhealthy
+    /// connection. It's doubled after each failed attempt.
+    pub min_backoff: Duration,
+    /// Upper limit of the delay between reconnection attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            protocol: Default::default(),
+            connections_per_endpoint: 1,
+            balancing: Default::default(),
+            health_check_interval: Duration::from_secs(1),
+            health_check_timeout: Duration::from_secs(1),
+            min_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Health of an endpoint, see [`EndpointStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Health {
+    /// All of the connections are healthy.
+    Healthy,
+    /// Some of the connections are healthy.
+    Degraded,
+    /// None of the connections are healthy or there are no connections.
+    Unhealthy,
+}
+
+/// Current state of the connections to an endpoint of the [`Pool`].
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub uri: Uri,
+    pub health: Health,
+    pub healthy_connections: usize,
+    pub total_connections: usize,
+    /// Number of requests sent through the connections which haven't
+    /// received a response yet.
+    pub in_flight: usize,
+    /// The error the last failed request or health check of the endpoint
+    /// failed with. Is reset once all of the connections become healthy.
+    pub last_error: Option<Arc<Error>>,
+}
+
+/// Returned as the reason of [`ClientError::ConnectionClosed`] if there are no
+/// healthy connections in the [`Pool`].
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("no healthy connections in the pool")]
+pub struct NoHealthyConnections;
+
+#[derive(Debug)]
+struct Connection {
+    client: reconnect::Client,
+    /// Index of the endpoint in [`PoolInner::endpoints`].
+    endpoint: usize,
+    /// New connections are considered healthy until proven otherwise, so that
+    /// the requests can be sent right away.
+    healthy: Cell<bool>,
+    /// Number of consecutive failures since the connection was last healthy.
+    failures: Cell<u32>,
+    next_check: Cell<Instant>,
+    in_flight: Cell<usize>,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    uri: Uri,
+    last_error: RefCell<Option<Arc<Error>>>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    endpoints: Vec<Endpoint>,
+    connections: Vec<Connection>,
+    config: Config,
+    /// Index of the connection to start the search of the next one from.
+    next: Cell<usize>,
+    /// Wakes up the health checker fiber.
+    wake_health_checker: watch::Sender<()>,
+}
+
+/// A pool of connections to several tarantool instances.
+///
+/// See [module level documentation](self) for details.
+///
+/// Can be cloned to share the same connections, the health checks are stopped
+/// once all of the copies are dropped.
+///
+/// See [`AsClient`] for the full API.
+#[derive(Debug, Clone)]
+pub struct Pool(Rc<PoolInner>);
+
+impl Pool {
+    /// Creates a new pool of connections to `uris` and starts the health
+    /// checker fiber. The connections are established lazily at the first
+    /// request or health check.
+    pub fn new(uris: impl IntoIterator<Item = Uri>, config: Config) -> Self {
+        let now = fiber::clock();
+        // Connecting blocks the fiber, so the health check's timeout can only
+        // be enforced via the connect timeout
+        let mut protocol = config.protocol.clone();
+        protocol.connect_timeout = Some(match protocol.connect_timeout {
+            Some(timeout) => timeout.min(config.health_check_timeout),
+            None => config.health_check_timeout,
+        });
+        let mut endpoints = vec![];
+        let mut connections = vec![];
+        for (i, uri) in uris.into_iter().enumerate() {
+            for _ in 0..config.connections_per_endpoint {
+                let client = reconnect::Client::with_uri_and_config(uri.clone(), protocol.clone());
+                connections.push(Connection {
+                    client,
+                    endpoint: i,
+                    healthy: Cell::new(true),
+                    failures: Cell::new(0),
+                    next_check: Cell::new(now),
+                    in_flight: Cell::new(0),
+                });
+            }
+            endpoints.push(Endpoint {
+                uri,
+                last_error: Default::default(),
+            });
+        }
+
+        let (tx, rx) = watch::channel(());
+        let inner = Rc::new(PoolInner {
+            endpoints,
+            connections,
+            config,
+            next: Cell::new(0),
+            wake_health_checker: tx,
+        });
+        fiber::Builder::new()
+            .func_async(health_checker(Rc::downgrade(&inner), rx))
+            .name("pool-health-check")
+            .start_non_joinable()
+            .unwrap();
+
+        Self(inner)
+    }
+
+    /// Returns the current state of each of the endpoints in the order they
+    /// were passed to [`Pool::new`].
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let mut res: Vec<_> = self
+            .0
+            .endpoints
+            .iter()
+            .map(|endpoint| EndpointStatus {
+                uri: endpoint.uri.clone(),
+                health: Health::Unhealthy,
+                healthy_connections: 0,
+                total_connections: 0,
+                in_flight: 0,
+                last_error: endpoint.last_error.borrow().clone(),
+            })
+            .collect();
+        for connection in &self.0.connections {
+            let status = &mut res[connection.endpoint];
+            status.total_connections += 1;
+            status.in_flight += connection.in_flight.get();
+            if connection.healthy.get() {
+                status.healthy_connections += 1;
+            }
+        }
+        for status in &mut res {
+            status.health = if status.total_connections == 0 {
+                Health::Unhealthy
+            } else if status.healthy_connections == status.total_connections {
+                Health::Healthy
+            } else if status.healthy_connections > 0 {
+                Health::Degraded
+            } else {
+                Health::Unhealthy
+            };
+        }
+        res
+    }
+
+    /// Returns a healthy connection chosen according to
+    /// [`Config::balancing`].
+    ///
+    /// Can be used to send several requests through the same connection,
+    /// e.g. to use [`Stream`]s or watchers. Note that the requests sent
+    /// through the returned client directly aren't accounted for by
+    /// [`Balancing::LeastLoaded`].
+    ///
+    /// [`Stream`]: super::stream::Stream
+    pub fn get(&self) -> Result<reconnect::Client, ClientError> {
+        let i = self.select()?;
+        Ok(self.0.connections[i].client.clone())
+    }
+
+    /// Returns the index of the connection for the next request.
+    fn select(&self) -> Result<usize, ClientError> {
+        let connections = &self.0.connections;
+        let start = self.0.next.get();
+        let mut candidates = (start..start + connections.len())
+            .map(|i| i % connections.len())
+            .filter(|&i| connections[i].healthy.get());
+        let selected = match self.0.config.balancing {
+            Balancing::RoundRobin => candidates.next(),
+            // `min_by_key` returns the first of the equal elements
+            Balancing::LeastLoaded => candidates.min_by_key(|&i| connections[i].in_flight.get()),
+        };
+        let Some(i) = selected else {
+            return Err(ClientError::ConnectionClosed(Arc::new(Error::other(
+                NoHealthyConnections,
+            ))));
+        };
+        self.0.next.set(i + 1);
+        Ok(i)
+    }
+}
+
+impl PoolInner {
+    fn mark_healthy(&self, i: usize) {
+        let connection = &self.connections[i];
+        connection.healthy.set(true);
+        connection.failures.set(0);
+        connection
+            .next_check
+            .set(fiber::clock().saturating_add(self.config.health_check_interval));
+
+        let endpoint = connection.endpoint;
+        let all_healthy = self
+            .connections
+            .iter()
+            .filter(|c| c.endpoint == endpoint)
+            .all(|c| c.healthy.get());
+        if all_healthy {
+            self.endpoints[endpoint].last_error.replace(None);
+        }
+    }
+
+    /// Excludes the connection from balancing and schedules a reconnection.
+    fn mark_unhealthy(&self, i: usize, error: Arc<Error>) {
+        let connection = &self.connections[i];
+        let failures = connection.failures.get().saturating_add(1);
+        connection.healthy.set(false);
+        connection.failures.set(failures);
+        let backoff = backoff(&self.config, failures);
+        connection
+            .next_check
+            .set(fiber::clock().saturating_add(backoff));
+        connection.client.reconnect();
+
+        self.endpoints[connection.endpoint]
+            .last_error
+            .replace(Some(error));
+        // Make the health checker take the new check time into account
+        _ = self.wake_health_checker.send(());
+    }
+}
+
+impl Drop for PoolInner {
+    fn drop(&mut self) {
+        // Let the health checker notice the pool is dropped
+        _ = self.wake_health_checker.send(());
+    }
+}
+
+/// Returns the delay before the next reconnection attempt after `failures`
+/// consecutive failures.
+fn backoff(config: &Config, failures: u32) -> Duration {
+    let factor = 2_u32.saturating_pow(failures.saturating_sub(1));
+    config
+        .min_backoff
+        .saturating_mul(factor)
+        .min(config.max_backoff)
+}
+
+async fn health_checker(pool: Weak<PoolInner>, mut wakeup: watch::Receiver<()>) {
+    loop {
+        let Some(inner) = pool.upgrade() else {
+            return;
+        };
+
+        let now = fiber::clock();
+        let timeout = inner.config.health_check_timeout;
+        let checks = inner
+            .connections
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.next_check.get() <= now)
+            .map(|(i, c)| async move { (i, c.client.ping().timeout(timeout).await) });
+        for (i, res) in futures::future::join_all(checks).await {
+            match res {
+                Ok(()) => inner.mark_healthy(i),
+                Err(e) => inner.mark_unhealthy(i, Arc::new(Error::other(e))),
+            }
+        }
+
+        let next_check = inner
+            .connections
+            .iter()
+            .map(|c| c.next_check.get())
+            .min()
+            .unwrap_or_else(|| fiber::clock().saturating_add(inner.config.health_check_interval));
+        // Don't keep the pool alive while sleeping
+        drop(inner);
+
+        // The result doesn't matter, the checker is woken up either by the
+        // timeout or by the pool
+        _ = wakeup.changed().deadline(next_check).await;
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl AsClient for Pool {
+    async fn send<R: protocol::api::Request>(
+        &self,
+        request: &R,
+    ) -> Result<R::Response, ClientError> {
+        let i = self.select()?;
+        let connection = &self.0.connections[i];
+        let res = {
+            let _guard = InFlightGuard::new(&connection.in_flight);
+            connection.client.send(request).await
+        };
+        if let Err(ClientError::ConnectionClosed(e)) = &res {
+            self.0.mark_unhealthy(i, e.clone());
+        }
+        res
+    }
+}
+
+/// Counts a request as in flight on a connection while alive. The counter is
+/// decremented on drop, so it stays correct even if the request's future is
+/// dropped before completion (e.g. on timeout).
+struct InFlightGuard<'a>(&'a Cell<usize>);
+
+impl<'a> InFlightGuard<'a> {
+    #[inline]
+    fn new(in_flight: &'a Cell<usize>) -> Self {
+        in_flight.set(in_flight.get() + 1);
+        Self(in_flight)
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::test::util::listen_port;
+
+    const _3_SEC: Duration = Duration::from_secs(3);
+
+    fn test_config() -> Config {
+        let mut config = Config::default();
+        config.protocol.creds = Some(("test_user".into(), "password".into()));
+        config.health_check_interval = Duration::from_millis(100);
+        config.min_backoff = Duration::from_millis(50);
+        config
+    }
+
+    fn good_uri() -> Uri {
+        Uri::tcp("localhost", listen_port())
+    }
+
+    fn bad_uri() -> Uri {
+        // Can be any other unused port
+        Uri::tcp("localhost", 0)
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn exponential_backoff() {
+        let mut config = Config::default();
+        config.min_backoff = Duration::from_millis(100);
+        config.max_backoff = Duration::from_secs(1);
+        let backoffs: Vec<_> = (1..=6).map(|n| backoff(&config, n).as_millis()).collect();
+        assert_eq!(backoffs, [100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(backoff(&config, u32::MAX), config.max_backoff);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn round_robin() {
+        let mut config = test_config();
+        config.connections_per_endpoint = 3;
+        let pool = Pool::new([good_uri()], config);
+        let selected: Vec<_> = (0..5).map(|_| pool.select().unwrap()).collect();
+        assert_eq!(selected, [0, 1, 2, 0, 1]);
+
+        // Unhealthy connections are skipped
+        pool.0.connections[2].healthy.set(false);
+        let selected: Vec<_> = (0..3).map(|_| pool.select().unwrap()).collect();
+        assert_eq!(selected, [0, 1, 0]);
+
+        for _ in 0..6 {
+            pool.ping().timeout(_3_SEC).await.unwrap();
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn least_loaded() {
+        let mut config = test_config();
+        config.connections_per_endpoint = 3;
+        config.balancing = Balancing::LeastLoaded;
+        let pool = Pool::new([good_uri()], config);
+        let connections = &pool.0.connections;
+        connections[0].in_flight.set(2);
+        connections[1].in_flight.set(1);
+        connections[2].in_flight.set(1);
+        assert_eq!(pool.select().unwrap(), 1);
+        assert_eq!(pool.select().unwrap(), 2);
+        connections[2].in_flight.set(3);
+        assert_eq!(pool.select().unwrap(), 1);
+
+        connections[0].in_flight.set(0);
+        connections[1].in_flight.set(0);
+        connections[2].in_flight.set(0);
+        let results = futures::future::join_all((0..6).map(|_| pool.ping())).await;
+        for res in results {
+            res.unwrap();
+        }
+        assert_eq!(pool.status()[0].in_flight, 0);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn in_flight_after_dropped_request() {
+        let pool = Pool::new([good_uri()], test_config());
+        pool.ping().timeout(_3_SEC).await.unwrap();
+
+        let res = pool
+            .eval("require'fiber'.sleep(1)", &())
+            .timeout(Duration::from_millis(50))
+            .await;
+        assert!(matches!(res, Err(fiber::r#async::timeout::Error::Expired)));
+        assert_eq!(pool.status()[0].in_flight, 0);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn no_connections() {
+        let mut config = test_config();
+        config.connections_per_endpoint = 0;
+        let pool = Pool::new([good_uri()], config);
+        let status = pool.status();
+        assert_eq!(status[0].total_connections, 0);
+        assert_eq!(status[0].health, Health::Unhealthy);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn unhealthy_endpoint() {
+        let mut config = test_config();
+        config.connections_per_endpoint = 2;
+        let pool = Pool::new([bad_uri(), good_uri()], config);
+
+        // Wait for the first round of health checks
+        fiber::r#async::sleep(Duration::from_millis(300)).await;
+        let status = pool.status();
+        assert_eq!(status[0].uri, bad_uri());
+        assert_eq!(status[0].health, Health::Unhealthy);
+        assert_eq!(status[0].healthy_connections, 0);
+        assert_eq!(status[0].total_connections, 2);
+        assert!(status[0].last_error.is_some());
+        assert_eq!(status[1].health, Health::Healthy);
+        assert_eq!(status[1].healthy_connections, 2);
+        assert!(status[1].last_error.is_none());
+
+        // All requests go to the healthy endpoint
+        for _ in 0..4 {
+            pool.ping().timeout(_3_SEC).await.unwrap();
+        }
+        assert!(pool.0.connections[0].failures.get() > 0);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn no_healthy_connections() {
+        let pool = Pool::new([bad_uri()], test_config());
+
+        // The first request fails to connect and marks the connection unhealthy
+        let err = pool.ping().await.unwrap_err();
+        assert!(matches!(err, ClientError::ConnectionClosed(_)));
+        assert_eq!(pool.status()[0].health, Health::Unhealthy);
+
+        let err = pool.ping().await.unwrap_err();
+        assert_eq!(err.to_string(), "no healthy connections in the pool");
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn recovers_after_reconnect() {
+        let pool = Pool::new([good_uri()], test_config());
+        pool.ping().timeout(_3_SEC).await.unwrap();
+
+        // Simulate a failure, the health checker reconnects the connection
+        let error = Arc::new(Error::other("test error"));
+        pool.0.mark_unhealthy(0, error);
+        assert_eq!(pool.status()[0].health, Health::Unhealthy);
+        assert!(pool.get().is_err());
+
+        fiber::r#async::sleep(Duration::from_millis(300)).await;
+        let status = pool.status();
+        assert_eq!(status[0].health, Health::Healthy);
+        assert!(status[0].last_error.is_none());
+        pool.get().unwrap().ping().timeout(_3_SEC).await.unwrap();
+    }
+}
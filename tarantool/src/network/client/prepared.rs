@@ -0,0 +1,213 @@
+//! Remote SQL prepared statements.
+//!
+//! A [`PreparedStatement`] is created with [`Client::prepare`]. The statement
+//! is parsed by the server once and can then be executed any number of times
+//! with different parameters, which saves the parsing cost on each execution.
+//!
+//! Prepared statements belong to the session, i.e. they can only be executed
+//! on the connection they were prepared on.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::network::client::Client;
+//!
+//! let client = Client::connect("localhost", 3301).await.unwrap();
+//! let stmt = client
+//!     .prepare(r#"SELECT "name" FROM "accounts" WHERE "id" = ?"#)
+//!     .await
+//!     .unwrap();
+//! for id in 1..10 {
+//!     let result = stmt.execute(&(id,)).await.unwrap();
+//!     let names: Vec<(String,)> = result.decode_rows().unwrap();
+//!     println!("{id}: {names:?}");
+//! }
+//! # };
+//! ```
+
+use super::{AsClient as _, Client, ClientError};
+use crate::network::protocol::api::{ExecutePrepared, Unprepare};
+use crate::network::protocol::codec::PreparedInfo;
+use crate::sql::{ColumnMetadata, SqlResult};
+use crate::tuple::ToTupleBuffer;
+
+/// A handle to an SQL statement prepared on the server.
+///
+/// See [module level documentation](self) for details.
+///
+/// If the statement is dropped without calling [`Self::unprepare`], it is
+/// removed from the session in the background and the response to the request
+/// is ignored.
+#[derive(Debug)]
+pub struct PreparedStatement {
+    client: Client,
+    info: PreparedInfo,
+    unprepared: bool,
+}
+
+impl PreparedStatement {
+    #[inline(always)]
+    pub(crate) fn new(client: Client, info: PreparedInfo) -> Self {
+        Self {
+            client,
+            info,
+            unprepared: false,
+        }
+    }
+
+    /// Returns the id of the statement.
+    #[inline(always)]
+    pub fn id(&self) -> u32 {
+        self.info.stmt_id
+    }
+
+    /// Returns the number of parameters the statement must be executed with.
+    #[inline(always)]
+    pub fn bind_count(&self) -> u64 {
+        self.info.bind_count
+    }
+
+    /// Returns metadata of the statement parameters.
+    ///
+    /// See [`PreparedInfo::bind_metadata`].
+    #[inline(always)]
+    pub fn bind_metadata(&self) -> &[ColumnMetadata] {
+        &self.info.bind_metadata
+    }
+
+    /// Returns metadata of the result columns. Is empty for DML statements.
+    #[inline(always)]
+    pub fn metadata(&self) -> &[ColumnMetadata] {
+        &self.info.metadata
+    }
+
+    /// Executes the statement with `bind_params`.
+    pub async fn execute<T>(&self, bind_params: &T) -> Result<SqlResult, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        let request = ExecutePrepared {
+            stmt_id: self.info.stmt_id,
+            bind_params,
+        };
+        self.client.send(&request).await
+    }
+
+    /// Removes the statement from the session and waits for the server's
+    /// response.
+    pub async fn unprepare(mut self) -> Result<(), ClientError> {
+        self.unprepared = true;
+        let request = Unprepare {
+            stmt_id: self.info.stmt_id,
+        };
+        self.client.send(&request).await
+    }
+}
+
+impl Drop for PreparedStatement {
+    fn drop(&mut self) {
+        if self.unprepared {
+            return;
+        }
+
+        let request = Unprepare {
+            stmt_id: self.info.stmt_id,
+        };
+        if let Err(e) = self.client.send_detached(&request) {
+            crate::say_error!("PreparedStatement::drop: failed to unprepare statement: {e}");
+        }
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::error::TarantoolErrorCode;
+    use crate::fiber::r#async::timeout::IntoTimeout as _;
+    use crate::network::protocol;
+    use crate::space::Space;
+    use crate::test::util::listen_port;
+    use std::time::Duration;
+
+    const _3_SEC: Duration = Duration::from_secs(3);
+
+    async fn test_client() -> Client {
+        Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                ..Default::default()
+            },
+        )
+        .timeout(_3_SEC)
+        .await
+        .unwrap()
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn prepare_execute_unprepare() {
+        let space = Space::find("test_s1").unwrap();
+        space.insert(&(6101, "6101")).unwrap();
+        space.insert(&(6102, "6102")).unwrap();
+
+        let client = test_client().await;
+        let stmt = client
+            .prepare(r#"SELECT * FROM "test_s1" WHERE "id" = ?"#)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert_eq!(stmt.bind_count(), 1);
+        assert_eq!(stmt.bind_metadata()[0].name, "?");
+        let columns: Vec<_> = stmt.metadata().iter().map(|c| &*c.name).collect();
+        assert_eq!(columns, ["id", "text"]);
+
+        for id in [6101, 6102] {
+            let result = stmt.execute(&(id,)).timeout(_3_SEC).await.unwrap();
+            assert_eq!(
+                result.decode_rows::<(u64, String)>().unwrap(),
+                [(id, id.to_string())]
+            );
+        }
+
+        let stmt_id = stmt.id();
+        stmt.unprepare().timeout(_3_SEC).await.unwrap();
+
+        // The statement no longer exists
+        let err = client
+            .send(&ExecutePrepared {
+                stmt_id,
+                bind_params: &(6101,),
+            })
+            .await
+            .unwrap_err();
+        let ClientError::ErrorResponse(err) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(err.error_code(), TarantoolErrorCode::WrongQueryId as u32);
+
+        space.delete(&(6101,)).unwrap();
+        space.delete(&(6102,)).unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn prepare_dml() {
+        let client = test_client().await;
+        let stmt = client
+            .prepare(r#"INSERT INTO "test_s1" VALUES (?, ?)"#)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert_eq!(stmt.bind_count(), 2);
+        assert!(stmt.metadata().is_empty());
+
+        let result = stmt.execute(&(6201, "6201")).timeout(_3_SEC).await.unwrap();
+        assert_eq!(result.row_count(), Some(1));
+
+        // Unprepared in the background
+        drop(stmt);
+        client.ping().timeout(_3_SEC).await.unwrap();
+
+        Space::find("test_s1").unwrap().delete(&(6201,)).unwrap();
+    }
+}
@@ -1,8 +1,12 @@
+use super::space::RemoteSpace;
+use super::uri::Uri;
+use super::watch::{self, Watcher};
 use super::AsClient;
 use crate::error::Error;
 use crate::fiber::r#async::Mutex;
 use crate::network::client::ClientError;
 use crate::network::protocol;
+use crate::network::protocol::ProtocolFeature;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -21,9 +25,11 @@ type ClientOrConnectionClosedError = Result<super::Client, Arc<Error>>;
 #[derive(Debug, Clone)]
 pub struct Client {
     client: Rc<Mutex<Option<ClientOrConnectionClosedError>>>,
-    url: String,
-    port: u16,
+    uri: Uri,
     protocol_config: protocol::Config,
+    /// Keys watched via [`Client::watch`]. These are shared between all
+    /// connections, so that the watchers survive reconnects.
+    watchers: watch::Registry,
 
     // Testing related code
     #[cfg(feature = "internal_test")]
@@ -52,9 +58,13 @@ impl Client {
             self.reconnect_count.fetch_add(1, Ordering::Relaxed);
         }
 
-        let res =
-            super::Client::connect_with_config(&self.url, self.port, self.protocol_config.clone())
-                .await;
+        let res = super::Client::connect_with_watchers(
+            &self.uri,
+            self.protocol_config.clone(),
+            self.watchers.clone(),
+            false,
+        )
+        .await;
         match res {
             Ok(new_client) => {
                 *client = Some(Ok(new_client.clone()));
@@ -114,11 +124,32 @@ impl Client {
     /// Takes explicit `config` in comparison to [`Self::new`]
     /// where default values are used.
     pub fn with_config(url: String, port: u16, config: protocol::Config) -> Self {
+        Self::with_uri_and_config(Uri::tcp(url, port), config)
+    }
+
+    /// Creates a new client but does not yet try to establish connection
+    /// to the instance at `uri`, e.g. `unix/:/path/to.sock` or
+    /// `user:password@host:port`. This will happen at the first call through
+    /// [`AsClient`] methods.
+    ///
+    /// See [`super::uri`] for the supported formats.
+    pub fn with_uri(uri: Uri) -> Self {
+        Self::with_uri_and_config(uri, Default::default())
+    }
+
+    /// Creates a new client but does not yet try to establish connection
+    /// to the instance at `uri`. This will happen at the first call through
+    /// [`AsClient`] methods.
+    ///
+    /// Takes explicit `config` in comparison to [`Self::with_uri`]
+    /// where default values are used. The credentials from `uri` if any take
+    /// precedence over [`protocol::Config::creds`].
+    pub fn with_uri_and_config(uri: Uri, config: protocol::Config) -> Self {
         Self {
             client: Default::default(),
-            url,
-            port,
+            uri,
             protocol_config: config,
+            watchers: Default::default(),
 
             #[cfg(feature = "internal_test")]
             inject_error: Default::default(),
@@ -127,6 +158,40 @@ impl Client {
         }
     }
 
+    /// Returns protocol version and features supported by the server.
+    ///
+    /// See [`super::Client::protocol_info`].
+    pub async fn protocol_info(&self) -> Result<protocol::ProtocolInfo, ClientError> {
+        self.client().await?.protocol_info().await
+    }
+
+    /// Subscribes to updates of `key` broadcast on the server via
+    /// `box.broadcast`.
+    ///
+    /// The returned [`Watcher`] is re-subscribed automatically after the
+    /// client reconnects, see [`Self::reconnect`]. Note that the watcher
+    /// doesn't receive any notifications after the connection is closed and
+    /// until the client is reconnected.
+    ///
+    /// See [`super::Client::watch`].
+    pub async fn watch(&self, key: &str) -> Result<Watcher, ClientError> {
+        let client = self.client().await?;
+        client.require_feature(ProtocolFeature::Watchers).await?;
+        self.watchers
+            .subscribe(key)
+            .map_err(ClientError::RequestEncode)
+    }
+
+    /// Finds the space by name.
+    ///
+    /// Note that the returned [`RemoteSpace`] is bound to the current
+    /// connection and must be looked up again after [`Self::reconnect`].
+    ///
+    /// See [`super::Client::space`].
+    pub async fn space(&self, name: &str) -> Result<Option<RemoteSpace>, ClientError> {
+        self.client().await?.space(name).await
+    }
+
     #[cfg(feature = "internal_test")]
     pub fn reconnect_count(&self) -> usize {
         // Don't count initial connection
@@ -299,6 +364,32 @@ mod tests {
         }
     }
 
+    #[crate::test(tarantool = "crate")]
+    async fn watch_after_reconnect() {
+        let client = test_client();
+        let info = client.protocol_info().await.unwrap();
+        if !info.supports(ProtocolFeature::Watchers) {
+            return;
+        }
+
+        let lua = crate::lua_state();
+        lua.exec("box.broadcast('test.reconnect.watch', 1)")
+            .unwrap();
+        let mut watcher = client.watch("test.reconnect.watch").await.unwrap();
+        watcher.changed().timeout(_3_SEC).await.unwrap();
+        assert_eq!(watcher.get::<i32>().unwrap(), Some(1));
+
+        // The key is watched on the new connection
+        client.reconnect_now().await.unwrap();
+        watcher.changed().timeout(_3_SEC).await.unwrap();
+        assert_eq!(watcher.get::<i32>().unwrap(), Some(1));
+
+        lua.exec("box.broadcast('test.reconnect.watch', 2)")
+            .unwrap();
+        watcher.changed().timeout(_3_SEC).await.unwrap();
+        assert_eq!(watcher.get::<i32>().unwrap(), Some(2));
+    }
+
     #[crate::test(tarantool = "crate")]
     async fn try_reconnect_only_once() {
         let client = Client::new("localhost".into(), 0);
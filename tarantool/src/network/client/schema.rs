@@ -0,0 +1,125 @@
+//! Remote schema cache.
+//!
+//! A [`Schema`] contains the definitions of spaces and indexes received from
+//! the server's `_vspace` and `_vindex` system spaces. It's used by
+//! [`RemoteSpace`] and [`RemoteIndex`] to look up spaces and indexes by name.
+//!
+//! The schema is cached per connection, see [`Client::schema`]. It's refreshed
+//! automatically once the client receives a response with a newer schema
+//! version, see [`Client::schema_version`].
+//!
+//! [`RemoteSpace`]: super::space::RemoteSpace
+//! [`RemoteIndex`]: super::space::RemoteIndex
+
+use std::collections::HashMap;
+
+use super::{AsClient as _, Client, ClientError};
+use crate::error::TarantoolErrorCode;
+use crate::index::{self, IndexId, IteratorType};
+use crate::network::protocol::api::{Select, WithSchemaVersion};
+use crate::space::{self, SpaceId, SystemSpace};
+use crate::tuple::Tuple;
+
+/// Definitions of the spaces and indexes of a remote instance.
+///
+/// See [module level documentation](self) for details.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    version: u64,
+    spaces: HashMap<String, space::Metadata<'static>>,
+    indexes: HashMap<(SpaceId, String), index::Metadata<'static>>,
+}
+
+impl Schema {
+    /// Fetches the schema from the server.
+    ///
+    /// The spaces and the indexes are fetched with separate requests, so the
+    /// fetching is retried if the schema changes in between.
+    pub(crate) async fn fetch(client: &Client) -> Result<Self, ClientError> {
+        loop {
+            let spaces = client.send(&select_all(SystemSpace::VSpace)).await?;
+            let version = client
+                .schema_version()
+                .expect("should be present after we received a response");
+            let request = WithSchemaVersion {
+                schema_version: version,
+                request: &select_all(SystemSpace::VIndex),
+            };
+            let indexes = match client.send(&request).await {
+                Err(ClientError::ErrorResponse(e))
+                    if e.error_code() == TarantoolErrorCode::WrongSchemaVersion as u32 =>
+                {
+                    continue;
+                }
+                res => res?,
+            };
+            return Self::from_rows(version, spaces, indexes);
+        }
+    }
+
+    fn from_rows(
+        version: u64,
+        spaces: Vec<Tuple>,
+        indexes: Vec<Tuple>,
+    ) -> Result<Self, ClientError> {
+        let mut schema = Self {
+            version,
+            ..Default::default()
+        };
+        for row in spaces {
+            let metadata: space::Metadata = row.decode().map_err(ClientError::ResponseDecode)?;
+            schema.spaces.insert(metadata.name.to_string(), metadata);
+        }
+        for row in indexes {
+            let metadata: index::Metadata = row.decode().map_err(ClientError::ResponseDecode)?;
+            let key = (metadata.space_id, metadata.name.to_string());
+            schema.indexes.insert(key, metadata);
+        }
+        Ok(schema)
+    }
+
+    /// Returns the schema version of the server at the moment the schema was
+    /// fetched.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the definition of the space `name`.
+    #[inline(always)]
+    pub fn space(&self, name: &str) -> Option<&space::Metadata<'static>> {
+        self.spaces.get(name)
+    }
+
+    /// Returns the id of the space `name`.
+    #[inline(always)]
+    pub fn space_id(&self, name: &str) -> Option<SpaceId> {
+        self.space(name).map(|space| space.id)
+    }
+
+    /// Returns the definition of the index `name` of the space with
+    /// `space_id`.
+    #[inline]
+    pub fn index(&self, space_id: SpaceId, name: &str) -> Option<&index::Metadata<'static>> {
+        // TODO: avoid allocating the key
+        self.indexes.get(&(space_id, name.to_string()))
+    }
+
+    /// Returns the id of the index `name` of the space with `space_id`.
+    #[inline(always)]
+    pub fn index_id(&self, space_id: SpaceId, name: &str) -> Option<IndexId> {
+        self.index(space_id, name).map(|index| index.index_id)
+    }
+}
+
+#[inline(always)]
+fn select_all(space: SystemSpace) -> Select<'static, ()> {
+    Select {
+        space_id: space as _,
+        index_id: 0,
+        limit: u32::MAX,
+        offset: 0,
+        iterator_type: IteratorType::All,
+        key: &(),
+    }
+}
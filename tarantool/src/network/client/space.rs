@@ -0,0 +1,494 @@
+//! Async remote space and index handles.
+//!
+//! [`RemoteSpace`] and [`RemoteIndex`] are the async counterparts of
+//! [`net_box::RemoteSpace`] and [`net_box::RemoteIndex`]. They refer to the
+//! space and index by name, which is resolved to an id using the
+//! [`Schema`](super::schema::Schema) cached by the [`Client`].
+//!
+//! Each request is sent along with the schema version it was resolved with.
+//! If the schema has changed on the server since then, the schema is
+//! refreshed and the request is retried once.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::index::IteratorType;
+//! use tarantool::network::client::Client;
+//!
+//! let client = Client::connect("localhost", 3301).await.unwrap();
+//! let space = client.space("accounts").await.unwrap().unwrap();
+//! space.insert(&(1, "Alice")).await.unwrap();
+//! let by_name = space.index("name").await.unwrap().unwrap();
+//! let rows = by_name.select(IteratorType::Eq, &("Alice",)).await.unwrap();
+//! # };
+//! ```
+//!
+//! [`net_box::RemoteSpace`]: crate::net_box::RemoteSpace
+//! [`net_box::RemoteIndex`]: crate::net_box::RemoteIndex
+
+use super::{AsClient as _, Client, ClientError};
+use crate::error::{BoxError, TarantoolErrorCode};
+use crate::index::{IndexId, IteratorType};
+use crate::network::protocol::api::{
+    Delete, Insert, Replace, Request, Select, Update, Upsert, WithSchemaVersion,
+};
+use crate::space::SpaceId;
+use crate::tuple::{Encode, ToTupleBuffer, Tuple};
+
+/// Sends the request returned by `make_request` for the ids of `space` and
+/// `index` (primary key if `None`). Retries once if the schema is outdated.
+async fn send_resolved<R, F>(
+    client: &Client,
+    space: &str,
+    index: Option<&str>,
+    make_request: F,
+) -> Result<R::Response, ClientError>
+where
+    R: Request,
+    F: Fn(SpaceId, IndexId) -> R,
+{
+    let mut retried = false;
+    loop {
+        let schema = client.schema().await?;
+        let Some(space_id) = schema.space_id(space) else {
+            let e = BoxError::new(
+                TarantoolErrorCode::NoSuchSpace,
+                format!("Space '{space}' does not exist"),
+            );
+            return Err(ClientError::ErrorResponse(e));
+        };
+        let index_id = match index {
+            None => 0,
+            Some(index) => schema.index_id(space_id, index).ok_or_else(|| {
+                ClientError::ErrorResponse(BoxError::new(
+                    TarantoolErrorCode::NoSuchIndexName,
+                    format!("No index '{index}' is defined in space '{space}'"),
+                ))
+            })?,
+        };
+
+        let request = WithSchemaVersion {
+            schema_version: schema.version(),
+            request: &make_request(space_id, index_id),
+        };
+        match client.send(&request).await {
+            Err(ClientError::ErrorResponse(e))
+                if !retried && e.error_code() == TarantoolErrorCode::WrongSchemaVersion as u32 =>
+            {
+                // The schema version in the error response header is newer
+                // than the cached one, so the schema is refetched
+                retried = true;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Async remote space.
+///
+/// See [module level documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct RemoteSpace {
+    client: Client,
+    name: String,
+}
+
+impl RemoteSpace {
+    #[inline(always)]
+    pub(crate) fn new(client: Client, name: String) -> Self {
+        Self { client, name }
+    }
+
+    /// Returns the name of the space.
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Finds the index by name.
+    pub async fn index(&self, name: &str) -> Result<Option<RemoteIndex>, ClientError> {
+        let schema = self.client.schema().await?;
+        let exists = schema
+            .space_id(&self.name)
+            .and_then(|space_id| schema.index(space_id, name))
+            .is_some();
+        if !exists {
+            return Ok(None);
+        }
+        Ok(Some(RemoteIndex {
+            client: self.client.clone(),
+            space: self.name.clone(),
+            index: Some(name.into()),
+        }))
+    }
+
+    /// Returns the primary index of the space.
+    #[inline(always)]
+    pub fn primary_key(&self) -> RemoteIndex {
+        RemoteIndex {
+            client: self.client.clone(),
+            space: self.name.clone(),
+            index: None,
+        }
+    }
+
+    /// The remote-call equivalent of the local call `Space::get(...)`
+    /// (see [details](crate::space::Space::get)).
+    #[inline(always)]
+    pub async fn get<K>(&self, key: &K) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        self.primary_key().get(key).await
+    }
+
+    /// The remote-call equivalent of the local call `Space::select(...)`
+    /// (see [details](crate::space::Space::select)).
+    #[inline(always)]
+    pub async fn select<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<Vec<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        self.primary_key().select(iterator_type, key).await
+    }
+
+    /// The remote-call equivalent of the local call `Space::insert(...)`
+    /// (see [details](crate::space::Space::insert)).
+    pub async fn insert<T>(&self, value: &T) -> Result<Option<Tuple>, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        send_resolved(&self.client, &self.name, None, |space_id, _| Insert {
+            space_id,
+            value,
+        })
+        .await
+    }
+
+    /// The remote-call equivalent of the local call `Space::replace(...)`
+    /// (see [details](crate::space::Space::replace)).
+    pub async fn replace<T>(&self, value: &T) -> Result<Option<Tuple>, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        send_resolved(&self.client, &self.name, None, |space_id, _| Replace {
+            space_id,
+            value,
+        })
+        .await
+    }
+
+    /// The remote-call equivalent of the local call `Space::update(...)`
+    /// (see [details](crate::space::Space::update)).
+    #[inline(always)]
+    pub async fn update<K, Op>(&self, key: &K, ops: &[Op]) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+        Op: Encode,
+    {
+        self.primary_key().update(key, ops).await
+    }
+
+    /// The remote-call equivalent of the local call `Space::upsert(...)`
+    /// (see [details](crate::space::Space::upsert)).
+    #[inline(always)]
+    pub async fn upsert<T, Op>(&self, value: &T, ops: &[Op]) -> Result<Option<Tuple>, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+        Op: Encode,
+    {
+        self.primary_key().upsert(value, ops).await
+    }
+
+    /// The remote-call equivalent of the local call `Space::delete(...)`
+    /// (see [details](crate::space::Space::delete)).
+    #[inline(always)]
+    pub async fn delete<K>(&self, key: &K) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        self.primary_key().delete(key).await
+    }
+}
+
+/// Async remote index.
+///
+/// See [module level documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct RemoteIndex {
+    client: Client,
+    space: String,
+    /// `None` for the primary index.
+    index: Option<String>,
+}
+
+impl RemoteIndex {
+    /// The remote-call equivalent of the local call `Index::get(...)`
+    /// (see [details](crate::index::Index::get)).
+    pub async fn get<K>(&self, key: &K) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        let rows = self.select_limit(IteratorType::Eq, key, 1, 0).await?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// The remote-call equivalent of the local call `Index::select(...)`
+    /// (see [details](crate::index::Index::select)).
+    #[inline(always)]
+    pub async fn select<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+    ) -> Result<Vec<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        self.select_limit(iterator_type, key, u32::MAX, 0).await
+    }
+
+    /// The same as [`Self::select`], but returns at most `limit` tuples
+    /// skipping the first `offset` ones.
+    pub async fn select_limit<K>(
+        &self,
+        iterator_type: IteratorType,
+        key: &K,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        send_resolved(
+            &self.client,
+            &self.space,
+            self.index.as_deref(),
+            |space_id, index_id| Select {
+                space_id,
+                index_id,
+                limit,
+                offset,
+                iterator_type,
+                key,
+            },
+        )
+        .await
+    }
+
+    /// The remote-call equivalent of the local call `Index::update(...)`
+    /// (see [details](crate::index::Index::update)).
+    pub async fn update<K, Op>(&self, key: &K, ops: &[Op]) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+        Op: Encode,
+    {
+        send_resolved(
+            &self.client,
+            &self.space,
+            self.index.as_deref(),
+            |space_id, index_id| Update {
+                space_id,
+                index_id,
+                key,
+                ops,
+            },
+        )
+        .await
+    }
+
+    /// The remote-call equivalent of the local call `Index::upsert(...)`
+    /// (see [details](crate::index::Index::upsert)).
+    pub async fn upsert<T, Op>(&self, value: &T, ops: &[Op]) -> Result<Option<Tuple>, ClientError>
+    where
+        T: ToTupleBuffer + ?Sized,
+        Op: Encode,
+    {
+        send_resolved(
+            &self.client,
+            &self.space,
+            self.index.as_deref(),
+            |space_id, index_id| Upsert {
+                space_id,
+                index_id,
+                value,
+                ops,
+            },
+        )
+        .await
+    }
+
+    /// The remote-call equivalent of the local call `Index::delete(...)`
+    /// (see [details](crate::index::Index::delete)).
+    pub async fn delete<K>(&self, key: &K) -> Result<Option<Tuple>, ClientError>
+    where
+        K: ToTupleBuffer + ?Sized,
+    {
+        send_resolved(
+            &self.client,
+            &self.space,
+            self.index.as_deref(),
+            |space_id, index_id| Delete {
+                space_id,
+                index_id,
+                key,
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::fiber::r#async::timeout::IntoTimeout as _;
+    use crate::network::protocol;
+    use crate::space::{Field, Space};
+    use crate::test::util::listen_port;
+    use std::time::Duration;
+
+    const _3_SEC: Duration = Duration::from_secs(3);
+
+    async fn test_client() -> Client {
+        Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                ..Default::default()
+            },
+        )
+        .timeout(_3_SEC)
+        .await
+        .unwrap()
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn lookup_by_name() {
+        let client = test_client().await;
+        assert!(client.space("no_such_space").await.unwrap().is_none());
+
+        let space = client.space("test_s2").await.unwrap().unwrap();
+        assert_eq!(space.name(), "test_s2");
+        assert!(space.index("no_such_index").await.unwrap().is_none());
+
+        let schema = client.schema().await.unwrap();
+        let space_id = Space::find("test_s2").unwrap().id();
+        assert_eq!(schema.space_id("test_s2"), Some(space_id));
+        assert_eq!(schema.index_id(space_id, "primary"), Some(0));
+        assert_eq!(Some(schema.version()), client.schema_version());
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn crud() {
+        let client = test_client().await;
+        let space = client.space("test_s1").await.unwrap().unwrap();
+
+        let res = space.insert(&(9101, "9101")).timeout(_3_SEC).await.unwrap();
+        assert_eq!(
+            res.unwrap().decode::<(u32, String)>().unwrap(),
+            (9101, "9101".into())
+        );
+        space
+            .replace(&(9102, "9102"))
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+
+        let row = space.get(&(9101,)).timeout(_3_SEC).await.unwrap().unwrap();
+        assert_eq!(
+            row.decode::<(u32, String)>().unwrap(),
+            (9101, "9101".into())
+        );
+
+        let rows = space
+            .select(IteratorType::GE, &(9101,))
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        let rows: Vec<(u32, String)> = rows.iter().map(|t| t.decode().unwrap()).collect();
+        assert_eq!(rows, [(9101, "9101".into()), (9102, "9102".into())]);
+
+        let rows = space
+            .primary_key()
+            .select_limit(IteratorType::GE, &(9101,), 1, 1)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].decode::<(u32, String)>().unwrap(),
+            (9102, "9102".into())
+        );
+
+        let res = space
+            .update(&(9101,), &[("=", 1, "updated")])
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert_eq!(
+            res.unwrap().decode::<(u32, String)>().unwrap(),
+            (9101, "updated".into())
+        );
+
+        space
+            .upsert(&(9103, "9103"), &[("=", 1, "upserted")])
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        let row = space.get(&(9103,)).timeout(_3_SEC).await.unwrap().unwrap();
+        assert_eq!(
+            row.decode::<(u32, String)>().unwrap(),
+            (9103, "9103".into())
+        );
+
+        for id in [9101, 9102, 9103] {
+            let res = space.delete(&(id,)).timeout(_3_SEC).await.unwrap();
+            assert!(res.is_some());
+        }
+        assert!(space.get(&(9101,)).timeout(_3_SEC).await.unwrap().is_none());
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn refresh_on_schema_change() {
+        let client = test_client().await;
+        let space = Space::builder("remote_space_refresh_test")
+            .field(Field::unsigned("id"))
+            .create()
+            .unwrap();
+        space.index_builder("pk").create().unwrap();
+
+        let remote = client
+            .space("remote_space_refresh_test")
+            .await
+            .unwrap()
+            .unwrap();
+        remote.insert(&(1,)).timeout(_3_SEC).await.unwrap();
+        let version = client.schema().await.unwrap().version();
+
+        // The space is recreated, the request is sent with the old schema
+        // version and is retried after the refresh
+        space.drop().unwrap();
+        let space = Space::builder("remote_space_refresh_test")
+            .field(Field::unsigned("id"))
+            .create()
+            .unwrap();
+        space.index_builder("pk").create().unwrap();
+        space.index_builder("sk").part("id").create().unwrap();
+
+        remote.insert(&(2,)).timeout(_3_SEC).await.unwrap();
+        assert!(client.schema().await.unwrap().version() > version);
+        assert_eq!(space.len().unwrap(), 1);
+        assert!(remote.index("sk").await.unwrap().is_some());
+
+        space.drop().unwrap();
+        let err = remote.insert(&(3,)).timeout(_3_SEC).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Space 'remote_space_refresh_test' does not exist"
+        );
+    }
+}
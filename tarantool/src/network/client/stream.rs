@@ -0,0 +1,290 @@
+//! IPROTO streams and interactive transactions.
+//!
+//! A [`Stream`] is created with [`Client::stream`]. All requests sent through
+//! the stream are tagged with the stream's id and are processed by the server
+//! sequentially, in the order they were sent. Requests from different streams
+//! and requests sent without a stream may be processed concurrently.
+//!
+//! Streams also allow running interactive transactions: a transaction is
+//! started with [`Stream::begin`] and is finished with [`Stream::commit`] or
+//! [`Stream::rollback`]. All the requests sent through the stream in between
+//! are executed within that transaction.
+//!
+//! Note that interactive transactions require either the vinyl engine or the
+//! memtx engine with `box.cfg.memtx_use_mvcc_engine` enabled on the server.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::network::client::{AsClient as _, Client};
+//! use tarantool::transaction::IsolationLevel;
+//!
+//! let client = Client::connect("localhost", 3301).await.unwrap();
+//! let stream = client.stream();
+//! stream.begin(None, IsolationLevel::ReadCommitted).await.unwrap();
+//! stream.call("box.space.accounts:insert", &((1, "alice"),)).await.unwrap();
+//! stream.call("box.space.accounts:insert", &((2, "bob"),)).await.unwrap();
+//! stream.commit().await.unwrap();
+//! # };
+//! ```
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use super::{AsClient, Client, ClientError};
+use crate::network::protocol::api::{Begin, Commit, InStream, Request, Rollback};
+use crate::network::protocol::ProtocolFeature;
+use crate::transaction::IsolationLevel;
+
+/// A handle to an IPROTO stream.
+///
+/// See [module level documentation](self) for details.
+///
+/// If the stream is dropped while a transaction is active, the transaction is
+/// rolled back. The rollback request is sent in the background, the response
+/// to it is ignored.
+///
+/// See [`AsClient`] for the full API.
+#[derive(Debug)]
+pub struct Stream {
+    client: Client,
+    id: u64,
+    in_transaction: Cell<bool>,
+}
+
+impl Stream {
+    #[inline(always)]
+    pub(crate) fn new(client: Client, id: u64) -> Self {
+        Self {
+            client,
+            id,
+            in_transaction: Cell::new(false),
+        }
+    }
+
+    /// Returns the id of the stream. It is unique within the connection.
+    #[inline(always)]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns `true` if a transaction was started in this stream and wasn't
+    /// yet committed or rolled back.
+    #[inline(always)]
+    pub fn is_in_transaction(&self) -> bool {
+        self.in_transaction.get()
+    }
+
+    /// Begin an interactive transaction in this stream.
+    ///
+    /// Returns an error if the server doesn't support interactive
+    /// transactions, see [`ProtocolFeature::Transactions`].
+    ///
+    /// - `timeout` is the transaction timeout after which the server rolls
+    ///   the transaction back. If `None` the default `box.cfg.txn_timeout` is
+    ///   used.
+    /// - `isolation` is the transaction isolation level.
+    pub async fn begin(
+        &self,
+        timeout: Option<Duration>,
+        isolation: IsolationLevel,
+    ) -> Result<(), ClientError> {
+        self.client
+            .require_feature(ProtocolFeature::Transactions)
+            .await?;
+        // Set the flag before sending the request, so that the transaction is
+        // rolled back in case the future is dropped before the response
+        // arrives.
+        self.in_transaction.set(true);
+        let res = self.send(&Begin { timeout, isolation }).await;
+        if res.is_err() {
+            self.in_transaction.set(false);
+        }
+        res
+    }
+
+    /// Commit the active transaction of this stream.
+    ///
+    /// If commit fails the transaction is rolled back by the server.
+    pub async fn commit(&self) -> Result<(), ClientError> {
+        let res = self.send(&Commit).await;
+        self.in_transaction.set(false);
+        res
+    }
+
+    /// Rollback the active transaction of this stream.
+    pub async fn rollback(&self) -> Result<(), ClientError> {
+        let res = self.send(&Rollback).await;
+        self.in_transaction.set(false);
+        res
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl AsClient for Stream {
+    async fn send<R: Request>(&self, request: &R) -> Result<R::Response, ClientError> {
+        self.client
+            .require_feature(ProtocolFeature::Streams)
+            .await?;
+        let request = InStream {
+            stream_id: self.id,
+            request,
+        };
+        self.client.send(&request).await
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        if !self.in_transaction.get() {
+            return;
+        }
+
+        let request = InStream {
+            stream_id: self.id,
+            request: &Rollback,
+        };
+        if let Err(e) = self.client.send_detached(&request) {
+            crate::say_error!("Stream::drop: failed to rollback transaction: {e}");
+        }
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::error::TarantoolErrorCode;
+    use crate::fiber;
+    use crate::fiber::r#async::timeout::{self, IntoTimeout as _};
+    use crate::network::protocol;
+    use crate::test::util::listen_port;
+
+    const _3_SEC: Duration = Duration::from_secs(3);
+
+    async fn test_client() -> Client {
+        Client::connect_with_config(
+            "localhost",
+            listen_port(),
+            protocol::Config {
+                creds: Some(("test_user".into(), "password".into())),
+                ..Default::default()
+            },
+        )
+        .timeout(_3_SEC)
+        .await
+        .unwrap()
+    }
+
+    async fn is_in_txn(stream: &Stream) -> bool {
+        let (res,): (bool,) = stream
+            .eval("return box.is_in_txn()", &())
+            .timeout(_3_SEC)
+            .await
+            .unwrap()
+            .decode()
+            .unwrap();
+        res
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn stream_ids_are_unique() {
+        let client = test_client().await;
+        let a = client.stream();
+        let b = client.stream();
+        assert_ne!(a.id(), 0);
+        assert_ne!(a.id(), b.id());
+
+        a.ping().timeout(_3_SEC).await.unwrap();
+        b.ping().timeout(_3_SEC).await.unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn begin_commit_rollback() {
+        let client = test_client().await;
+        let stream = client.stream();
+        assert!(!is_in_txn(&stream).await);
+
+        stream
+            .begin(None, IsolationLevel::Default)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert!(stream.is_in_transaction());
+        assert!(is_in_txn(&stream).await);
+
+        stream.commit().timeout(_3_SEC).await.unwrap();
+        assert!(!stream.is_in_transaction());
+        assert!(!is_in_txn(&stream).await);
+
+        stream
+            .begin(Some(_3_SEC), IsolationLevel::ReadCommitted)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert!(is_in_txn(&stream).await);
+
+        stream.rollback().timeout(_3_SEC).await.unwrap();
+        assert!(!stream.is_in_transaction());
+        assert!(!is_in_txn(&stream).await);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn begin_twice() {
+        let client = test_client().await;
+        let stream = client.stream();
+        stream
+            .begin(None, IsolationLevel::Default)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        let err = stream
+            .begin(None, IsolationLevel::Default)
+            .timeout(_3_SEC)
+            .await
+            .unwrap_err();
+        let timeout::Error::Failed(ClientError::ErrorResponse(err)) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(
+            err.error_code(),
+            TarantoolErrorCode::ActiveTransaction as u32
+        );
+        stream.rollback().timeout(_3_SEC).await.unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn rollback_on_drop() {
+        let lua = crate::lua_state();
+        lua.exec("_G.stream_rollback_on_drop = false").unwrap();
+
+        let client = test_client().await;
+        let stream = client.stream();
+        stream
+            .begin(None, IsolationLevel::Default)
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        stream
+            .eval(
+                "box.on_rollback(function() _G.stream_rollback_on_drop = true end)",
+                &(),
+            )
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        drop(stream);
+
+        // The rollback request is processed in the background.
+        client.ping().timeout(_3_SEC).await.unwrap();
+        let deadline = fiber::clock().saturating_add(_3_SEC);
+        loop {
+            let rolled_back: bool = lua.eval("return _G.stream_rollback_on_drop").unwrap();
+            if rolled_back {
+                break;
+            }
+            assert!(fiber::clock() < deadline, "transaction wasn't rolled back");
+            fiber::sleep(Duration::from_millis(10));
+        }
+        lua.exec("_G.stream_rollback_on_drop = nil").unwrap();
+    }
+}
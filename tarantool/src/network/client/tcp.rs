@@ -54,9 +54,11 @@ pub enum Error {
     WriteClosed,
     #[error("connect timeout")]
     Timeout,
+    #[error("tls handshake with '{address}' failed: {error}")]
+    Tls { error: io::Error, address: String },
 }
 
-fn cvt(t: libc::c_int) -> io::Result<libc::c_int> {
+pub(super) fn cvt(t: libc::c_int) -> io::Result<libc::c_int> {
     if t == -1 {
         Err(io::Error::last_os_error())
     } else {
@@ -66,7 +68,7 @@ fn cvt(t: libc::c_int) -> io::Result<libc::c_int> {
 
 /// A wrapper around a raw file descriptor, which automatically closes the
 /// descriptor if dropped.
-struct AutoCloseFd(RawFd);
+pub(super) struct AutoCloseFd(RawFd);
 
 impl AsRawFd for AutoCloseFd {
     #[inline(always)]
@@ -249,24 +251,7 @@ impl TcpStream {
     #[inline(always)]
     #[track_caller]
     pub fn close(&mut self) -> io::Result<()> {
-        let Some(fd) = self.fd.take() else {
-            // Already closed.
-            return Ok(());
-        };
-
-        // SAFETY: safe because we close the `fd` only once
-        let rc = unsafe { ffi::coio_close(fd) };
-        if rc != 0 {
-            let e = io::Error::last_os_error();
-            if e.raw_os_error() == Some(libc::EBADF) {
-                crate::say_error!("close({fd}): Bad file descriptor");
-                if cfg!(debug_assertions) {
-                    panic!("close({}): Bad file descriptor", fd);
-                }
-            }
-            return Err(e);
-        }
-        Ok(())
+        close_fd(&self.fd)
     }
 }
 
@@ -285,107 +270,34 @@ impl From<AutoCloseFd> for TcpStream {
 }
 
 impl AsyncWrite for TcpStream {
+    #[inline(always)]
     fn poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
-        let Some(fd) = self.fd.get() else {
-            let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
-            return Poll::Ready(Err(e));
-        };
-
-        let (result, err) = (
-            // `self.fd` must be nonblocking for this to work correctly
-            unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) },
-            io::Error::last_os_error(),
-        );
-
-        if result >= 0 {
-            return Poll::Ready(Ok(result as usize));
-        }
-        match err.kind() {
-            io::ErrorKind::WouldBlock => {
-                // SAFETY: Safe as long as this future is executed by
-                // `fiber::block_on` async executor.
-                unsafe { ContextExt::set_coio_wait(cx, fd, ffi::CoIOFlags::WRITE) }
-                Poll::Pending
-            }
-            io::ErrorKind::Interrupted => {
-                // Return poll pending without setting coio wait
-                // so that write can be retried immediately.
-                //
-                // SAFETY: Safe as long as this future is executed by
-                // `fiber::block_on` async executor.
-                unsafe { ContextExt::set_deadline(cx, fiber::clock()) }
-                Poll::Pending
-            }
-            _ => Poll::Ready(Err(err)),
-        }
+        poll_write_fd(&self.fd, cx, buf)
     }
 
+    #[inline(always)]
     fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        if self.fd.get().is_none() {
-            let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
-            return Poll::Ready(Err(e));
-        };
-
-        // [`TcpStream`] similarily to std does not buffer anything,
-        // so there is nothing to flush.
-        //
-        // If buffering is needed use [`futures::io::BufWriter`] on top of this stream.
-        Poll::Ready(Ok(()))
+        poll_flush_fd(&self.fd)
     }
 
-    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        if self.fd.get().is_none() {
-            let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
-            return Poll::Ready(Err(e));
-        };
-
-        let res = self.close();
-        Poll::Ready(res)
+    #[inline(always)]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        poll_close_fd(&self.fd)
     }
 }
 
 impl AsyncRead for TcpStream {
+    #[inline(always)]
     fn poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        let Some(fd) = self.fd.get() else {
-            let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
-            return Poll::Ready(Err(e));
-        };
-
-        let (result, err) = (
-            // `self.fd` must be nonblocking for this to work correctly
-            unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) },
-            io::Error::last_os_error(),
-        );
-
-        if result >= 0 {
-            return Poll::Ready(Ok(result as usize));
-        }
-        match err.kind() {
-            io::ErrorKind::WouldBlock => {
-                // SAFETY: Safe as long as this future is executed by
-                // `fiber::block_on` async executor.
-                unsafe { ContextExt::set_coio_wait(cx, fd, ffi::CoIOFlags::READ) }
-                Poll::Pending
-            }
-            io::ErrorKind::Interrupted => {
-                // Return poll pending without setting coio wait
-                // so that read can be retried immediately.
-                //
-                // SAFETY: Safe as long as this future is executed by
-                // `fiber::block_on` async executor.
-                unsafe { ContextExt::set_deadline(cx, fiber::clock()) }
-                Poll::Pending
-            }
-            _ => Poll::Ready(Err(err)),
-        }
+        poll_read_fd(&self.fd, cx, buf)
     }
 }
 
@@ -397,6 +309,136 @@ impl Drop for TcpStream {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// fd based AsyncRead & AsyncWrite
+////////////////////////////////////////////////////////////////////////////////
+
+// These functions implement the async IO on top of a nonblocking socket
+// descriptor. They're shared between [`TcpStream`] and
+// [`super::unix::UnixStream`].
+
+#[track_caller]
+pub(super) fn close_fd(fd: &Cell<Option<RawFd>>) -> io::Result<()> {
+    let Some(fd) = fd.take() else {
+        // Already closed.
+        return Ok(());
+    };
+
+    // SAFETY: safe because we close the `fd` only once
+    let rc = unsafe { ffi::coio_close(fd) };
+    if rc != 0 {
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() == Some(libc::EBADF) {
+            crate::say_error!("close({fd}): Bad file descriptor");
+            if cfg!(debug_assertions) {
+                panic!("close({}): Bad file descriptor", fd);
+            }
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+pub(super) fn poll_write_fd(
+    fd: &Cell<Option<RawFd>>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    let Some(fd) = fd.get() else {
+        let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
+        return Poll::Ready(Err(e));
+    };
+
+    let (result, err) = (
+        // `fd` must be nonblocking for this to work correctly
+        unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) },
+        io::Error::last_os_error(),
+    );
+
+    if result >= 0 {
+        return Poll::Ready(Ok(result as usize));
+    }
+    match err.kind() {
+        io::ErrorKind::WouldBlock => {
+            // SAFETY: Safe as long as this future is executed by
+            // `fiber::block_on` async executor.
+            unsafe { ContextExt::set_coio_wait(cx, fd, ffi::CoIOFlags::WRITE) }
+            Poll::Pending
+        }
+        io::ErrorKind::Interrupted => {
+            // Return poll pending without setting coio wait
+            // so that write can be retried immediately.
+            //
+            // SAFETY: Safe as long as this future is executed by
+            // `fiber::block_on` async executor.
+            unsafe { ContextExt::set_deadline(cx, fiber::clock()) }
+            Poll::Pending
+        }
+        _ => Poll::Ready(Err(err)),
+    }
+}
+
+pub(super) fn poll_flush_fd(fd: &Cell<Option<RawFd>>) -> Poll<io::Result<()>> {
+    if fd.get().is_none() {
+        let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
+        return Poll::Ready(Err(e));
+    };
+
+    // The streams similarily to std do not buffer anything,
+    // so there is nothing to flush.
+    //
+    // If buffering is needed use [`futures::io::BufWriter`] on top of the stream.
+    Poll::Ready(Ok(()))
+}
+
+pub(super) fn poll_close_fd(fd: &Cell<Option<RawFd>>) -> Poll<io::Result<()>> {
+    if fd.get().is_none() {
+        let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
+        return Poll::Ready(Err(e));
+    };
+
+    Poll::Ready(close_fd(fd))
+}
+
+pub(super) fn poll_read_fd(
+    fd: &Cell<Option<RawFd>>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    let Some(fd) = fd.get() else {
+        let e = io::Error::new(io::ErrorKind::Other, "socket closed already");
+        return Poll::Ready(Err(e));
+    };
+
+    let (result, err) = (
+        // `fd` must be nonblocking for this to work correctly
+        unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) },
+        io::Error::last_os_error(),
+    );
+
+    if result >= 0 {
+        return Poll::Ready(Ok(result as usize));
+    }
+    match err.kind() {
+        io::ErrorKind::WouldBlock => {
+            // SAFETY: Safe as long as this future is executed by
+            // `fiber::block_on` async executor.
+            unsafe { ContextExt::set_coio_wait(cx, fd, ffi::CoIOFlags::READ) }
+            Poll::Pending
+        }
+        io::ErrorKind::Interrupted => {
+            // Return poll pending without setting coio wait
+            // so that read can be retried immediately.
+            //
+            // SAFETY: Safe as long as this future is executed by
+            // `fiber::block_on` async executor.
+            unsafe { ContextExt::set_deadline(cx, fiber::clock()) }
+            Poll::Pending
+        }
+        _ => Poll::Ready(Err(err)),
+    }
+}
+
 /// Resolves provided url and port to a sequence of sock addrs.
 ///
 /// # Returns
@@ -475,7 +517,7 @@ fn resolve_addr(url: &str, port: u16, timeout: f64) -> Result<Vec<SockAddr>, Err
 
 /// # Safety
 /// addr_info.add should be a valid
-unsafe fn connect_socket(addr_info: &AddrInfo<'_>) -> io::Result<AutoCloseFd> {
+pub(super) unsafe fn connect_socket(addr_info: &AddrInfo<'_>) -> io::Result<AutoCloseFd> {
     let fd = nonblocking_socket(addr_info.kind)?;
     let Err(e) = cvt(libc::connect(
         fd.as_raw_fd(),
@@ -492,7 +534,7 @@ unsafe fn connect_socket(addr_info: &AddrInfo<'_>) -> io::Result<AutoCloseFd> {
 
 #[cfg(target_os = "linux")]
 #[inline(always)]
-fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
+pub(super) fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
     // SAFETY: This is safe because `libc::socket` doesn't do undefined behavior
     unsafe {
         let raw_fd = cvt(libc::socket(
@@ -507,7 +549,7 @@ fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
 }
 
 #[cfg(target_os = "macos")]
-fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
+pub(super) fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
     // SAFETY: This is safe because `libc::socket` doesn't do undefined behavior
     let fd = unsafe { AutoCloseFd::from_raw_fd(cvt(libc::socket(kind, libc::SOCK_STREAM, 0))?) };
     // SAFETY: This is safe because fd is open
@@ -530,7 +572,7 @@ fn nonblocking_socket(kind: libc::c_int) -> io::Result<AutoCloseFd> {
     Ok(fd)
 }
 
-fn check_socket_error(fd: &impl AsRawFd) -> io::Result<()> {
+pub(super) fn check_socket_error(fd: &impl AsRawFd) -> io::Result<()> {
     // SAFETY: passed only to ffi call so it's fine
     let mut val: libc::c_int = 0;
     let mut val_len = mem::size_of::<libc::c_int>() as libc::socklen_t;
@@ -583,11 +625,11 @@ impl PartialEq for SockAddr {
 
 impl Eq for SockAddr {}
 
-struct AddrInfo<'a> {
-    kind: libc::c_int,
-    addr: *const libc::sockaddr,
-    addr_len: libc::socklen_t,
-    marker: marker::PhantomData<&'a ()>,
+pub(super) struct AddrInfo<'a> {
+    pub(super) kind: libc::c_int,
+    pub(super) addr: *const libc::sockaddr,
+    pub(super) addr_len: libc::socklen_t,
+    pub(super) marker: marker::PhantomData<&'a ()>,
 }
 
 impl<'a> From<&'a SockAddr> for AddrInfo<'a> {
@@ -0,0 +1,129 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[cfg(feature = "async-std")]
+use async_std::io::{Read as AsyncRead, Write as AsyncWrite};
+#[cfg(not(feature = "async-std"))]
+use futures::{AsyncRead, AsyncWrite};
+
+use super::tcp::{self, TcpStream};
+#[cfg(feature = "tls")]
+use super::tls::TlsStream;
+use super::unix::UnixStream;
+use super::uri::Address;
+use crate::network::protocol::Config;
+
+/// The stream [`Client`](super::Client) sends requests and receives responses
+/// through.
+#[derive(Debug, Clone)]
+pub(crate) enum Transport {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+    #[cfg(feature = "tls")]
+    Tls(TlsStream),
+}
+
+impl Transport {
+    /// Connects to `address` using the timeout and TLS settings from `config`.
+    ///
+    /// This functions makes the fiber **yield**.
+    pub(crate) async fn connect(address: &Address, config: &Config) -> Result<Self, tcp::Error> {
+        let timeout = config.connect_timeout.unwrap_or(Duration::MAX);
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &config.tls {
+            let Address::Tcp { host, port } = address else {
+                return Err(tcp::Error::Connect {
+                    error: io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "tls is not supported for unix sockets",
+                    ),
+                    address: address.to_string(),
+                });
+            };
+            return TlsStream::connect_timeout(host, *port, tls, timeout)
+                .await
+                .map(Self::Tls);
+        }
+        match address {
+            Address::Tcp { host, port } => {
+                TcpStream::connect_timeout(host, *port, timeout).map(Self::Tcp)
+            }
+            Address::Unix { path } => UnixStream::connect_timeout(path, timeout).map(Self::Unix),
+        }
+    }
+
+    /// Returns `true` if the connection is encrypted with TLS.
+    #[cfg_attr(not(feature = "picodata"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn is_tls(&self) -> bool {
+        match self {
+            Self::Tcp(_) | Self::Unix(_) => false,
+            #[cfg(feature = "tls")]
+            Self::Tls(_) => true,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn close(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.close(),
+            Self::Unix(stream) => stream.close(),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => stream.close(),
+        }
+    }
+}
+
+impl AsyncWrite for Transport {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+            Self::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+            Self::Unix(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_close(cx),
+            Self::Unix(stream) => Pin::new(stream).poll_close(cx),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_close(cx),
+        }
+    }
+}
+
+impl AsyncRead for Transport {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+            Self::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(feature = "tls")]
+            Self::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
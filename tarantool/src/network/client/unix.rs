@@ -0,0 +1,295 @@
+//! Contains an implementation of a custom async coio based [`UnixStream`].
+//!
+//! ## Example
+//! ```no_run
+//! # async {
+//! use futures::AsyncReadExt;
+//! use tarantool::network::client::unix::UnixStream;
+//!
+//! let mut stream = UnixStream::connect("/var/run/tarantool/app.sock")
+//!     .unwrap();
+//! let mut buf = vec![];
+//! let read_size = stream
+//!     .read(&mut buf)
+//!     .await
+//!     .unwrap();
+//! # };
+//! ```
+
+use std::cell::Cell;
+use std::io;
+use std::marker;
+use std::mem;
+use std::os::fd::{AsRawFd, IntoRawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+#[cfg(feature = "async-std")]
+use async_std::io::{Read as AsyncRead, Write as AsyncWrite};
+#[cfg(not(feature = "async-std"))]
+use futures::{AsyncRead, AsyncWrite};
+
+use super::tcp::{self, AddrInfo, Error};
+use crate::ffi::tarantool as ffi;
+use crate::fiber;
+
+/// Initial delay before retrying to connect to a socket with a full backlog.
+const MIN_BACKOFF: Duration = Duration::from_millis(1);
+/// Maximum delay before retrying to connect to a socket with a full backlog.
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Async UnixStream based on fibers and coio.
+///
+/// The same as [`TcpStream`] but connects to a unix domain socket, e.g. the
+/// one tarantool listens on when `box.cfg.listen` is `unix/:/path/to.sock`.
+///
+/// See module level [documentation](super::unix) for examples.
+///
+/// [`TcpStream`]: super::tcp::TcpStream
+#[derive(Debug, Clone)]
+pub struct UnixStream {
+    /// A raw unix socket file descriptor. Replaced with `None` when the stream
+    /// is closed.
+    ///
+    /// See [`TcpStream`](super::tcp::TcpStream) for why it's wrapped in a `Rc`.
+    fd: Rc<Cell<Option<RawFd>>>,
+}
+
+impl UnixStream {
+    /// Creates a [`UnixStream`] connected to the socket at `path`.
+    ///
+    /// This functions makes the fiber **yield**.
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::connect_timeout(path, Duration::MAX)
+    }
+
+    /// Creates a [`UnixStream`] connected to the socket at `path` with
+    /// provided `timeout`.
+    ///
+    /// This functions makes the fiber **yield**.
+    pub fn connect_timeout(path: impl AsRef<Path>, timeout: Duration) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let deadline = fiber::clock().saturating_add(timeout);
+        let connect = || -> io::Result<Self> {
+            let addr = sockaddr_un(path)?;
+            let addr_info = AddrInfo {
+                kind: libc::AF_UNIX,
+                addr: &addr as *const libc::sockaddr_un as *const libc::sockaddr,
+                addr_len: mem::size_of::<libc::sockaddr_un>() as _,
+                marker: marker::PhantomData,
+            };
+            let mut backoff = MIN_BACKOFF;
+            let fd = loop {
+                // SAFETY: safe cause addr_info is bound to the lifetime of addr
+                match unsafe { tcp::connect_socket(&addr_info) } {
+                    // Unlike tcp, a nonblocking connect on a unix socket fails
+                    // with EAGAIN if the listener's backlog is full. No
+                    // connection is in progress in this case, so retry later
+                    // with a new socket.
+                    Err(e) if e.raw_os_error() == Some(libc::EAGAIN) => {
+                        let now = fiber::clock();
+                        if now >= deadline {
+                            return Err(io::ErrorKind::TimedOut.into());
+                        }
+                        fiber::sleep(backoff.min(deadline.duration_since(now)));
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                    res => break res?,
+                }
+            };
+            let timeout = deadline.duration_since(fiber::clock());
+            crate::coio::coio_wait(fd.as_raw_fd(), ffi::CoIOFlags::WRITE, timeout.as_secs_f64())?;
+            tcp::check_socket_error(&fd)?;
+            Ok(Self::from(fd.into_raw_fd()))
+        };
+        match connect() {
+            Ok(stream) => Ok(stream),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => Err(Error::Timeout),
+            Err(error) => Err(Error::Connect {
+                error,
+                address: format!("unix/:{}", path.display()),
+            }),
+        }
+    }
+
+    #[inline(always)]
+    #[track_caller]
+    pub fn close(&mut self) -> io::Result<()> {
+        tcp::close_fd(&self.fd)
+    }
+}
+
+fn sockaddr_un(path: &Path) -> io::Result<libc::sockaddr_un> {
+    // SAFETY: all zeros is a valid value for sockaddr_un
+    let mut addr: libc::sockaddr_un = unsafe { mem::zeroed() };
+    addr.sun_family = libc::AF_UNIX as _;
+    let bytes = path.as_os_str().as_bytes();
+    // The path must be nul terminated
+    if bytes.len() >= addr.sun_path.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "socket path is too long",
+        ));
+    }
+    if bytes.contains(&0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "socket path contains a nul byte",
+        ));
+    }
+    for (dst, src) in addr.sun_path.iter_mut().zip(bytes) {
+        *dst = *src as _;
+    }
+    Ok(addr)
+}
+
+impl From<RawFd> for UnixStream {
+    fn from(value: RawFd) -> Self {
+        Self {
+            fd: Rc::new(Cell::new(Some(value))),
+        }
+    }
+}
+
+impl AsyncWrite for UnixStream {
+    #[inline(always)]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        tcp::poll_write_fd(&self.fd, cx, buf)
+    }
+
+    #[inline(always)]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tcp::poll_flush_fd(&self.fd)
+    }
+
+    #[inline(always)]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tcp::poll_close_fd(&self.fd)
+    }
+}
+
+impl AsyncRead for UnixStream {
+    #[inline(always)]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        tcp::poll_read_fd(&self.fd, cx, buf)
+    }
+}
+
+impl Drop for UnixStream {
+    fn drop(&mut self) {
+        if let Err(e) = self.close() {
+            crate::say_error!("UnixStream::drop: closing unix stream failed: {e}");
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+
+    use crate::fiber::r#async::timeout;
+
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    use futures::{AsyncReadExt, AsyncWriteExt};
+
+    const _10_SEC: Duration = Duration::from_secs(10);
+
+    fn socket_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}.sock", std::process::id()));
+        _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn connect_failure() {
+        let path = socket_path("unix-connect-failure");
+        let err = UnixStream::connect_timeout(&path, _10_SEC).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "failed to connect to address 'unix/:{}': No such file or directory (os error 2)",
+                path.display()
+            )
+        );
+
+        let long_path = "x".repeat(200);
+        let err = UnixStream::connect(long_path).unwrap_err();
+        assert!(err.to_string().ends_with("socket path is too long"));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn connect_full_backlog() {
+        let path = socket_path("unix-full-backlog");
+        let listener = UnixListener::bind(&path).unwrap();
+        // Shrink the backlog, so that it's easy to fill.
+        assert_eq!(unsafe { libc::listen(listener.as_raw_fd(), 0) }, 0);
+
+        let mut pending = vec![];
+        let err = loop {
+            match UnixStream::connect_timeout(&path, Duration::from_millis(100)) {
+                Ok(stream) => pending.push(stream),
+                Err(e) => break e,
+            }
+            assert!(pending.len() < 10, "backlog is never full");
+        };
+        assert!(matches!(err, Error::Timeout), "{}", err);
+
+        // Once the listener starts accepting, the retries succeed.
+        let n_pending = pending.len();
+        let jh = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            for _ in 0..n_pending + 1 {
+                listener.accept().unwrap();
+            }
+        });
+        UnixStream::connect_timeout(&path, _10_SEC).unwrap();
+        jh.join().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn read_write() {
+        let path = socket_path("unix-read-write");
+        let listener = UnixListener::bind(&path).unwrap();
+        // Spawn echo listener
+        let jh = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = vec![0; 5];
+            std::io::Read::read_exact(&mut stream, &mut buf).unwrap();
+            std::io::Write::write_all(&mut stream, &buf).unwrap();
+        });
+
+        fiber::block_on(async {
+            let mut stream = UnixStream::connect_timeout(&path, _10_SEC).unwrap();
+            timeout::timeout(_10_SEC, stream.write_all(&[1, 2, 3, 4, 5]))
+                .await
+                .unwrap();
+            let mut buf = vec![0; 5];
+            timeout::timeout(_10_SEC, stream.read_exact(&mut buf))
+                .await
+                .unwrap();
+            assert_eq!(buf, [1, 2, 3, 4, 5]);
+        });
+        jh.join().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,252 @@
+//! Parsing of tarantool URIs.
+//!
+//! Supports the same formats tarantool accepts in `box.cfg.listen` and
+//! `net.box.connect`:
+//! - `3301` - port on `localhost`,
+//! - `host:port`, `[ipv6]:port`,
+//! - `unix/:/path/to.sock`, `unix/:relative/path.sock`,
+//! - any of the above prefixed with `user@` or `user:password@`.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::network::client::{Client, uri::Uri};
+//!
+//! let uri: Uri = "admin:secret@unix/:/var/run/tarantool/app.sock".parse().unwrap();
+//! let client = Client::connect_uri(&uri).await.unwrap();
+//! # };
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Address of a tarantool instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Address {
+    /// A tcp socket at `host:port`.
+    Tcp { host: String, port: u16 },
+    /// A unix domain socket at `path`.
+    Unix { path: PathBuf },
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp { host, port } if host.contains(':') => write!(f, "[{host}]:{port}"),
+            Self::Tcp { host, port } => write!(f, "{host}:{port}"),
+            Self::Unix { path } => write!(f, "unix/:{}", path.display()),
+        }
+    }
+}
+
+/// A parsed tarantool URI.
+///
+/// See [module level documentation](self) for the supported formats.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Uri {
+    pub address: Address,
+    pub user: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Uri {
+    /// Creates a URI of a tcp socket at `host:port` without credentials.
+    #[inline]
+    pub fn tcp(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            address: Address::Tcp {
+                host: host.into(),
+                port,
+            },
+            user: None,
+            password: None,
+        }
+    }
+
+    /// Creates a URI of a unix domain socket at `path` without credentials.
+    #[inline]
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        Self {
+            address: Address::Unix { path: path.into() },
+            user: None,
+            password: None,
+        }
+    }
+
+    /// Returns the credentials if the URI contains a user name. The password
+    /// defaults to an empty string.
+    #[inline]
+    pub fn creds(&self) -> Option<(String, String)> {
+        let user = self.user.clone()?;
+        Some((user, self.password.clone().unwrap_or_default()))
+    }
+}
+
+/// The password is hidden.
+impl fmt::Debug for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Uri")
+            .field("address", &self.address)
+            .field("user", &self.user)
+            .field("password", &self.password.as_ref().map(|_| "<hidden>"))
+            .finish()
+    }
+}
+
+/// The password is not displayed.
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(user) = &self.user {
+            write!(f, "{user}@")?;
+        }
+        self.address.fmt(f)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid uri '{uri}': {reason}")]
+pub struct InvalidUri {
+    uri: String,
+    reason: &'static str,
+}
+
+impl FromStr for Uri {
+    type Err = InvalidUri;
+
+    fn from_str(uri: &str) -> Result<Self, InvalidUri> {
+        let err = |reason| InvalidUri {
+            uri: uri.into(),
+            reason,
+        };
+
+        // A unix socket path may contain '@', so the credentials of a unix
+        // address end right before the `unix/:` prefix. Otherwise the password
+        // may contain '@', so the credentials end at the last one.
+        let unix_creds = uri.find("unix/:").and_then(|i| match &uri[..i] {
+            "" => Some((None, uri)),
+            prefix => Some((Some(prefix.strip_suffix('@')?), &uri[i..])),
+        });
+        let (creds, address) = match (unix_creds, uri.rsplit_once('@')) {
+            (Some(res), _) => res,
+            (None, Some((creds, address))) => (Some(creds), address),
+            (None, None) => (None, uri),
+        };
+        let (user, password) = match creds {
+            Some(creds) => {
+                let (user, password) = match creds.split_once(':') {
+                    Some((user, password)) => (user, Some(password.to_owned())),
+                    None => (creds, None),
+                };
+                if user.is_empty() {
+                    return Err(err("empty user name"));
+                }
+                (Some(user.to_owned()), password)
+            }
+            None => (None, None),
+        };
+
+        let address = if let Some(path) = address.strip_prefix("unix/:") {
+            if path.is_empty() {
+                return Err(err("empty unix socket path"));
+            }
+            Address::Unix { path: path.into() }
+        } else {
+            let (host, port) = match address.rsplit_once(':') {
+                Some((host, port)) => {
+                    let host = match host.strip_prefix('[') {
+                        Some(host) => host.strip_suffix(']').ok_or_else(|| err("missing ']'"))?,
+                        None => host,
+                    };
+                    (host, port)
+                }
+                None => ("localhost", address),
+            };
+            if host.is_empty() {
+                return Err(err("empty host"));
+            }
+            let port = port.parse().map_err(|_| err("invalid port"))?;
+            Address::Tcp {
+                host: host.into(),
+                port,
+            }
+        };
+
+        Ok(Self {
+            address,
+            user,
+            password,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(uri: &str) -> Uri {
+        uri.parse().unwrap()
+    }
+
+    #[test]
+    fn tcp() {
+        assert_eq!(parse("3301"), Uri::tcp("localhost", 3301));
+        assert_eq!(parse("localhost:3301"), Uri::tcp("localhost", 3301));
+        assert_eq!(parse("127.0.0.1:3301"), Uri::tcp("127.0.0.1", 3301));
+        assert_eq!(parse("[::1]:3301"), Uri::tcp("::1", 3301));
+        assert_eq!(parse("[::1]:3301").to_string(), "[::1]:3301");
+    }
+
+    #[test]
+    fn unix() {
+        assert_eq!(parse("unix/:/tmp/app.sock"), Uri::unix("/tmp/app.sock"));
+        assert_eq!(parse("unix/:app.sock"), Uri::unix("app.sock"));
+        assert_eq!(
+            parse("unix/:/tmp/app.sock").to_string(),
+            "unix/:/tmp/app.sock"
+        );
+    }
+
+    #[test]
+    fn creds() {
+        let uri = parse("admin:secret@localhost:3301");
+        assert_eq!(uri.address, Uri::tcp("localhost", 3301).address);
+        assert_eq!(uri.creds(), Some(("admin".into(), "secret".into())));
+        assert_eq!(uri.to_string(), "admin@localhost:3301");
+        assert!(!format!("{uri:?}").contains("secret"));
+
+        let uri = parse("guest@unix/:/tmp/app.sock");
+        assert_eq!(uri.address, Uri::unix("/tmp/app.sock").address);
+        assert_eq!(uri.creds(), Some(("guest".into(), "".into())));
+
+        // The unix socket path may contain '@'
+        assert_eq!(parse("unix/:/run/app@1.sock"), Uri::unix("/run/app@1.sock"));
+        let uri = parse("admin:p@ss@unix/:/run/app@1.sock");
+        assert_eq!(uri.address, Uri::unix("/run/app@1.sock").address);
+        assert_eq!(uri.creds(), Some(("admin".into(), "p@ss".into())));
+
+        // The password may contain '@' and ':'
+        let uri = parse("admin:p@ss:word@localhost:3301");
+        assert_eq!(uri.creds(), Some(("admin".into(), "p@ss:word".into())));
+
+        assert_eq!(parse("localhost:3301").creds(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        for uri in [
+            "",
+            "localhost",
+            "localhost:port",
+            "localhost:70000",
+            ":3301",
+            "[::1:3301",
+            "unix/:",
+            "@localhost:3301",
+            ":secret@localhost:3301",
+        ] {
+            let err = uri.parse::<Uri>().unwrap_err();
+            assert!(err.to_string().starts_with(&format!("invalid uri '{uri}'")));
+        }
+    }
+}
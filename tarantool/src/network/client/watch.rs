@@ -0,0 +1,234 @@
+//! Remote watchers.
+//!
+//! A [`Watcher`] is created with [`Client::watch`] or
+//! [`reconnect::Client::watch`]. It receives notifications about updates of
+//! a key broadcast on the server via `box.broadcast`. This includes builtin
+//! keys like `box.status`, `box.id`, `box.election`, etc.
+//!
+//! All watchers of the same key on the same connection share a single
+//! subscription. The subscription is cancelled once all of the corresponding
+//! watchers are dropped.
+//!
+//! # Example
+//! ```no_run
+//! # async {
+//! use tarantool::network::client::Client;
+//!
+//! let client = Client::connect("localhost", 3301).await.unwrap();
+//! let mut watcher = client.watch("box.id").await.unwrap();
+//! loop {
+//!     watcher.changed().await.unwrap();
+//!     let id: Option<u32> = watcher.get().unwrap();
+//!     println!("instance id: {id:?}");
+//! }
+//! # };
+//! ```
+//!
+//! [`Client::watch`]: super::Client::watch
+//! [`reconnect::Client::watch`]: super::reconnect::Client::watch
+
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use super::{maybe_wake_sender, ClientInner};
+use crate::fiber::r#async::watch;
+use crate::fiber::r#async::RecvError;
+use crate::fiber::NoYieldsRefCell;
+use crate::msgpack::{self, Decode, DecodeError};
+use crate::network::protocol::codec::Event;
+
+/// Value of the watched key. Is `None` until the first notification is
+/// received.
+type Value = Option<Vec<u8>>;
+
+#[derive(Debug)]
+struct Subscription {
+    tx: watch::Sender<Value>,
+    watchers_count: usize,
+}
+
+#[derive(Debug, Default)]
+struct RegistryInner {
+    subscriptions: HashMap<String, Subscription>,
+    /// The connection on which the keys are currently watched.
+    client: Weak<NoYieldsRefCell<ClientInner>>,
+}
+
+/// A set of watched keys shared between [`Watcher`]s and the connections they
+/// receive notifications from.
+///
+/// A single registry may outlive several connections, in which case the keys
+/// are re-subscribed to on each new connection, see [`Registry::attach`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Registry(Rc<NoYieldsRefCell<RegistryInner>>);
+
+impl Registry {
+    /// Makes `client` the connection on which the keys are watched and
+    /// subscribes to all of the currently watched keys on it.
+    pub(super) fn attach(&self, client: &Rc<NoYieldsRefCell<ClientInner>>) {
+        let mut inner = self.0.borrow_mut();
+        inner.client = Rc::downgrade(client);
+
+        let mut client = client.borrow_mut();
+        for key in inner.subscriptions.keys() {
+            if let Err(e) = client.protocol.watch(key) {
+                crate::say_error!("failed to watch key '{key}': {e}");
+            }
+        }
+        maybe_wake_sender(&client);
+    }
+
+    /// Creates a new [`Watcher`] for `key`, subscribing to it on the current
+    /// connection if it isn't watched yet.
+    pub(crate) fn subscribe(&self, key: &str) -> Result<Watcher, crate::error::Error> {
+        let mut inner = self.0.borrow_mut();
+        if let Some(subscription) = inner.subscriptions.get_mut(key) {
+            subscription.watchers_count += 1;
+            let rx = subscription.tx.subscribe();
+            return Ok(Watcher {
+                key: key.into(),
+                rx,
+                registry: self.clone(),
+            });
+        }
+
+        if let Some(client) = inner.client.upgrade() {
+            let mut client = client.borrow_mut();
+            client.protocol.watch(key)?;
+            maybe_wake_sender(&client);
+        }
+
+        let (tx, rx) = watch::channel(None);
+        let subscription = Subscription {
+            tx,
+            watchers_count: 1,
+        };
+        inner.subscriptions.insert(key.into(), subscription);
+        Ok(Watcher {
+            key: key.into(),
+            rx,
+            registry: self.clone(),
+        })
+    }
+
+    fn unsubscribe(&self, key: &str) {
+        let mut inner = self.0.borrow_mut();
+        let Some(subscription) = inner.subscriptions.get_mut(key) else {
+            return;
+        };
+        subscription.watchers_count -= 1;
+        if subscription.watchers_count > 0 {
+            return;
+        }
+        inner.subscriptions.remove(key);
+
+        if let Some(client) = inner.client.upgrade() {
+            let mut client = client.borrow_mut();
+            if let Err(e) = client.protocol.unwatch(key) {
+                crate::say_error!("failed to unwatch key '{key}': {e}");
+            }
+            maybe_wake_sender(&client);
+        }
+    }
+
+    /// Notifies the watchers of the corresponding key.
+    pub(crate) fn dispatch(&self, event: Event) {
+        let inner = self.0.borrow();
+        let Some(subscription) = inner.subscriptions.get(&event.key) else {
+            return;
+        };
+        let data = event
+            .data
+            .unwrap_or_else(|| vec![rmp::Marker::Null.to_u8()]);
+        if subscription.tx.send(Some(data)).is_err() {
+            crate::say_warn!("failed to notify watchers of key '{}'", event.key);
+        }
+    }
+
+    /// Closes all subscriptions, all of the corresponding watchers will
+    /// receive a [`RecvError`].
+    pub(crate) fn close(&self) {
+        let subscriptions = std::mem::take(&mut self.0.borrow_mut().subscriptions);
+        drop(subscriptions);
+    }
+}
+
+/// Receives notifications about updates of a watched key.
+///
+/// See [module level documentation](self) for details.
+#[derive(Debug)]
+pub struct Watcher {
+    key: String,
+    rx: watch::Receiver<Value>,
+    registry: Registry,
+}
+
+impl Watcher {
+    /// Returns the watched key.
+    #[inline(always)]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Waits for the next notification, then marks it as seen.
+    ///
+    /// Returns immediately if there's a notification which isn't yet marked
+    /// as seen. Note that the first notification is sent by the server right
+    /// after the subscription and contains the current value of the key.
+    ///
+    /// Returns an error if the connection was closed.
+    #[inline(always)]
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        self.rx.changed().await
+    }
+
+    /// Returns `true` if there's a notification which isn't yet marked as
+    /// seen, see [`Self::changed`].
+    #[inline(always)]
+    pub fn has_changed(&self) -> bool {
+        self.rx.has_changed()
+    }
+
+    /// Returns the msgpack encoded value from the latest notification.
+    ///
+    /// Returns `None` if no notifications were received yet.
+    #[inline(always)]
+    pub fn get_raw(&self) -> Option<Vec<u8>> {
+        self.rx.get_cloned()
+    }
+
+    /// Returns the decoded value from the latest notification.
+    ///
+    /// Returns `Ok(None)` if no notifications were received yet.
+    pub fn get<T>(&self) -> Result<Option<T>, DecodeError>
+    where
+        T: for<'de> Decode<'de>,
+    {
+        let value = self.rx.borrow();
+        let Some(data) = &*value else {
+            return Ok(None);
+        };
+        msgpack::decode(data).map(Some)
+    }
+}
+
+impl Clone for Watcher {
+    fn clone(&self) -> Self {
+        let mut inner = self.registry.0.borrow_mut();
+        // The subscription may be missing if the registry was closed
+        if let Some(subscription) = inner.subscriptions.get_mut(&self.key) {
+            subscription.watchers_count += 1;
+        }
+        Self {
+            key: self.key.clone(),
+            rx: self.rx.clone(),
+            registry: self.registry.clone(),
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.registry.unsubscribe(&self.key);
+    }
+}
@@ -12,6 +12,8 @@
 #[cfg(feature = "network_client")]
 pub mod client;
 pub mod protocol;
+#[cfg(feature = "network_client")]
+pub mod server;
 
 pub use protocol::ProtocolError;
 
@@ -1,13 +1,16 @@
 use std::io::{Cursor, Write};
+use std::time::Duration;
 
 use crate::error::Error;
 use crate::index::IndexId;
 use crate::index::IteratorType;
 use crate::space::SpaceId;
+use crate::sql::SqlResult;
+use crate::transaction::IsolationLevel;
 use crate::tuple::Encode;
 use crate::tuple::{ToTupleBuffer, Tuple};
 
-use super::codec::IProtoType;
+use super::codec::{IProtoType, PreparedInfo, ProtocolFeature, ProtocolInfo};
 use super::{codec, SyncIndex};
 
 pub trait Request {
@@ -49,6 +52,194 @@ impl Request for Ping {
     }
 }
 
+/// Protocol version and features negotiation request.
+pub struct Id<'a> {
+    pub version: u64,
+    pub features: &'a [ProtocolFeature],
+}
+
+impl<'a> Request for Id<'a> {
+    const TYPE: IProtoType = IProtoType::Id;
+    type Response = ProtocolInfo;
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_id(out, self.version, self.features)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        codec::decode_id(r#in)
+    }
+}
+
+/// Subscribe to updates of `key` broadcast via `box.broadcast`.
+///
+/// The server doesn't respond to this request, instead it sends
+/// [`IProtoType::Event`] packets. Sending this request again acknowledges the
+/// received event, the server doesn't send the next event until then.
+pub struct Watch<'a> {
+    pub key: &'a str,
+}
+
+impl<'a> Request for Watch<'a> {
+    const TYPE: IProtoType = IProtoType::Watch;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_watch(out, self.key)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
+/// Unsubscribe from updates of `key`. The server doesn't respond to this
+/// request.
+pub struct Unwatch<'a> {
+    pub key: &'a str,
+}
+
+impl<'a> Request for Unwatch<'a> {
+    const TYPE: IProtoType = IProtoType::Unwatch;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_unwatch(out, self.key)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
+/// A wrapper which sends the inner request in the IPROTO stream with the given
+/// id. Requests from one stream are processed by the server sequentially and
+/// can be combined into an interactive transaction, see [`Begin`].
+pub struct InStream<'r, R> {
+    pub stream_id: u64,
+    pub request: &'r R,
+}
+
+impl<'r, R> Request for InStream<'r, R>
+where
+    R: Request,
+{
+    const TYPE: IProtoType = R::TYPE;
+    type Response = R::Response;
+
+    #[inline(always)]
+    fn encode_header(&self, out: &mut impl Write, sync: SyncIndex) -> Result<(), Error> {
+        codec::encode_header_with_stream_id(out, sync, self.stream_id, Self::TYPE)
+    }
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        self.request.encode_body(out)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        R::decode_response_body(r#in)
+    }
+}
+
+/// A wrapper which sends the inner request along with the schema version the
+/// client expects. The server responds with
+/// [`TarantoolErrorCode::WrongSchemaVersion`] if the schema has changed since.
+///
+/// [`TarantoolErrorCode::WrongSchemaVersion`]: crate::error::TarantoolErrorCode::WrongSchemaVersion
+pub struct WithSchemaVersion<'r, R> {
+    pub schema_version: u64,
+    pub request: &'r R,
+}
+
+impl<'r, R> Request for WithSchemaVersion<'r, R>
+where
+    R: Request,
+{
+    const TYPE: IProtoType = R::TYPE;
+    type Response = R::Response;
+
+    #[inline(always)]
+    fn encode_header(&self, out: &mut impl Write, sync: SyncIndex) -> Result<(), Error> {
+        codec::encode_header_with_schema_version(out, sync, self.schema_version, Self::TYPE)
+    }
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        self.request.encode_body(out)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        R::decode_response_body(r#in)
+    }
+}
+
+/// Begin an interactive transaction. Must be sent within a stream, see
+/// [`InStream`].
+pub struct Begin {
+    pub timeout: Option<Duration>,
+    pub isolation: IsolationLevel,
+}
+
+impl Request for Begin {
+    const TYPE: IProtoType = IProtoType::Begin;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_begin(out, self.timeout, self.isolation)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
+/// Commit the interactive transaction of the stream, see [`InStream`].
+pub struct Commit;
+
+impl Request for Commit {
+    const TYPE: IProtoType = IProtoType::Commit;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_commit(out)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
+/// Rollback the interactive transaction of the stream, see [`InStream`].
+pub struct Rollback;
+
+impl Request for Rollback {
+    const TYPE: IProtoType = IProtoType::Rollback;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_rollback(out)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
 pub struct Call<'a, 'b, T: ?Sized> {
     pub fn_name: &'a str,
     pub args: &'b T,
@@ -105,7 +296,7 @@ where
     T: ToTupleBuffer + ?Sized,
 {
     const TYPE: IProtoType = IProtoType::Execute;
-    type Response = Vec<Tuple>;
+    type Response = SqlResult;
 
     #[inline(always)]
     fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
@@ -114,7 +305,72 @@ where
 
     #[inline(always)]
     fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
-        codec::decode_multiple_rows(r#in)
+        codec::decode_sql_result(r#in)
+    }
+}
+
+/// Prepares the SQL statement on the server. The statement is bound to the
+/// session and can be executed by id with [`ExecutePrepared`].
+pub struct Prepare<'a> {
+    pub sql: &'a str,
+}
+
+impl<'a> Request for Prepare<'a> {
+    const TYPE: IProtoType = IProtoType::Prepare;
+    type Response = PreparedInfo;
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_prepare(out, self.sql)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        codec::decode_prepare(r#in)
+    }
+}
+
+/// Removes the statement prepared with [`Prepare`] from the session.
+pub struct Unprepare {
+    pub stmt_id: u32,
+}
+
+impl Request for Unprepare {
+    const TYPE: IProtoType = IProtoType::Prepare;
+    type Response = ();
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_unprepare(out, self.stmt_id)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(_in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        Ok(())
+    }
+}
+
+/// Executes the statement prepared with [`Prepare`].
+pub struct ExecutePrepared<'b, T: ?Sized> {
+    pub stmt_id: u32,
+    pub bind_params: &'b T,
+}
+
+impl<'b, T> Request for ExecutePrepared<'b, T>
+where
+    T: ToTupleBuffer + ?Sized,
+{
+    const TYPE: IProtoType = IProtoType::Execute;
+    type Response = SqlResult;
+
+    #[inline(always)]
+    fn encode_body(&self, out: &mut impl Write) -> Result<(), Error> {
+        codec::encode_execute_prepared(out, self.stmt_id, self.bind_params)
+    }
+
+    #[inline(always)]
+    fn decode_response_body(r#in: &mut Cursor<Vec<u8>>) -> Result<Self::Response, Error> {
+        codec::decode_sql_result(r#in)
     }
 }
 
@@ -1,5 +1,6 @@
 use std::io::{self, Cursor, Read, Seek, Write};
 use std::os::raw::c_char;
+use std::time::Duration;
 
 use crate::auth::AuthMethod;
 use crate::error::Error;
@@ -7,6 +8,8 @@ use crate::error::TarantoolError;
 use crate::index::IteratorType;
 use crate::msgpack;
 use crate::network::protocol::ProtocolError;
+use crate::sql::{ColumnMetadata, SqlResult};
+use crate::transaction::IsolationLevel;
 use crate::tuple::{ToTupleBuffer, Tuple};
 
 use super::SyncIndex;
@@ -21,6 +24,8 @@ pub mod iproto_key {
     // ...
     pub const SCHEMA_VERSION: u8 = 0x05;
     // ...
+    pub const STREAM_ID: u8 = 0x0a;
+    // ...
     pub const SPACE_ID: u8 = 0x10;
     pub const INDEX_ID: u8 = 0x11;
     pub const LIMIT: u8 = 0x12;
@@ -38,6 +43,9 @@ pub mod iproto_key {
     // ...
     pub const DATA: u8 = 0x30;
     pub const ERROR: u8 = 0x31;
+    pub const METADATA: u8 = 0x32;
+    pub const BIND_METADATA: u8 = 0x33;
+    pub const BIND_COUNT: u8 = 0x34;
     // ...
     pub const SQL_TEXT: u8 = 0x40;
     pub const SQL_BIND: u8 = 0x41;
@@ -47,6 +55,13 @@ pub mod iproto_key {
     // ...
     pub const ERROR_EXT: u8 = 0x52;
     // ...
+    pub const VERSION: u8 = 0x54;
+    pub const FEATURES: u8 = 0x55;
+    pub const TIMEOUT: u8 = 0x56;
+    pub const EVENT_KEY: u8 = 0x57;
+    pub const EVENT_DATA: u8 = 0x58;
+    pub const TXN_ISOLATION: u8 = 0x59;
+    // ...
 }
 use iproto_key::*;
 
@@ -81,12 +96,77 @@ crate::define_enum_with_introspection! {
         // ...
         Ping = 64,
         // ...
+        /// Protocol features negotiation.
+        Id = 73,
+        /// Subscribe to updates of a key broadcast via `box.broadcast`.
+        Watch = 74,
+        /// Unsubscribe from updates of a key.
+        Unwatch = 75,
+        /// Notification about an update of a watched key. Is sent by the
+        /// server.
+        Event = 76,
+        // ...
         /// Error marker. This value will be combined with the error code in the
         /// actual iproto response: `(IProtoType::Error | error_code)`.
         Error = 1 << 15,
     }
 }
 
+crate::define_enum_with_introspection! {
+    /// Optional feature of the iproto protocol negotiated via [`IProtoType::Id`].
+    ///
+    /// See `enum iproto_feature_id` in \<tarantool>/src/box/iproto_features.h
+    /// for source of truth.
+    #[non_exhaustive]
+    #[repr(u8)]
+    pub enum ProtocolFeature {
+        /// Streams support, see `IPROTO_STREAM_ID`.
+        Streams = 0,
+        /// Interactive transactions support, see [`IProtoType::Begin`].
+        Transactions = 1,
+        /// `MP_ERROR` msgpack extension support.
+        ErrorExtension = 2,
+        /// Remote watchers support, see `IPROTO_WATCH`.
+        Watchers = 3,
+        /// Pagination support, see `IPROTO_AFTER_POSITION`.
+        Pagination = 4,
+        /// Space and index names in DML requests.
+        SpaceAndIndexNames = 5,
+        /// `IPROTO_WATCH_ONCE` request support.
+        WatchOnce = 6,
+        /// Tuple format in DML request responses.
+        DmlTupleExtension = 7,
+        /// Tuple format in CALL/EVAL request responses.
+        CallRetTupleExtension = 8,
+        /// Tuple format in CALL/EVAL request arguments.
+        CallArgTupleExtension = 9,
+        /// Space snapshot cursors support.
+        FetchSnapshotCursor = 10,
+        /// Synchronous transactions in `IPROTO_BEGIN` and `IPROTO_COMMIT`.
+        IsSync = 11,
+    }
+}
+
+/// Protocol version and features of one side of the connection, as
+/// exchanged via [`IProtoType::Id`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtocolInfo {
+    /// Version of the iproto protocol. Is `0` if the peer doesn't support
+    /// [`IProtoType::Id`].
+    pub version: u64,
+    /// Supported protocol features. Features unknown to this crate are
+    /// omitted.
+    pub features: Vec<ProtocolFeature>,
+}
+
+impl ProtocolInfo {
+    /// Returns `true` if `feature` is in the list of supported features.
+    #[inline]
+    pub fn supports(&self, feature: ProtocolFeature) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
 /// Encode an IPROTO request header.
 #[inline(always)]
 pub fn encode_header(
@@ -105,7 +185,58 @@ pub fn encode_header(
     helper.encode(stream)
 }
 
+/// Encode an IPROTO request header with the `IPROTO_STREAM_ID` key. Requests
+/// with the same non-zero stream id are processed by the server sequentially.
+pub fn encode_header_with_stream_id(
+    stream: &mut impl Write,
+    sync: SyncIndex,
+    stream_id: u64,
+    request_type: IProtoType,
+) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 3)?;
+    rmp::encode::write_pfix(stream, REQUEST_TYPE)?;
+    rmp::encode::write_pfix(stream, request_type as u8)?;
+    rmp::encode::write_pfix(stream, SYNC)?;
+    rmp::encode::write_uint(stream, sync.0)?;
+    rmp::encode::write_pfix(stream, STREAM_ID)?;
+    rmp::encode::write_uint(stream, stream_id)?;
+    Ok(())
+}
+
+/// Encode an IPROTO request header with the `IPROTO_SCHEMA_VERSION` key. The
+/// server responds with [`TarantoolErrorCode::WrongSchemaVersion`] if its
+/// schema version differs from `schema_version`.
+///
+/// [`TarantoolErrorCode::WrongSchemaVersion`]: crate::error::TarantoolErrorCode::WrongSchemaVersion
+pub fn encode_header_with_schema_version(
+    stream: &mut impl Write,
+    sync: SyncIndex,
+    schema_version: u64,
+    request_type: IProtoType,
+) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 3)?;
+    rmp::encode::write_pfix(stream, REQUEST_TYPE)?;
+    rmp::encode::write_pfix(stream, request_type as u8)?;
+    rmp::encode::write_pfix(stream, SYNC)?;
+    rmp::encode::write_uint(stream, sync.0)?;
+    rmp::encode::write_pfix(stream, SCHEMA_VERSION)?;
+    rmp::encode::write_uint(stream, schema_version)?;
+    Ok(())
+}
+
 pub fn chap_sha1_auth_data(password: &str, salt: &[u8]) -> Vec<u8> {
+    let scramble = chap_sha1_scramble(password, salt);
+
+    // 5 is the maximum possible MP_STR header size
+    let mut res = Vec::with_capacity(scramble.len() + 5);
+    rmp::encode::write_str_len(&mut res, scramble.len() as _).expect("Can't fail for a Vec");
+    res.write_all(&scramble).expect("Can't fail for a Vec");
+    return res;
+}
+
+/// Computes the 'chap-sha1' scramble of the `password` which is sent by the
+/// client in the [`IProtoType::Auth`] request.
+pub fn chap_sha1_scramble(password: &str, salt: &[u8]) -> [u8; 20] {
     // prepare 'chap-sha1' scramble:
     // salt = base64_decode(encoded_salt);
     // step_1 = sha1(password);
@@ -133,14 +264,7 @@ pub fn chap_sha1_auth_data(password: &str, salt: &[u8]) -> Vec<u8> {
         .zip(step_3.iter())
         .for_each(|(a, b)| *a ^= *b);
 
-    let scramble_bytes = &step_1_and_scramble.as_slice();
-    debug_assert_eq!(scramble_bytes.len(), 20);
-
-    // 5 is the maximum possible MP_STR header size
-    let mut res = Vec::with_capacity(scramble_bytes.len() + 5);
-    rmp::encode::write_str_len(&mut res, scramble_bytes.len() as _).expect("Can't fail for a Vec");
-    res.write_all(scramble_bytes).expect("Can't fail for a Vec");
-    return res;
+    step_1_and_scramble.into()
 }
 
 #[cfg(feature = "picodata")]
@@ -226,6 +350,70 @@ pub fn encode_ping(stream: &mut impl Write) -> Result<(), Error> {
     Ok(())
 }
 
+pub fn encode_id(
+    stream: &mut impl Write,
+    version: u64,
+    features: &[ProtocolFeature],
+) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 2)?;
+    rmp::encode::write_pfix(stream, VERSION)?;
+    rmp::encode::write_uint(stream, version)?;
+    rmp::encode::write_pfix(stream, FEATURES)?;
+    rmp::encode::write_array_len(stream, features.len() as _)?;
+    for &feature in features {
+        rmp::encode::write_uint(stream, feature as _)?;
+    }
+    Ok(())
+}
+
+pub fn encode_watch(stream: &mut impl Write, key: &str) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 1)?;
+    rmp::encode::write_pfix(stream, EVENT_KEY)?;
+    rmp::encode::write_str(stream, key)?;
+    Ok(())
+}
+
+pub fn encode_unwatch(stream: &mut impl Write, key: &str) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 1)?;
+    rmp::encode::write_pfix(stream, EVENT_KEY)?;
+    rmp::encode::write_str(stream, key)?;
+    Ok(())
+}
+
+pub fn encode_begin(
+    stream: &mut impl Write,
+    timeout: Option<Duration>,
+    isolation: IsolationLevel,
+) -> Result<(), Error> {
+    let mut map_len = 0;
+    if timeout.is_some() {
+        map_len += 1;
+    }
+    if isolation != IsolationLevel::Default {
+        map_len += 1;
+    }
+    rmp::encode::write_map_len(stream, map_len)?;
+    if let Some(timeout) = timeout {
+        rmp::encode::write_pfix(stream, TIMEOUT)?;
+        rmp::encode::write_f64(stream, timeout.as_secs_f64())?;
+    }
+    if isolation != IsolationLevel::Default {
+        rmp::encode::write_pfix(stream, TXN_ISOLATION)?;
+        rmp::encode::write_uint(stream, isolation.as_raw() as _)?;
+    }
+    Ok(())
+}
+
+pub fn encode_commit(stream: &mut impl Write) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 0)?;
+    Ok(())
+}
+
+pub fn encode_rollback(stream: &mut impl Write) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 0)?;
+    Ok(())
+}
+
 pub fn encode_execute<P>(stream: &mut impl Write, sql: &str, bind_params: &P) -> Result<(), Error>
 where
     P: ToTupleBuffer + ?Sized,
@@ -239,6 +427,39 @@ where
     Ok(())
 }
 
+pub fn encode_execute_prepared<P>(
+    stream: &mut impl Write,
+    stmt_id: u32,
+    bind_params: &P,
+) -> Result<(), Error>
+where
+    P: ToTupleBuffer + ?Sized,
+{
+    rmp::encode::write_map_len(stream, 2)?;
+    rmp::encode::write_pfix(stream, STMT_ID)?;
+    rmp::encode::write_u32(stream, stmt_id)?;
+
+    rmp::encode::write_pfix(stream, SQL_BIND)?;
+    bind_params.write_tuple_data(stream)?;
+    Ok(())
+}
+
+pub fn encode_prepare(stream: &mut impl Write, sql: &str) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 1)?;
+    rmp::encode::write_pfix(stream, SQL_TEXT)?;
+    rmp::encode::write_str(stream, sql)?;
+    Ok(())
+}
+
+/// Encodes an `IPROTO_PREPARE` request body which removes the prepared
+/// statement from the session.
+pub fn encode_unprepare(stream: &mut impl Write, stmt_id: u32) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 1)?;
+    rmp::encode::write_pfix(stream, STMT_ID)?;
+    rmp::encode::write_u32(stream, stmt_id)?;
+    Ok(())
+}
+
 pub fn encode_call<T>(stream: &mut impl Write, function_name: &str, args: &T) -> Result<(), Error>
 where
     T: ToTupleBuffer + ?Sized,
@@ -452,6 +673,13 @@ impl Header {
             }
         }
 
+        if iproto_type == Some(IProtoType::Event as _) {
+            // Event notifications are not responses to any requests, so these
+            // fields are optional
+            sync.get_or_insert(0);
+            schema_version.get_or_insert(0);
+        }
+
         if sync.is_none() || iproto_type.is_none() || schema_version.is_none() {
             return Err(io::Error::from(io::ErrorKind::InvalidData).into());
         }
@@ -661,6 +889,77 @@ pub fn decode_greeting(stream: &mut impl Read) -> Result<Vec<u8>, Error> {
     Ok(salt)
 }
 
+pub fn decode_id(stream: &mut (impl Read + Seek)) -> Result<ProtocolInfo, Error> {
+    let mut info = ProtocolInfo::default();
+    let map_len = rmp::decode::read_map_len(stream)?;
+    for _ in 0..map_len {
+        let key = rmp::decode::read_pfix(stream)?;
+        match key {
+            VERSION => {
+                info.version = rmp::decode::read_int(stream)?;
+            }
+            FEATURES => {
+                let n_features = rmp::decode::read_array_len(stream)?;
+                for _ in 0..n_features {
+                    let id: u64 = rmp::decode::read_int(stream)?;
+                    if let Some(feature) = ProtocolFeature::from_i64(id as _) {
+                        info.features.push(feature);
+                    }
+                }
+            }
+            _ => {
+                msgpack::skip_value(stream)?;
+            }
+        }
+    }
+    Ok(info)
+}
+
+/// Notification about an update of a watched key, see [`IProtoType::Event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// The watched key.
+    pub key: String,
+    /// Msgpack encoded value associated with the key. Is `None` if the key
+    /// was never broadcast or was broadcast with a `nil` value.
+    pub data: Option<Vec<u8>>,
+}
+
+pub fn decode_event(stream: &mut (impl Read + Seek)) -> Result<Event, Error> {
+    let mut key = None;
+    let mut data = None;
+    let map_len = rmp::decode::read_map_len(stream)?;
+    for _ in 0..map_len {
+        let k = rmp::decode::read_pfix(stream)?;
+        match k {
+            EVENT_KEY => {
+                key = Some(decode_string(stream)?);
+            }
+            EVENT_DATA => {
+                let start = stream.stream_position()?;
+                msgpack::skip_value(stream)?;
+                let end = stream.stream_position()?;
+                stream.seek(io::SeekFrom::Start(start))?;
+                let mut buf = vec![0; (end - start) as usize];
+                stream.read_exact(&mut buf)?;
+                data = Some(buf);
+            }
+            _ => {
+                msgpack::skip_value(stream)?;
+            }
+        }
+    }
+
+    let Some(key) = key else {
+        return Err(ProtocolError::ResponseFieldNotFound {
+            key: "EVENT_KEY",
+            context: "required for EVENT packets",
+        }
+        .into());
+    };
+    Ok(Event { key, data })
+}
+
 pub fn decode_call(buffer: &mut Cursor<Vec<u8>>) -> Result<Tuple, Error> {
     let payload_len = rmp::decode::read_map_len(buffer)?;
     for _ in 0..payload_len {
@@ -723,6 +1022,162 @@ pub fn decode_single_row(buffer: &mut Cursor<Vec<u8>>) -> Result<Option<Tuple>,
     Ok(None)
 }
 
+/// Constant definitions for keys of the column metadata maps in
+/// `IPROTO_METADATA`.
+///
+/// See `enum iproto_metadata_key` in \<tarantool>/src/box/iproto_constants.h
+pub mod metadata_key {
+    pub const NAME: u8 = 0x00;
+    pub const TYPE: u8 = 0x01;
+    pub const COLL: u8 = 0x02;
+    pub const IS_NULLABLE: u8 = 0x03;
+    pub const IS_AUTOINCREMENT: u8 = 0x04;
+    pub const SPAN: u8 = 0x05;
+}
+
+/// Constant definitions for keys of the `IPROTO_SQL_INFO` map.
+///
+/// See `enum sql_info_key` in \<tarantool>/src/box/execute.h
+pub mod sql_info_key {
+    pub const ROW_COUNT: u8 = 0x00;
+    pub const AUTOINCREMENT_IDS: u8 = 0x01;
+}
+
+/// Decodes the body of an `IPROTO_EXECUTE` response.
+pub fn decode_sql_result(buffer: &mut Cursor<impl AsRef<[u8]>>) -> Result<SqlResult, Error> {
+    let mut result = SqlResult::default();
+    let payload_len = rmp::decode::read_map_len(buffer)?;
+    for _ in 0..payload_len {
+        let key = rmp::decode::read_pfix(buffer)?;
+        match key {
+            METADATA => {
+                result.metadata = decode_metadata(buffer)?;
+            }
+            DATA => {
+                let start = buffer.position() as usize;
+                let rows_count = rmp::decode::read_array_len(buffer)? as usize;
+                let mut rows = Vec::with_capacity(rows_count);
+                for _ in 0..rows_count {
+                    let row_start = buffer.position() as usize;
+                    msgpack::skip_value(buffer)?;
+                    rows.push(row_start - start..buffer.position() as usize - start);
+                }
+                let end = buffer.position() as usize;
+                result.data = buffer.get_ref().as_ref()[start..end].to_vec();
+                result.rows = rows;
+            }
+            SQL_INFO => {
+                let info_len = rmp::decode::read_map_len(buffer)?;
+                for _ in 0..info_len {
+                    let key = rmp::decode::read_pfix(buffer)?;
+                    match key {
+                        sql_info_key::ROW_COUNT => {
+                            result.row_count = Some(rmp::decode::read_int(buffer)?);
+                        }
+                        sql_info_key::AUTOINCREMENT_IDS => {
+                            let ids_count = rmp::decode::read_array_len(buffer)? as usize;
+                            let mut ids = Vec::with_capacity(ids_count);
+                            for _ in 0..ids_count {
+                                ids.push(rmp::decode::read_int(buffer)?);
+                            }
+                            result.autoincrement_ids = ids;
+                        }
+                        _ => {
+                            msgpack::skip_value(buffer)?;
+                        }
+                    }
+                }
+            }
+            _ => {
+                msgpack::skip_value(buffer)?;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Information about an SQL statement prepared via `IPROTO_PREPARE`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreparedInfo {
+    /// Id of the prepared statement.
+    pub stmt_id: u32,
+    /// Number of parameters the statement must be executed with.
+    pub bind_count: u64,
+    /// Metadata of the statement parameters. The names are either `"?"` for
+    /// the positional parameters, or the parameter names, e.g. `":ID"`.
+    pub bind_metadata: Vec<ColumnMetadata>,
+    /// Metadata of the result columns. Is empty for DML statements.
+    pub metadata: Vec<ColumnMetadata>,
+}
+
+/// Decodes the body of an `IPROTO_PREPARE` response.
+pub fn decode_prepare(buffer: &mut (impl Read + Seek)) -> Result<PreparedInfo, Error> {
+    let mut info = PreparedInfo::default();
+    let mut stmt_id = None;
+    let payload_len = rmp::decode::read_map_len(buffer)?;
+    for _ in 0..payload_len {
+        let key = rmp::decode::read_pfix(buffer)?;
+        match key {
+            STMT_ID => {
+                stmt_id = Some(rmp::decode::read_int(buffer)?);
+            }
+            BIND_COUNT => {
+                info.bind_count = rmp::decode::read_int(buffer)?;
+            }
+            BIND_METADATA => {
+                info.bind_metadata = decode_metadata(buffer)?;
+            }
+            METADATA => {
+                info.metadata = decode_metadata(buffer)?;
+            }
+            _ => {
+                msgpack::skip_value(buffer)?;
+            }
+        }
+    }
+
+    let Some(stmt_id) = stmt_id else {
+        return Err(ProtocolError::ResponseFieldNotFound {
+            key: "STMT_ID",
+            context: "required for PREPARE responses",
+        }
+        .into());
+    };
+    info.stmt_id = stmt_id;
+    Ok(info)
+}
+
+fn decode_metadata(stream: &mut (impl Read + Seek)) -> Result<Vec<ColumnMetadata>, Error> {
+    let columns_count = rmp::decode::read_array_len(stream)? as usize;
+    let mut columns = Vec::with_capacity(columns_count);
+    for _ in 0..columns_count {
+        columns.push(decode_column_metadata(stream)?);
+    }
+    Ok(columns)
+}
+
+fn decode_column_metadata(stream: &mut (impl Read + Seek)) -> Result<ColumnMetadata, Error> {
+    let mut column = ColumnMetadata::default();
+    let map_len = rmp::decode::read_map_len(stream)?;
+    for _ in 0..map_len {
+        let key = rmp::decode::read_pfix(stream)?;
+        match key {
+            metadata_key::NAME => column.name = decode_string(stream)?,
+            metadata_key::TYPE => column.field_type = decode_string(stream)?,
+            metadata_key::COLL => column.collation = Some(decode_string(stream)?),
+            metadata_key::IS_NULLABLE => column.is_nullable = Some(rmp::decode::read_bool(stream)?),
+            metadata_key::IS_AUTOINCREMENT => {
+                column.is_autoincrement = Some(rmp::decode::read_bool(stream)?)
+            }
+            metadata_key::SPAN => column.span = rmp_serde::from_read(&mut *stream)?,
+            _ => {
+                msgpack::skip_value(stream)?;
+            }
+        }
+    }
+    Ok(column)
+}
+
 pub fn decode_tuple(buffer: &mut Cursor<Vec<u8>>) -> Result<Tuple, Error> {
     let payload_offset = buffer.position();
     msgpack::skip_value(buffer)?;
@@ -741,3 +1196,156 @@ pub fn value_slice(cursor: &mut Cursor<impl AsRef<[u8]>>) -> crate::Result<&[u8]
     msgpack::skip_value(cursor)?;
     Ok(&cursor.get_ref().as_ref()[start..(cursor.position() as usize)])
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// server side
+////////////////////////////////////////////////////////////////////////////////
+
+/// Size of the greeting message, which the server sends right after the
+/// connection is established.
+pub const GREETING_SIZE: usize = 128;
+
+/// Encode the greeting message. The first line contains the server `version`
+/// and the `instance_uuid`, the second one contains the base64 encoded `salt`
+/// used for authentication.
+///
+/// See [tarantool docs](https://www.tarantool.io/en/doc/latest/dev_guide/internals/iproto/authentication/#greeting-message).
+pub fn encode_greeting(
+    stream: &mut impl Write,
+    version: &str,
+    instance_uuid: &str,
+    salt: &[u8],
+) -> Result<(), Error> {
+    const LINE_LEN: usize = GREETING_SIZE / 2 - 1;
+
+    let line = format!("Tarantool {version} (Binary) {instance_uuid}");
+    let salt = base64::encode(salt);
+    if line.len() > LINE_LEN || salt.len() > LINE_LEN {
+        let e = io::Error::new(io::ErrorKind::InvalidInput, "greeting is too long");
+        return Err(e.into());
+    }
+
+    let mut greeting = [b' '; GREETING_SIZE];
+    greeting[..line.len()].copy_from_slice(line.as_bytes());
+    greeting[LINE_LEN] = b'\n';
+    greeting[LINE_LEN + 1..LINE_LEN + 1 + salt.len()].copy_from_slice(salt.as_bytes());
+    greeting[GREETING_SIZE - 1] = b'\n';
+    stream.write_all(&greeting)?;
+    Ok(())
+}
+
+/// Decode an IPROTO request header.
+///
+/// Unlike responses, requests may omit `IPROTO_SYNC` and
+/// `IPROTO_SCHEMA_VERSION`, in which case these are set to `0`.
+pub fn decode_request_header(stream: &mut (impl Read + Seek)) -> Result<Header, Error> {
+    let mut sync = 0;
+    let mut iproto_type = None;
+    let mut schema_version = 0;
+
+    let map_len = rmp::decode::read_map_len(stream)?;
+    for _ in 0..map_len {
+        let key = rmp::decode::read_pfix(stream)?;
+        match key {
+            REQUEST_TYPE => iproto_type = Some(rmp::decode::read_int(stream)?),
+            SYNC => sync = rmp::decode::read_int(stream)?,
+            SCHEMA_VERSION => schema_version = rmp::decode::read_int(stream)?,
+            _ => msgpack::skip_value(stream)?,
+        }
+    }
+
+    let Some(iproto_type) = iproto_type else {
+        return Err(io::Error::from(io::ErrorKind::InvalidData).into());
+    };
+
+    Ok(Header {
+        sync: SyncIndex(sync),
+        iproto_type,
+        error_code: 0,
+        schema_version,
+    })
+}
+
+/// Encode an IPROTO response header. For error responses `response_type`
+/// should be `IProtoType::Error | error_code`, see [`encode_error`].
+pub fn encode_response_header(
+    stream: &mut impl Write,
+    sync: SyncIndex,
+    schema_version: u64,
+    response_type: u32,
+) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 3)?;
+    rmp::encode::write_pfix(stream, REQUEST_TYPE)?;
+    rmp::encode::write_uint(stream, response_type as _)?;
+    rmp::encode::write_pfix(stream, SYNC)?;
+    rmp::encode::write_uint(stream, sync.0)?;
+    rmp::encode::write_pfix(stream, SCHEMA_VERSION)?;
+    rmp::encode::write_uint(stream, schema_version)?;
+    Ok(())
+}
+
+/// Encode the body of an error response. The error is encoded both as a plain
+/// `IPROTO_ERROR_24` message and as an `IPROTO_ERROR` stack including the
+/// error's causes, so it can be decoded with [`decode_error`].
+pub fn encode_error(stream: &mut impl Write, error: &TarantoolError) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 2)?;
+    rmp::encode::write_pfix(stream, ERROR)?;
+    rmp::encode::write_str(stream, error.message())?;
+
+    rmp::encode::write_pfix(stream, ERROR_EXT)?;
+    encode_extended_error(stream, error)
+}
+
+/// Encode the extended error info, i.e. the error stack consisting of the
+/// `error` and all of its causes. This is the reverse of
+/// [`decode_extended_error`].
+///
+/// This is also the payload of the `MP_ERROR` msgpack extension.
+pub fn encode_extended_error(stream: &mut impl Write, error: &TarantoolError) -> Result<(), Error> {
+    rmp::encode::write_map_len(stream, 1)?;
+    rmp::encode::write_pfix(stream, extended_error_keys::STACK)?;
+    let stack_len = std::iter::successors(Some(error), |e| e.cause()).count();
+    rmp::encode::write_array_len(stream, stack_len as _)?;
+    for node in std::iter::successors(Some(error), |e| e.cause()) {
+        encode_error_stack_node(stream, node)?;
+    }
+    Ok(())
+}
+
+/// Encode a single error of an `IPROTO_ERROR` stack. This is the reverse of
+/// [`decode_error_stack_node`].
+pub fn encode_error_stack_node(
+    mut stream: &mut impl Write,
+    error: &TarantoolError,
+) -> Result<(), Error> {
+    let mut map_len = 3;
+    map_len += error.file.is_some() as u32;
+    map_len += error.line.is_some() as u32;
+    map_len += error.errno.is_some() as u32;
+    map_len += !error.fields.is_empty() as u32;
+    rmp::encode::write_map_len(stream, map_len)?;
+
+    rmp::encode::write_pfix(stream, error_field::TYPE)?;
+    rmp::encode::write_str(stream, error.error_type())?;
+    if let Some(file) = &error.file {
+        rmp::encode::write_pfix(stream, error_field::FILE)?;
+        rmp::encode::write_str(stream, file)?;
+    }
+    if let Some(line) = error.line {
+        rmp::encode::write_pfix(stream, error_field::LINE)?;
+        rmp::encode::write_uint(stream, line as _)?;
+    }
+    rmp::encode::write_pfix(stream, error_field::MESSAGE)?;
+    rmp::encode::write_str(stream, error.message())?;
+    if let Some(errno) = error.errno {
+        rmp::encode::write_pfix(stream, error_field::ERRNO)?;
+        rmp::encode::write_uint(stream, errno as _)?;
+    }
+    rmp::encode::write_pfix(stream, error_field::CODE)?;
+    rmp::encode::write_uint(stream, error.code as _)?;
+    if !error.fields.is_empty() {
+        rmp::encode::write_pfix(stream, error_field::FIELDS)?;
+        rmp_serde::encode::write(&mut stream, &error.fields).map_err(Error::other)?;
+    }
+    Ok(())
+}
@@ -13,8 +13,8 @@ pub use codec::*;
 
 use crate::auth::AuthMethod;
 use crate::error;
-use crate::error::TarantoolError;
-use std::collections::HashMap;
+use crate::error::{TarantoolError, TarantoolErrorCode};
+use std::collections::{HashMap, HashSet};
 use std::io::{Cursor, Read, Seek};
 use std::time::Duration;
 
@@ -36,8 +36,22 @@ pub enum ProtocolError {
 
     #[error("{0} is not implemented yet")]
     Unimplemented(String),
+
+    #[error("feature {0:?} is not supported by the peer")]
+    FeatureNotSupported(ProtocolFeature),
 }
 
+/// Version of the iproto protocol implemented by [`Protocol`].
+pub const PROTOCOL_VERSION: u64 = 3;
+
+/// Protocol features supported by [`Protocol`]. These are sent to the server
+/// during the [`IProtoType::Id`] handshake.
+pub const PROTOCOL_FEATURES: &[ProtocolFeature] = &[
+    ProtocolFeature::Streams,
+    ProtocolFeature::Transactions,
+    ProtocolFeature::Watchers,
+];
+
 /// Unique identifier of the sent message on this connection.
 /// It is used to retrieve response for the corresponding request.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -65,6 +79,8 @@ pub type ResponseError = TarantoolError;
 enum State {
     /// Awaits greeting
     Init,
+    /// Awaits protocol features negotiation
+    Id,
     /// Awaits auth
     Auth,
     /// Ready to accept new messages
@@ -81,6 +97,10 @@ pub struct Config {
     pub auth_method: AuthMethod,
     /// Connection establishment timeout.
     pub connect_timeout: Option<Duration>,
+    /// If set, the connection is established over TLS. Only supported for
+    /// tcp connections.
+    #[cfg(feature = "tls")]
+    pub tls: Option<crate::network::client::tls::TlsConfig>,
     // TODO: add buffer limits here
 }
 
@@ -104,6 +124,17 @@ pub struct Protocol {
     /// (user, password)
     creds: Option<(String, String)>,
     auth_method: AuthMethod,
+    /// Salt received in the greeting, used for auth.
+    salt: Vec<u8>,
+    /// Protocol version and features of the server, received during the
+    /// [`IProtoType::Id`] handshake.
+    peer_info: Option<ProtocolInfo>,
+    /// Keys subscribed to via [`Protocol::watch`].
+    watched_keys: HashSet<String>,
+    /// Received notifications about watched keys updates.
+    events: Vec<Event>,
+    /// Schema version from the header of the latest response.
+    schema_version: Option<u64>,
 }
 
 impl Default for Protocol {
@@ -121,6 +152,11 @@ impl Protocol {
             pending_outgoing: Vec::new(),
             creds: None,
             auth_method: AuthMethod::default(),
+            salt: Vec::new(),
+            peer_info: None,
+            watched_keys: HashSet::new(),
+            events: Vec::new(),
+            schema_version: None,
             outgoing: Vec::new(),
             incoming: HashMap::new(),
             // Greeting is exactly 128 bytes
@@ -136,6 +172,25 @@ impl Protocol {
         protocol
     }
 
+    /// Returns protocol version and features supported by the server.
+    ///
+    /// Returns `None` if the [`IProtoType::Id`] handshake hasn't finished yet.
+    /// If the server doesn't support the handshake, [`ProtocolInfo::version`]
+    /// is `0` and [`ProtocolInfo::features`] is empty.
+    #[inline(always)]
+    pub fn peer_protocol_info(&self) -> Option<&ProtocolInfo> {
+        self.peer_info.as_ref()
+    }
+
+    /// Returns the schema version of the server received in the latest
+    /// response.
+    ///
+    /// Returns `None` if no responses were received yet.
+    #[inline(always)]
+    pub fn schema_version(&self) -> Option<u64> {
+        self.schema_version
+    }
+
     /// Returns `true` if the [`Protocol`] has passed initialization and authorization
     /// stages.
     ///
@@ -167,6 +222,40 @@ impl Protocol {
         Ok(self.sync.next_index())
     }
 
+    /// Subscribes to updates of `key` broadcast by the server via
+    /// `box.broadcast`. Does nothing if the key is already watched.
+    ///
+    /// Notifications are retrieved with [`Protocol::take_events`]. Each
+    /// received notification is acknowledged automatically.
+    ///
+    /// Like other requests, the subscription is queued if the protocol is not
+    /// [`Self::is_ready`] yet.
+    pub fn watch(&mut self, key: &str) -> Result<(), error::Error> {
+        if self.watched_keys.contains(key) {
+            return Ok(());
+        }
+        self.send_request(&api::Watch { key })?;
+        self.watched_keys.insert(key.into());
+        Ok(())
+    }
+
+    /// Unsubscribes from updates of `key`. Does nothing if the key isn't
+    /// watched.
+    pub fn unwatch(&mut self, key: &str) -> Result<(), error::Error> {
+        if !self.watched_keys.contains(key) {
+            return Ok(());
+        }
+        self.send_request(&api::Unwatch { key })?;
+        self.watched_keys.remove(key);
+        Ok(())
+    }
+
+    /// Returns notifications about updates of the watched keys received since
+    /// the last call, leaving the buffer empty.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
     /// Take existing response by [`SyncIndex`].
     pub fn take_response<R: Request>(
         &mut self,
@@ -231,13 +320,43 @@ impl Protocol {
     ) -> Result<Option<SyncIndex>, error::Error> {
         let sync = match self.state {
             State::Init => {
-                let salt = codec::decode_greeting(message)?;
+                self.salt = codec::decode_greeting(message)?;
+                self.state = State::Id;
+                // Write straight to outgoing, it should be empty
+                debug_assert!(self.outgoing.is_empty());
+                let mut buf = Cursor::new(&mut self.outgoing);
+                let sync = self.sync.next_index();
+                write_to_buffer(
+                    &mut buf,
+                    sync,
+                    &api::Id {
+                        version: PROTOCOL_VERSION,
+                        features: PROTOCOL_FEATURES,
+                    },
+                )?;
+                None
+            }
+            State::Id => {
+                let header = codec::Header::decode(message)?;
+                self.schema_version = Some(header.schema_version);
+                let info = if header.iproto_type == IProtoType::Error as u32 {
+                    let error = codec::decode_error(message, &header)?;
+                    if error.code != TarantoolErrorCode::UnknownRequestType as u32 {
+                        return Err(error::Error::Remote(error));
+                    }
+                    // Server is too old to support the handshake
+                    ProtocolInfo::default()
+                } else {
+                    codec::decode_id(message)?
+                };
+                self.peer_info = Some(info);
+
                 if let Some((user, pass)) = self.creds.as_ref() {
                     // Auth
                     self.state = State::Auth;
-                    // Write straight to outgoing, it should be empty
-                    debug_assert!(self.outgoing.is_empty());
+                    let end = self.outgoing.len();
                     let mut buf = Cursor::new(&mut self.outgoing);
+                    buf.set_position(end as u64);
                     let sync = self.sync.next_index();
                     write_to_buffer(
                         &mut buf,
@@ -245,7 +364,7 @@ impl Protocol {
                         &api::Auth {
                             user,
                             pass,
-                            salt: &salt,
+                            salt: &self.salt,
                             method: self.auth_method,
                         },
                     )?;
@@ -257,6 +376,7 @@ impl Protocol {
             }
             State::Auth => {
                 let header = codec::Header::decode(message)?;
+                self.schema_version = Some(header.schema_version);
                 if header.iproto_type == IProtoType::Error as u32 {
                     let error = codec::decode_error(message, &header)?;
                     return Err(error::Error::Remote(error));
@@ -266,6 +386,17 @@ impl Protocol {
             }
             State::Ready => {
                 let header = codec::Header::decode(message)?;
+                if header.iproto_type == IProtoType::Event as u32 {
+                    let event = codec::decode_event(message)?;
+                    if self.watched_keys.contains(&event.key) {
+                        // Acknowledge the event, so that the server sends
+                        // the next one
+                        self.send_request(&api::Watch { key: &event.key })?;
+                        self.events.push(event);
+                    }
+                    return Ok(None);
+                }
+                self.schema_version = Some(header.schema_version);
                 let response;
                 if header.iproto_type == IProtoType::Error as u32 {
                     response = Err(codec::decode_error(message, &header)?);
@@ -346,6 +477,44 @@ mod tests {
         greeting
     }
 
+    /// Encodes a response header and `body` into a message which can be
+    /// passed to [`Protocol::process_incoming`] in two chunks: the message
+    /// size and the message itself.
+    fn fake_response(sync: u64, iproto_type: u32, body: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut message = Vec::new();
+        rmp::encode::write_map_len(&mut message, 3).unwrap();
+        rmp::encode::write_pfix(&mut message, iproto_key::REQUEST_TYPE).unwrap();
+        rmp::encode::write_uint(&mut message, iproto_type as _).unwrap();
+        rmp::encode::write_pfix(&mut message, iproto_key::SYNC).unwrap();
+        rmp::encode::write_uint(&mut message, sync).unwrap();
+        rmp::encode::write_pfix(&mut message, iproto_key::SCHEMA_VERSION).unwrap();
+        rmp::encode::write_uint(&mut message, 1).unwrap();
+        message.extend_from_slice(body);
+
+        let mut size = Vec::new();
+        rmp::encode::write_u32(&mut size, message.len() as _).unwrap();
+        (size, message)
+    }
+
+    fn fake_id_response(version: u64, features: &[u64]) -> (Vec<u8>, Vec<u8>) {
+        let mut body = Vec::new();
+        rmp::encode::write_map_len(&mut body, 2).unwrap();
+        rmp::encode::write_pfix(&mut body, iproto_key::VERSION).unwrap();
+        rmp::encode::write_uint(&mut body, version).unwrap();
+        rmp::encode::write_pfix(&mut body, iproto_key::FEATURES).unwrap();
+        rmp::encode::write_array_len(&mut body, features.len() as _).unwrap();
+        for &feature in features {
+            rmp::encode::write_uint(&mut body, feature).unwrap();
+        }
+        fake_response(0, IProtoType::Ok as _, &body)
+    }
+
+    fn process_response(conn: &mut Protocol, (size, message): (Vec<u8>, Vec<u8>)) {
+        let res = conn.process_incoming(&mut Cursor::new(size)).unwrap();
+        assert_eq!(res, None);
+        conn.process_incoming(&mut Cursor::new(message)).unwrap();
+    }
+
     #[crate::test(tarantool = "crate")]
     fn connection_established() {
         let mut conn = Protocol::new();
@@ -356,7 +525,15 @@ mod tests {
             .unwrap();
         assert_eq!(conn.msg_size_hint, None);
         assert_eq!(conn.read_size_hint(), 5);
-        assert!(conn.is_ready())
+        // Id request is sent right after the greeting
+        assert!(!conn.is_ready());
+        assert!(conn.ready_outgoing_len() > 0);
+        conn.take_outgoing_data();
+        assert_eq!(conn.schema_version(), None);
+
+        process_response(&mut conn, fake_id_response(3, &[0, 1]));
+        assert!(conn.is_ready());
+        assert_eq!(conn.schema_version(), Some(1));
     }
 
     #[crate::test(tarantool = "crate")]
@@ -364,7 +541,115 @@ mod tests {
         let mut conn = Protocol::new();
         conn.process_incoming(&mut Cursor::new(fake_greeting()))
             .unwrap();
+        conn.take_outgoing_data();
         conn.send_request(&api::Ping).unwrap();
+        // Requests are queued until the handshake is done
+        assert_eq!(conn.ready_outgoing_len(), 0);
+        process_response(&mut conn, fake_id_response(3, &[0, 1]));
+        assert!(conn.ready_outgoing_len() > 0);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn id_handshake() {
+        let mut conn = Protocol::new();
+        assert_eq!(conn.peer_protocol_info(), None);
+        conn.process_incoming(&mut Cursor::new(fake_greeting()))
+            .unwrap();
+
+        let mut request = Cursor::new(conn.take_outgoing_data());
+        let _size = rmp::decode::read_u32(&mut request).unwrap();
+        let _header_len = rmp::decode::read_map_len(&mut request).unwrap();
+        let key = rmp::decode::read_pfix(&mut request).unwrap();
+        assert_eq!(key, iproto_key::REQUEST_TYPE);
+        let iproto_type: u32 = rmp::decode::read_int(&mut request).unwrap();
+        assert_eq!(iproto_type, IProtoType::Id as u32);
+
+        // Unknown features are ignored
+        process_response(&mut conn, fake_id_response(6, &[0, 1, 3, 4, 999]));
+        let info = conn.peer_protocol_info().unwrap();
+        assert_eq!(info.version, 6);
+        assert_eq!(
+            info.features,
+            [
+                ProtocolFeature::Streams,
+                ProtocolFeature::Transactions,
+                ProtocolFeature::Watchers,
+                ProtocolFeature::Pagination,
+            ]
+        );
+        assert!(info.supports(ProtocolFeature::Watchers));
+        assert!(!info.supports(ProtocolFeature::ErrorExtension));
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn watch_events() {
+        let mut conn = Protocol::new();
+        conn.process_incoming(&mut Cursor::new(fake_greeting()))
+            .unwrap();
+        conn.take_outgoing_data();
+        process_response(&mut conn, fake_id_response(3, &[0, 1, 3]));
+
+        conn.watch("foo").unwrap();
+        assert!(conn.ready_outgoing_len() > 0);
+        conn.take_outgoing_data();
+        // Already watched
+        conn.watch("foo").unwrap();
+        assert_eq!(conn.ready_outgoing_len(), 0);
+
+        let fake_event = |key: &str, data: Option<&str>| {
+            let mut body = Vec::new();
+            rmp::encode::write_map_len(&mut body, 1 + data.is_some() as u32).unwrap();
+            rmp::encode::write_pfix(&mut body, iproto_key::EVENT_KEY).unwrap();
+            rmp::encode::write_str(&mut body, key).unwrap();
+            if let Some(data) = data {
+                rmp::encode::write_pfix(&mut body, iproto_key::EVENT_DATA).unwrap();
+                rmp::encode::write_str(&mut body, data).unwrap();
+            }
+            fake_response(0, IProtoType::Event as _, &body)
+        };
+
+        process_response(&mut conn, fake_event("foo", None));
+        process_response(&mut conn, fake_event("foo", Some("bar")));
+        // Not watched keys are ignored
+        process_response(&mut conn, fake_event("baz", Some("bar")));
+        assert_eq!(
+            conn.take_events(),
+            [
+                Event {
+                    key: "foo".into(),
+                    data: None,
+                },
+                Event {
+                    key: "foo".into(),
+                    data: Some(b"\xa3bar".to_vec()),
+                },
+            ]
+        );
+        assert!(conn.take_events().is_empty());
+        // Events are acknowledged
+        assert!(conn.ready_outgoing_len() > 0);
+        conn.take_outgoing_data();
+
+        conn.unwatch("foo").unwrap();
         assert!(conn.ready_outgoing_len() > 0);
+        process_response(&mut conn, fake_event("foo", Some("bar")));
+        assert!(conn.take_events().is_empty());
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn id_not_supported_by_server() {
+        let mut conn = Protocol::new();
+        conn.process_incoming(&mut Cursor::new(fake_greeting()))
+            .unwrap();
+
+        let mut body = Vec::new();
+        rmp::encode::write_map_len(&mut body, 1).unwrap();
+        rmp::encode::write_pfix(&mut body, iproto_key::ERROR).unwrap();
+        rmp::encode::write_str(&mut body, "Unknown request type 73").unwrap();
+        let iproto_type = IProtoType::Error as u32 | TarantoolErrorCode::UnknownRequestType as u32;
+        process_response(&mut conn, fake_response(0, iproto_type, &body));
+
+        assert!(conn.is_ready());
+        assert_eq!(conn.peer_protocol_info(), Some(&ProtocolInfo::default()));
     }
 }
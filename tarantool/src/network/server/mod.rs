@@ -0,0 +1,668 @@
+//! Fiber based IPROTO server.
+//!
+//! A [`Server`] accepts connections on a [`CoIOListener`], sends the greeting,
+//! authenticates users with the 'chap-sha1' method and dispatches `CALL`,
+//! `EVAL` and `SELECT` requests to a user provided [`Handler`]. `PING` and
+//! `ID` requests are answered by the server itself.
+//!
+//! Each connection is served in a separate fiber, requests from a single
+//! connection are processed sequentially. A handler may yield, e.g. to forward
+//! the request to another instance with a [`Client`].
+//!
+//! This is useful for building proxies speaking the binary protocol and test
+//! doubles for the client code without running Lua.
+//!
+//! # Example
+//! ```no_run
+//! use tarantool::error::BoxError;
+//! use tarantool::network::server::{request, Context, Handler, Server};
+//! use tarantool::tuple::Tuple;
+//!
+//! struct Echo;
+//!
+//! impl Handler for Echo {
+//!     fn call(&self, _: &Context, request: &request::Call) -> Result<Tuple, BoxError> {
+//!         Ok(request.args.clone())
+//!     }
+//! }
+//!
+//! let server = Server::bind("127.0.0.1:3302", Echo)
+//!     .unwrap()
+//!     .user("admin", "secret");
+//! let handle = server.start().unwrap();
+//! // ...
+//! handle.stop();
+//! ```
+//!
+//! [`Client`]: super::client::Client
+
+pub mod request;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, Cursor, Read, Write};
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::rc::Rc;
+
+use crate::auth::AuthMethod;
+use crate::coio::{CoIOListener, CoIOStream};
+use crate::error::{BoxError, Error, TarantoolErrorCode};
+use crate::fiber::{self, FiberId};
+use crate::network::protocol::codec::{self, IProtoType};
+use crate::network::protocol::{SyncIndex, PROTOCOL_VERSION};
+use crate::tuple::{ToTupleBuffer, Tuple};
+
+/// Tarantool version reported in the greeting. Clients use it to determine
+/// which protocol extensions are supported, so it should be recent enough for
+/// the `IPROTO_ID` request to be sent.
+pub const GREETING_VERSION: &str = "2.11.0";
+
+/// Name of the user of unauthenticated connections.
+pub const GUEST: &str = "guest";
+
+/// Handles requests received by a [`Server`].
+///
+/// All methods return an error by default, so only the needed requests must be
+/// implemented. The returned errors are sent back to the client.
+pub trait Handler {
+    /// Handles an `IPROTO_CALL` request. The returned tuple contains the
+    /// values returned by the function.
+    fn call(&self, ctx: &Context, request: &request::Call) -> Result<Tuple, BoxError> {
+        let _ = ctx;
+        Err(BoxError::new(
+            TarantoolErrorCode::NoSuchProc,
+            format!("Procedure '{}' is not defined", request.function_name),
+        ))
+    }
+
+    /// Handles an `IPROTO_EVAL` request. The returned tuple contains the
+    /// values returned by the expression.
+    fn eval(&self, ctx: &Context, request: &request::Eval) -> Result<Tuple, BoxError> {
+        let _ = (ctx, request);
+        Err(unsupported(IProtoType::Eval))
+    }
+
+    /// Handles an `IPROTO_SELECT` request.
+    fn select(&self, ctx: &Context, request: &request::Select) -> Result<Vec<Tuple>, BoxError> {
+        let _ = (ctx, request);
+        Err(unsupported(IProtoType::Select))
+    }
+}
+
+#[inline]
+fn unsupported(request_type: IProtoType) -> BoxError {
+    BoxError::new(
+        TarantoolErrorCode::Unsupported,
+        format!("{request_type:?} requests are not supported by this server"),
+    )
+}
+
+/// Information about the connection a request is received on.
+#[derive(Debug, Clone)]
+pub struct Context {
+    connection_id: u64,
+    user: String,
+    sync: SyncIndex,
+}
+
+impl Context {
+    /// Returns the id of the connection unique within the [`Server`].
+    #[inline(always)]
+    pub fn connection_id(&self) -> u64 {
+        self.connection_id
+    }
+
+    /// Returns the name of the authenticated user or [`GUEST`].
+    #[inline(always)]
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Returns the sync of the request being handled.
+    #[inline(always)]
+    pub fn sync(&self) -> SyncIndex {
+        self.sync
+    }
+}
+
+/// IPROTO server.
+///
+/// See [module level documentation](self) for details.
+pub struct Server {
+    listener: CoIOListener,
+    local_addr: SocketAddr,
+    handler: Box<dyn Handler>,
+    /// user -> password
+    users: HashMap<String, String>,
+}
+
+impl Server {
+    /// Creates a server listening on `addr`.
+    pub fn bind(addr: impl ToSocketAddrs, handler: impl Handler + 'static) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        Ok(Self {
+            listener: CoIOListener::try_from(listener)?,
+            local_addr,
+            handler: Box::new(handler),
+            users: HashMap::new(),
+        })
+    }
+
+    /// Allows the `user` to authenticate with the `password`.
+    ///
+    /// If at least one user is added, requests other than `PING`, `ID` and
+    /// `AUTH` are rejected on unauthenticated connections.
+    #[inline]
+    pub fn user(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.users.insert(user.into(), password.into());
+        self
+    }
+
+    /// Returns the address the server is listening on.
+    #[inline(always)]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Starts accepting connections in a separate fiber.
+    ///
+    /// The server is stopped once the returned handle is dropped, see
+    /// [`ServerHandle::stop`].
+    pub fn start(self) -> crate::Result<ServerHandle> {
+        let Self {
+            listener,
+            local_addr,
+            handler,
+            users,
+        } = self;
+        let shared = Rc::new(Shared {
+            handler,
+            users,
+            instance_uuid: uuid::Uuid::from_bytes(random_bytes()?).to_string(),
+            last_connection_id: Cell::new(0),
+            connections: Default::default(),
+            is_stopped: Cell::new(false),
+        });
+        let shared_in_fiber = shared.clone();
+        let fiber_id = fiber::Builder::new()
+            .name(format!("iproto-server-{local_addr}"))
+            .func(move || accept_loop(listener, shared_in_fiber))
+            .start_non_joinable()?;
+        Ok(ServerHandle {
+            local_addr,
+            fiber_id,
+            shared,
+        })
+    }
+}
+
+struct Shared {
+    handler: Box<dyn Handler>,
+    /// user -> password
+    users: HashMap<String, String>,
+    instance_uuid: String,
+    last_connection_id: Cell<u64>,
+    /// Sockets of the open connections, used for closing them when the
+    /// server is stopped.
+    connections: RefCell<HashMap<u64, RawFd>>,
+    is_stopped: Cell<bool>,
+}
+
+/// Handle of a started [`Server`].
+///
+/// The server is stopped once the handle is dropped.
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    fiber_id: FiberId,
+    shared: Rc<Shared>,
+}
+
+impl ServerHandle {
+    /// Returns the address the server is listening on.
+    #[inline(always)]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the number of currently open connections.
+    #[inline]
+    pub fn connection_count(&self) -> usize {
+        self.shared.connections.borrow().len()
+    }
+
+    /// Stops accepting new connections and closes the open ones.
+    ///
+    /// Requests which are being handled at the moment are not interrupted,
+    /// but the responses to them are not sent.
+    #[inline(always)]
+    pub fn stop(self) {
+        drop(self)
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.shared.is_stopped.set(true);
+        fiber::cancel(self.fiber_id);
+        fiber::wakeup(self.fiber_id);
+        for &fd in self.shared.connections.borrow().values() {
+            // SAFETY: the socket is closed by the connection fiber after it's
+            // removed from the `connections`.
+            unsafe { libc::shutdown(fd, libc::SHUT_RDWR) };
+        }
+    }
+}
+
+fn accept_loop(listener: CoIOListener, shared: Rc<Shared>) {
+    loop {
+        let res = listener.accept();
+        if shared.is_stopped.get() || fiber::is_cancelled() {
+            break;
+        }
+        let stream = match res {
+            Ok(stream) => stream,
+            Err(e) => {
+                crate::say_warn!("failed accepting an iproto connection: {e}");
+                continue;
+            }
+        };
+
+        let connection_id = shared.last_connection_id.get() + 1;
+        shared.last_connection_id.set(connection_id);
+        let shared = shared.clone();
+        let res = fiber::Builder::new()
+            .name(format!("iproto-connection-{connection_id}"))
+            .func(move || {
+                let res = Connection::new(connection_id, stream, &shared).serve();
+                if let Err(e) = res {
+                    if !shared.is_stopped.get() {
+                        crate::say_verbose!("iproto connection {connection_id} closed: {e}");
+                    }
+                }
+            })
+            .start_non_joinable();
+        if let Err(e) = res {
+            crate::say_warn!("failed starting an iproto connection fiber: {e}");
+        }
+    }
+}
+
+/// Reads random bytes used for the salt and the instance uuid.
+fn random_bytes<const N: usize>() -> io::Result<[u8; N]> {
+    let mut res = [0; N];
+    File::open("/dev/urandom")?.read_exact(&mut res)?;
+    Ok(res)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Connection
+////////////////////////////////////////////////////////////////////////////////
+
+struct Connection<'s> {
+    stream: CoIOStream,
+    shared: &'s Shared,
+    salt: [u8; 32],
+    ctx: Context,
+}
+
+impl<'s> Connection<'s> {
+    fn new(connection_id: u64, stream: CoIOStream, shared: &'s Shared) -> Self {
+        shared
+            .connections
+            .borrow_mut()
+            .insert(connection_id, stream.as_raw_fd());
+        Self {
+            stream,
+            shared,
+            salt: [0; 32],
+            ctx: Context {
+                connection_id,
+                user: GUEST.into(),
+                sync: SyncIndex(0),
+            },
+        }
+    }
+
+    fn serve(mut self) -> Result<(), Error> {
+        self.salt = random_bytes()?;
+        let mut greeting = Vec::with_capacity(codec::GREETING_SIZE);
+        codec::encode_greeting(
+            &mut greeting,
+            GREETING_VERSION,
+            &self.shared.instance_uuid,
+            &self.salt,
+        )?;
+        self.stream.write_all(&greeting)?;
+
+        loop {
+            // Size of the message is a msgpack unsigned integer of any width,
+            // so read the marker first to find out how many bytes follow
+            let mut marker = [0; 1];
+            match self.stream.read_exact(&mut marker) {
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                res => res?,
+            }
+            let size: u32 = rmp::decode::read_int(&mut (&marker[..]).chain(&mut self.stream))?;
+            let mut message = vec![0; size as usize];
+            self.stream.read_exact(&mut message)?;
+
+            let mut message = Cursor::new(message);
+            let header = codec::decode_request_header(&mut message)?;
+            let response = self.process_request(&header, &mut message)?;
+            self.stream.write_all(&response)?;
+        }
+    }
+
+    /// Returns the encoded response to the request.
+    fn process_request(
+        &mut self,
+        header: &codec::Header,
+        body: &mut Cursor<Vec<u8>>,
+    ) -> Result<Vec<u8>, Error> {
+        self.ctx.sync = header.sync;
+
+        let mut response = Vec::new();
+        // Message size placeholder
+        rmp::encode::write_u32(&mut response, 0)?;
+        let mut body_buf = Vec::new();
+        let response_type = match self.handle_request(header.iproto_type, body, &mut body_buf) {
+            Ok(()) => IProtoType::Ok as u32,
+            Err(e) => {
+                body_buf.clear();
+                codec::encode_error(&mut body_buf, &e)?;
+                IProtoType::Error as u32 | e.error_code()
+            }
+        };
+        codec::encode_response_header(&mut response, header.sync, 0, response_type)?;
+        response.extend_from_slice(&body_buf);
+
+        let size = (response.len() - 5) as u32;
+        rmp::encode::write_u32(&mut &mut response[..5], size)?;
+        Ok(response)
+    }
+
+    /// Writes the body of a successful response to `out`.
+    fn handle_request(
+        &mut self,
+        request_type: u32,
+        body: &mut Cursor<Vec<u8>>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), BoxError> {
+        const PING: u32 = IProtoType::Ping as _;
+        const ID: u32 = IProtoType::Id as _;
+        const AUTH: u32 = IProtoType::Auth as _;
+        const CALL: u32 = IProtoType::Call as _;
+        const EVAL: u32 = IProtoType::Eval as _;
+        const SELECT: u32 = IProtoType::Select as _;
+
+        let handler = &self.shared.handler;
+        match request_type {
+            PING => encode(|| codec::encode_ping(out)),
+            ID => encode(|| codec::encode_id(out, PROTOCOL_VERSION, &[])),
+            AUTH => {
+                let request = request::Auth::decode(body)?;
+                self.authenticate(request)?;
+                encode(|| codec::encode_ping(out))
+            }
+            CALL => {
+                let request = request::Call::decode(body)?;
+                self.check_access(IProtoType::Call)?;
+                let res = handler.call(&self.ctx, &request)?;
+                encode(|| encode_data(out, &res))
+            }
+            EVAL => {
+                let request = request::Eval::decode(body)?;
+                self.check_access(IProtoType::Eval)?;
+                let res = handler.eval(&self.ctx, &request)?;
+                encode(|| encode_data(out, &res))
+            }
+            SELECT => {
+                let request = request::Select::decode(body)?;
+                self.check_access(IProtoType::Select)?;
+                let rows = handler.select(&self.ctx, &request)?;
+                encode(|| encode_rows(out, &rows))
+            }
+            _ => Err(BoxError::new(
+                TarantoolErrorCode::UnknownRequestType,
+                format!("Unknown request type {request_type}"),
+            )),
+        }
+    }
+
+    fn authenticate(&mut self, request: request::Auth) -> Result<(), BoxError> {
+        if request.method != AuthMethod::ChapSha1.as_str() {
+            return Err(BoxError::new(
+                TarantoolErrorCode::UnknownAuthMethod,
+                format!("Unknown authentication method '{}'", request.method),
+            ));
+        }
+        let Some(password) = self.shared.users.get(&request.user) else {
+            return Err(password_mismatch());
+        };
+        if request.scramble != codec::chap_sha1_scramble(password, &self.salt) {
+            return Err(password_mismatch());
+        }
+        self.ctx.user = request.user;
+        Ok(())
+    }
+
+    fn check_access(&self, request_type: IProtoType) -> Result<(), BoxError> {
+        if self.ctx.user == GUEST && !self.shared.users.is_empty() {
+            return Err(BoxError::new(
+                TarantoolErrorCode::AccessDenied,
+                format!("{request_type:?} access is denied for user '{GUEST}'"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Connection<'_> {
+    fn drop(&mut self) {
+        // This runs before the stream is closed and also if the handler
+        // panics, so the server never shuts down a stale socket
+        self.shared
+            .connections
+            .borrow_mut()
+            .remove(&self.ctx.connection_id);
+    }
+}
+
+#[inline]
+fn password_mismatch() -> BoxError {
+    BoxError::new(
+        TarantoolErrorCode::PasswordMismatch,
+        "User not found or supplied credentials are invalid",
+    )
+}
+
+#[inline]
+fn encode(f: impl FnOnce() -> Result<(), Error>) -> Result<(), BoxError> {
+    f().map_err(|e| BoxError::new(TarantoolErrorCode::ProcC, e.to_string()))
+}
+
+fn encode_data(out: &mut Vec<u8>, data: &Tuple) -> Result<(), Error> {
+    rmp::encode::write_map_len(out, 1)?;
+    rmp::encode::write_pfix(out, codec::iproto_key::DATA)?;
+    data.write_tuple_data(out)?;
+    Ok(())
+}
+
+fn encode_rows(out: &mut Vec<u8>, rows: &[Tuple]) -> Result<(), Error> {
+    rmp::encode::write_map_len(out, 1)?;
+    rmp::encode::write_pfix(out, codec::iproto_key::DATA)?;
+    rmp::encode::write_array_len(out, rows.len() as _)?;
+    for row in rows {
+        row.write_tuple_data(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::fiber::r#async::timeout::IntoTimeout as _;
+    use crate::index::IteratorType;
+    use crate::network::client::{AsClient as _, Client, ClientError};
+    use crate::network::protocol;
+    use std::time::Duration;
+
+    const _3_SEC: Duration = Duration::from_secs(3);
+
+    struct TestHandler;
+
+    impl Handler for TestHandler {
+        fn call(&self, ctx: &Context, request: &request::Call) -> Result<Tuple, BoxError> {
+            match &*request.function_name {
+                "echo" => Ok(request.args.clone()),
+                "whoami" => Ok(Tuple::new(&(ctx.user(),)).unwrap()),
+                "fail" => Err(BoxError::new(TarantoolErrorCode::ProcC, "failed")),
+                name => Err(BoxError::new(
+                    TarantoolErrorCode::NoSuchProc,
+                    format!("Procedure '{name}' is not defined"),
+                )),
+            }
+        }
+
+        fn select(&self, _: &Context, request: &request::Select) -> Result<Vec<Tuple>, BoxError> {
+            assert_eq!(request.iterator_type, IteratorType::GE);
+            let (from,): (u32,) = request.key.decode().unwrap();
+            let rows = (from..)
+                .skip(request.offset as _)
+                .take(request.limit as _)
+                .map(|i| Tuple::new(&(i, request.space_id)).unwrap())
+                .collect();
+            Ok(rows)
+        }
+    }
+
+    fn start_server(users: &[(&str, &str)]) -> ServerHandle {
+        let mut server = Server::bind("127.0.0.1:0", TestHandler).unwrap();
+        for (user, password) in users {
+            server = server.user(*user, *password);
+        }
+        server.start().unwrap()
+    }
+
+    async fn connect(server: &ServerHandle, creds: Option<(&str, &str)>) -> Client {
+        let addr = server.local_addr();
+        Client::connect_with_config(
+            &addr.ip().to_string(),
+            addr.port(),
+            protocol::Config {
+                creds: creds.map(|(u, p)| (u.into(), p.into())),
+                ..Default::default()
+            },
+        )
+        .timeout(_3_SEC)
+        .await
+        .unwrap()
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn requests() {
+        let server = start_server(&[]);
+        let client = connect(&server, None).await;
+
+        client.ping().timeout(_3_SEC).await.unwrap();
+        let info = client.protocol_info().timeout(_3_SEC).await.unwrap();
+        assert_eq!(info.version, PROTOCOL_VERSION);
+        assert!(info.features.is_empty());
+
+        let res = client
+            .call("echo", &(1, "two"))
+            .timeout(_3_SEC)
+            .await
+            .unwrap();
+        assert_eq!(res.decode::<(u32, String)>().unwrap(), (1, "two".into()));
+
+        let res = client.call("whoami", &()).timeout(_3_SEC).await.unwrap();
+        assert_eq!(res.decode::<(String,)>().unwrap(), (GUEST.into(),));
+
+        let request = protocol::api::Select {
+            space_id: 512,
+            index_id: 0,
+            limit: 2,
+            offset: 1,
+            iterator_type: IteratorType::GE,
+            key: &(10,),
+        };
+        let rows = client.send(&request).timeout(_3_SEC).await.unwrap();
+        let rows: Vec<(u32, u32)> = rows.iter().map(|t| t.decode().unwrap()).collect();
+        assert_eq!(rows, [(11, 512), (12, 512)]);
+
+        // Errors are sent back to the client
+        let err = client.call("fail", &()).await.unwrap_err();
+        let ClientError::ErrorResponse(e) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(e.error_code(), TarantoolErrorCode::ProcC as u32);
+        assert_eq!(e.message(), "failed");
+        assert_eq!(e.file(), Some(file!()));
+
+        let err = client.eval("return 1", &()).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "server responded with error: Unsupported: Eval requests are not supported by this server"
+        );
+
+        // The connection is still usable after errors
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(server.connection_count(), 1);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn authentication() {
+        let server = start_server(&[("alice", "secret")]);
+
+        let client = connect(&server, Some(("alice", "secret"))).await;
+        let res = client.call("whoami", &()).timeout(_3_SEC).await.unwrap();
+        assert_eq!(res.decode::<(String,)>().unwrap(), ("alice".into(),));
+
+        let client = connect(&server, Some(("alice", "wrong"))).await;
+        let err = client.ping().await.unwrap_err();
+        #[rustfmt::skip]
+        assert_eq!(err.to_string(), "server responded with error: PasswordMismatch: User not found or supplied credentials are invalid");
+
+        let client = connect(&server, Some(("bob", "secret"))).await;
+        let err = client.ping().await.unwrap_err();
+        #[rustfmt::skip]
+        assert_eq!(err.to_string(), "server responded with error: PasswordMismatch: User not found or supplied credentials are invalid");
+
+        // Guest can only ping
+        let client = connect(&server, None).await;
+        client.ping().timeout(_3_SEC).await.unwrap();
+        let err = client.call("whoami", &()).await.unwrap_err();
+        #[rustfmt::skip]
+        assert_eq!(err.to_string(), "server responded with error: AccessDenied: Call access is denied for user 'guest'");
+    }
+
+    #[crate::test(tarantool = "crate")]
+    async fn stop() {
+        let server = start_server(&[]);
+        let addr = server.local_addr();
+        let client = connect(&server, None).await;
+        client.ping().timeout(_3_SEC).await.unwrap();
+        assert_eq!(server.connection_count(), 1);
+
+        server.stop();
+        let err = client.ping().timeout(_3_SEC).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                crate::fiber::r#async::timeout::Error::Failed(ClientError::ConnectionClosed(_))
+            ),
+            "{}",
+            err
+        );
+
+        let res = Client::connect(&addr.ip().to_string(), addr.port())
+            .timeout(_3_SEC)
+            .await;
+        assert!(res.is_err());
+    }
+}
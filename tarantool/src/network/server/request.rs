@@ -0,0 +1,221 @@
+//! Decoded bodies of the requests dispatched to a [`Handler`].
+//!
+//! [`Handler`]: super::Handler
+
+use std::io::Cursor;
+
+use crate::error::{BoxError, Error, TarantoolErrorCode};
+use crate::index::{IndexId, IteratorType};
+use crate::msgpack;
+use crate::network::protocol::codec::iproto_key::*;
+use crate::network::protocol::codec::{decode_string, value_slice};
+use crate::space::SpaceId;
+use crate::tuple::Tuple;
+
+/// Body of an `IPROTO_CALL` request.
+#[derive(Debug)]
+pub struct Call {
+    /// Name of the called function.
+    pub function_name: String,
+    /// Arguments of the call.
+    pub args: Tuple,
+}
+
+/// Body of an `IPROTO_EVAL` request.
+#[derive(Debug)]
+pub struct Eval {
+    /// The evaluated expression.
+    pub expr: String,
+    /// Arguments of the expression.
+    pub args: Tuple,
+}
+
+/// Body of an `IPROTO_SELECT` request.
+#[derive(Debug)]
+pub struct Select {
+    pub space_id: SpaceId,
+    pub index_id: IndexId,
+    pub limit: u32,
+    pub offset: u32,
+    pub iterator_type: IteratorType,
+    pub key: Tuple,
+}
+
+/// Body of an `IPROTO_AUTH` request.
+#[derive(Debug)]
+pub(crate) struct Auth {
+    pub user: String,
+    pub method: String,
+    pub scramble: Vec<u8>,
+}
+
+impl Call {
+    pub(crate) fn decode(body: &mut Cursor<Vec<u8>>) -> Result<Self, BoxError> {
+        let mut function_name = None;
+        let mut args = None;
+        decode_map(body, |key, body| {
+            match key {
+                FUNCTION_NAME => function_name = Some(decode_string(body)?),
+                TUPLE => args = Some(decode_tuple(body)?),
+                _ => msgpack::skip_value(body)?,
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            function_name: required(function_name, "FUNCTION_NAME")?,
+            args: args.unwrap_or_else(empty_tuple),
+        })
+    }
+}
+
+impl Eval {
+    pub(crate) fn decode(body: &mut Cursor<Vec<u8>>) -> Result<Self, BoxError> {
+        let mut expr = None;
+        let mut args = None;
+        decode_map(body, |key, body| {
+            match key {
+                EXPR => expr = Some(decode_string(body)?),
+                TUPLE => args = Some(decode_tuple(body)?),
+                _ => msgpack::skip_value(body)?,
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            expr: required(expr, "EXPR")?,
+            args: args.unwrap_or_else(empty_tuple),
+        })
+    }
+}
+
+impl Select {
+    pub(crate) fn decode(body: &mut Cursor<Vec<u8>>) -> Result<Self, BoxError> {
+        let mut space_id = None;
+        let mut index_id = 0;
+        let mut limit = u32::MAX;
+        let mut offset = 0;
+        let mut iterator_type = IteratorType::Eq;
+        let mut key = None;
+        decode_map(body, |k, body| {
+            match k {
+                SPACE_ID => space_id = Some(rmp::decode::read_int(body)?),
+                INDEX_ID => index_id = rmp::decode::read_int(body)?,
+                LIMIT => limit = rmp::decode::read_int(body)?,
+                OFFSET => offset = rmp::decode::read_int(body)?,
+                ITERATOR => {
+                    let value: u32 = rmp::decode::read_int(body)?;
+                    iterator_type = iterator_type_from_u32(value)
+                        .ok_or_else(|| Error::other(format!("unknown iterator type {value}")))?;
+                }
+                KEY => key = Some(decode_tuple(body)?),
+                _ => msgpack::skip_value(body)?,
+            }
+            Ok(())
+        })?;
+        Ok(Self {
+            space_id: required(space_id, "SPACE_ID")?,
+            index_id,
+            limit,
+            offset,
+            iterator_type,
+            key: key.unwrap_or_else(empty_tuple),
+        })
+    }
+}
+
+impl Auth {
+    pub(crate) fn decode(body: &mut Cursor<Vec<u8>>) -> Result<Self, BoxError> {
+        let mut user = None;
+        let mut auth_data = None;
+        decode_map(body, |key, body| {
+            match key {
+                USER_NAME => user = Some(decode_string(body)?),
+                TUPLE => {
+                    let len = rmp::decode::read_array_len(body)?;
+                    let method = decode_string(body)?;
+                    let scramble = match rmpv::decode::read_value(body).map_err(Error::other)? {
+                        rmpv::Value::String(s) => s.into_bytes(),
+                        rmpv::Value::Binary(b) => b,
+                        _ => Vec::new(),
+                    };
+                    for _ in 2..len {
+                        msgpack::skip_value(body)?;
+                    }
+                    auth_data = Some((method, scramble));
+                }
+                _ => msgpack::skip_value(body)?,
+            }
+            Ok(())
+        })?;
+        let Some((method, scramble)) = auth_data else {
+            return Err(BoxError::new(
+                TarantoolErrorCode::InvalidAuthRequest,
+                "Invalid authentication request: missing auth data",
+            ));
+        };
+        Ok(Self {
+            user: required(user, "USER_NAME")?,
+            method,
+            scramble,
+        })
+    }
+}
+
+/// Calls `f` for each key of the msgpack map in `body`. Any decoding error is
+/// converted into a [`TarantoolErrorCode::InvalidMsgpack`] error.
+fn decode_map<F>(body: &mut Cursor<Vec<u8>>, mut f: F) -> Result<(), BoxError>
+where
+    F: FnMut(u8, &mut Cursor<Vec<u8>>) -> crate::Result<()>,
+{
+    let mut decode = || -> crate::Result<()> {
+        let map_len = rmp::decode::read_map_len(body)?;
+        for _ in 0..map_len {
+            let key = rmp::decode::read_pfix(body)?;
+            f(key, body)?;
+        }
+        Ok(())
+    };
+    decode().map_err(|e| {
+        BoxError::new(
+            TarantoolErrorCode::InvalidMsgpack,
+            format!("Invalid MsgPack - request body: {e}"),
+        )
+    })
+}
+
+#[inline(always)]
+fn decode_tuple(body: &mut Cursor<Vec<u8>>) -> crate::Result<Tuple> {
+    Tuple::try_from_slice(value_slice(body)?)
+}
+
+fn required<T>(value: Option<T>, key: &str) -> Result<T, BoxError> {
+    value.ok_or_else(|| {
+        BoxError::new(
+            TarantoolErrorCode::MissingRequestField,
+            format!("Missing mandatory field '{key}' in request"),
+        )
+    })
+}
+
+#[inline(always)]
+fn empty_tuple() -> Tuple {
+    Tuple::new(&()).expect("encoding an empty array can't fail")
+}
+
+fn iterator_type_from_u32(value: u32) -> Option<IteratorType> {
+    let res = match value {
+        0 => IteratorType::Eq,
+        1 => IteratorType::Req,
+        2 => IteratorType::All,
+        3 => IteratorType::LT,
+        4 => IteratorType::LE,
+        5 => IteratorType::GE,
+        6 => IteratorType::GT,
+        7 => IteratorType::BitsAllSet,
+        8 => IteratorType::BitsAnySet,
+        9 => IteratorType::BitsAllNotSet,
+        10 => IteratorType::Overlaps,
+        11 => IteratorType::Neighbor,
+        _ => return None,
+    };
+    Some(res)
+}
@@ -7,9 +7,167 @@
 //! are reserved for local functions. The rest of the identifiers (up to i32::MAX)
 //! are used for SQL procedures in picodata.
 
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+#[cfg(feature = "picodata")]
 use crate::ffi::tarantool::box_generate_func_id;
+use crate::schema;
+use crate::session;
+use crate::set_error;
+use crate::space::{FieldType, Space, SystemSpace};
 
+crate::define_str_enum! {
+    /// Language of a function.
+    pub enum FunctionLanguage {
+        Lua = "LUA",
+        C = "C",
+        SqlBuiltin = "SQL_BUILTIN",
+        SqlExpr = "SQL_EXPR",
+    }
+}
+
+/// Options for a new function, used by [`create_function`].
+///
+/// For details see [box.schema.func.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/func_create/).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionCreateOptions {
+    pub if_not_exists: bool,
+    pub language: FunctionLanguage,
+    /// Body of a persistent function. If `None` the function is not
+    /// persistent, i.e. it must be defined in lua or in a C module.
+    pub body: Option<String>,
+    pub setuid: bool,
+    pub is_deterministic: bool,
+    pub is_sandboxed: bool,
+    pub returns: FieldType,
+    pub param_list: Vec<FieldType>,
+    /// Languages the function is exported to, defaults to `[Lua]`.
+    pub exports: Vec<FunctionLanguage>,
+    /// The function returns multiple keys in a multikey functional index.
+    pub is_multikey: bool,
+    pub comment: String,
+}
+
+impl Default for FunctionCreateOptions {
+    fn default() -> Self {
+        Self {
+            if_not_exists: false,
+            language: FunctionLanguage::Lua,
+            body: None,
+            setuid: false,
+            is_deterministic: false,
+            is_sandboxed: false,
+            returns: FieldType::Any,
+            param_list: vec![],
+            exports: vec![FunctionLanguage::Lua],
+            is_multikey: false,
+            comment: String::new(),
+        }
+    }
+}
+
+/// Create a function.
+/// (for details see [box.schema.func.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/func_create/)).
+///
+/// - `name` - name of function, which should conform to the rules for object names.
+/// - `opts` - see FunctionCreateOptions struct.
+///
+/// Returns ID of the function.
+pub fn create_function(name: &str, opts: &FunctionCreateOptions) -> Result<u32, Error> {
+    if let Some(id) = function_id(name)? {
+        if opts.if_not_exists {
+            return Ok(id);
+        }
+        set_error!(TarantoolErrorCode::FunctionExists, "{}", name);
+        return Err(TarantoolError::last().into());
+    }
+
+    #[cfg(feature = "picodata")]
+    let id = func_next_id()?;
+    #[cfg(not(feature = "picodata"))]
+    let id = {
+        // Same as `_func:auto_increment` in lua.
+        let sys_func: Space = SystemSpace::Func.into();
+        match sys_func
+            .primary_key()
+            .select(crate::index::IteratorType::LE, &())?
+            .next()
+        {
+            Some(t) => t.field::<u32>(0)?.unwrap() + 1,
+            None => 1,
+        }
+    };
+
+    let now = time::OffsetDateTime::now_utc();
+    let datetime = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let exports = opts.exports.iter().map(|l| l.as_str().into()).collect();
+    let param_list = opts.param_list.iter().map(|t| t.as_str().into()).collect();
+    let func_opts = vec![("is_multikey".into(), opts.is_multikey.into())];
+
+    // `_func` has more fields than serde supports in a tuple.
+    let tuple: Vec<rmpv::Value> = vec![
+        id.into(),
+        session::euid()?.into(),
+        name.into(),
+        (opts.setuid as u32).into(),
+        opts.language.as_str().into(),
+        opts.body.as_deref().unwrap_or("").into(),
+        "function".into(),
+        rmpv::Value::Array(param_list),
+        opts.returns.as_str().into(),
+        // aggregate
+        "none".into(),
+        // sql_data_access
+        "none".into(),
+        opts.is_deterministic.into(),
+        opts.is_sandboxed.into(),
+        // is_null_call
+        true.into(),
+        rmpv::Value::Array(exports),
+        rmpv::Value::Map(func_opts),
+        opts.comment.as_str().into(),
+        // created
+        datetime.as_str().into(),
+        // last_altered
+        datetime.into(),
+    ];
+    let sys_func: Space = SystemSpace::Func.into();
+    sys_func.insert(&tuple)?;
+
+    Ok(id)
+}
+
+/// Drop a function.
+///
+/// - `func_id` - ID of existing function.
+pub fn drop_function(func_id: u32) -> Result<(), Error> {
+    schema::revoke_object_privileges("function", func_id)?;
+
+    let sys_func: Space = SystemSpace::Func.into();
+    sys_func.delete(&(func_id,))?;
+
+    Ok(())
+}
+
+/// Returns ID of the function with the given `name` or `None` if there's
+/// no such function.
+pub fn function_id(name: &str) -> Result<Option<u32>, Error> {
+    let sys_vfunc: Space = SystemSpace::VFunc.into();
+    let name_idx = sys_vfunc.index("name").unwrap();
+    Ok(match name_idx.get(&(name,))? {
+        None => None,
+        Some(t) => t.field(0)?,
+    })
+}
+
+#[cfg(feature = "picodata")]
 fn next_id(use_reserved_range: bool) -> Result<u32, Error> {
     unsafe {
         let mut id: u32 = 0;
@@ -21,12 +179,14 @@ fn next_id(use_reserved_range: bool) -> Result<u32, Error> {
     }
 }
 
+#[cfg(feature = "picodata")]
 /// Generate next function id from reserved range
 /// (used for stored procedures in picodata).
 pub fn func_next_reserved_id() -> Result<u32, Error> {
     next_id(true)
 }
 
+#[cfg(feature = "picodata")]
 /// Generate next function id from default range
 /// (used for local tarantool functions).
 pub fn func_next_id() -> Result<u32, Error> {
@@ -36,17 +196,48 @@ pub fn func_next_id() -> Result<u32, Error> {
 #[cfg(feature = "internal_test")]
 mod tests {
     use super::*;
-    use std::convert::TryInto;
 
+    #[cfg(feature = "picodata")]
     #[crate::test(tarantool = "crate")]
     pub fn test_func_next_id() {
         let id = func_next_id().unwrap();
         assert!(id > 0 && id <= 32_000);
     }
 
+    #[cfg(feature = "picodata")]
     #[crate::test(tarantool = "crate")]
     pub fn test_func_next_reserved_id() {
+        use std::convert::TryInto;
         let id = func_next_reserved_id().unwrap();
         assert!(id > 32_000 && id <= i32::MAX.try_into().unwrap());
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn create_and_drop_function() {
+        let name = crate::temp_space_name!();
+        let opts = FunctionCreateOptions {
+            body: Some("function(a, b) return a + b end".into()),
+            is_deterministic: true,
+            is_sandboxed: true,
+            ..Default::default()
+        };
+        let id = create_function(&name, &opts).unwrap();
+        assert_eq!(function_id(&name).unwrap(), Some(id));
+
+        let e = create_function(&name, &opts).unwrap_err();
+        assert_eq!(e.to_string(), format!("box error: FunctionExists: {name}"));
+        let opts = FunctionCreateOptions {
+            if_not_exists: true,
+            ..opts
+        };
+        assert_eq!(create_function(&name, &opts).unwrap(), id);
+
+        let res: i32 = crate::global_lua()
+            .eval_with("return box.func[...]:call{1, 2}", &name)
+            .unwrap();
+        assert_eq!(res, 3);
+
+        drop_function(id).unwrap();
+        assert_eq!(function_id(&name).unwrap(), None);
+    }
 }
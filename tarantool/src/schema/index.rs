@@ -1,8 +1,15 @@
+use std::collections::BTreeMap;
+
 use crate::c_ptr;
-use crate::error::{Error, TarantoolError};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
 use crate::ffi::lua;
 use crate::ffi::tarantool::luaT_call;
-use crate::index::{Index, IndexOptions};
+use crate::index::{Index, IndexAlterOptions, IndexOptions, IndexType, IteratorType, Part};
+use crate::schema::function::FunctionCreateOptions;
+use crate::schema::space::space_metadata;
+use crate::set_error;
+use crate::space::{SpaceId, SystemSpace};
+use crate::util::{NumOrStr, Value};
 use tlua::AsLua as _;
 use tlua::{
     LuaError::{self, ExecutionError},
@@ -17,6 +24,8 @@ use tlua::{
 ///
 /// For details see [space_object:create_index](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/create_index/)
 pub fn create_index(space_id: u32, index_name: &str, opts: &IndexOptions) -> Result<Index, Error> {
+    check_index_options(space_id, index_name, opts, None)?;
+
     let lua = crate::lua_state();
     let b: LuaTable<_> = lua
         .get("box")
@@ -39,6 +48,123 @@ pub fn create_index(space_id: u32, index_name: &str, opts: &IndexOptions) -> Res
     Ok(Index::new(space_id, index_id))
 }
 
+/// Check the options of a new index, so that the errors in the definitions
+/// of multikey and functional indexes are reported before any DDL is
+/// executed.
+///
+/// - `func` - options of the function which will be created for a functional
+///   index. If `None` the function named in `opts.func` must already exist.
+pub(crate) fn check_index_options(
+    space_id: SpaceId,
+    index_name: &str,
+    opts: &IndexOptions,
+    func: Option<&FunctionCreateOptions>,
+) -> Result<(), Error> {
+    let sys_vindex = SystemSpace::VIndex.as_space();
+    if opts.if_not_exists == Some(true) {
+        let name_idx = sys_vindex.index("name").unwrap();
+        if name_idx.get(&(space_id, index_name))?.is_some() {
+            return Ok(());
+        }
+    }
+
+    let error = |reason: &str| -> Result<(), Error> {
+        let space_name = space_metadata(space_id)
+            .map(|m| m.name.into_owned())
+            .unwrap_or_else(|_| space_id.to_string());
+        set_error!(
+            TarantoolErrorCode::ModifyIndex,
+            "Can't create or modify index '{}' in space '{}': {}",
+            index_name,
+            space_name,
+            reason
+        );
+        Err(TarantoolError::last().into())
+    };
+
+    let is_primary = match opts.id {
+        Some(id) => id == 0,
+        None => sys_vindex
+            .select(IteratorType::Eq, &(space_id,))?
+            .next()
+            .is_none(),
+    };
+    let is_tree = opts.r#type.unwrap_or_default() == IndexType::Tree;
+    let parts = opts.parts.as_deref().unwrap_or_default();
+
+    let mut multikey_path = None;
+    for part in parts.iter().filter(|p| p.is_multikey()) {
+        let field = match &part.field {
+            NumOrStr::Num(n) => n.to_string(),
+            NumOrStr::Str(s) => s.clone(),
+        };
+        let path = format!("{}{}", field, part.path.as_deref().unwrap_or(""));
+        if path.matches("[*]").count() > 1 {
+            return error("no more than one array index placeholder [*] is allowed in JSON path");
+        }
+        let prefix = &path[..path.find("[*]").unwrap()];
+        match multikey_path {
+            None => multikey_path = Some(prefix.to_string()),
+            Some(ref p) if p != prefix => {
+                return error("all multikey parts must have the same JSON path before [*]");
+            }
+            Some(_) => {}
+        }
+    }
+    if multikey_path.is_some() {
+        if is_primary {
+            return error("primary key cannot be multikey");
+        }
+        if !is_tree {
+            return error("only TREE index can be multikey");
+        }
+    }
+
+    let Some(func_name) = &opts.func else {
+        return Ok(());
+    };
+    if is_primary {
+        return error("primary key can not use a function");
+    }
+    if !is_tree {
+        return error("only TREE index can use a function");
+    }
+    if multikey_path.is_some() {
+        return error("functional index can not use [*] in parts, use a multikey function instead");
+    }
+
+    let (is_persistent, is_deterministic, is_sandboxed) = match func {
+        Some(f) => (
+            f.body.as_deref().map_or(false, |b| !b.is_empty()),
+            f.is_deterministic,
+            f.is_sandboxed,
+        ),
+        None => {
+            let name_idx = SystemSpace::VFunc.as_space().index("name").unwrap();
+            let Some(t) = name_idx.get(&(func_name,))? else {
+                set_error!(TarantoolErrorCode::NoSuchFunction, "{}", func_name);
+                return Err(TarantoolError::last().into());
+            };
+            (
+                t.field::<&str>(5)?.map_or(false, |b| !b.is_empty()),
+                t.field::<bool>(11)?.unwrap_or(false),
+                t.field::<bool>(12)?.unwrap_or(false),
+            )
+        }
+    };
+    if !is_persistent {
+        return error(&format!("function '{func_name}' must be persistent"));
+    }
+    if !is_deterministic {
+        return error(&format!("function '{func_name}' must be deterministic"));
+    }
+    if !is_sandboxed {
+        return error(&format!("function '{func_name}' must be sandboxed"));
+    }
+
+    Ok(())
+}
+
 /// Drop existing index.
 ///
 /// - `space_id` - ID of existing space.
@@ -112,3 +238,177 @@ pub fn drop_index(space_id: u32, index_id: u32) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Alter existing index.
+///
+/// - `space_id` - ID of existing space.
+/// - `index_id` - ID of existing index.
+/// - `opts` - see IndexAlterOptions struct.
+///
+/// The index parts are resolved against the space format the same way
+/// `index_object:alter` does it in lua, and the new definition is written into
+/// `_index`, so the rest of the checks (e.g. whether the data satisfies the new
+/// unique constraint) are done by tarantool.
+///
+/// For details see [index_object:alter](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_index/alter/)
+pub fn alter_index(
+    space_id: SpaceId,
+    index_id: u32,
+    opts: &IndexAlterOptions,
+) -> Result<(), Error> {
+    let sys_index = SystemSpace::Index.as_space();
+    let Some(tuple) = sys_index.get(&(space_id, index_id))? else {
+        set_error!(
+            TarantoolErrorCode::NoSuchIndexID,
+            "index #{} for space #{} not found",
+            index_id,
+            space_id
+        );
+        return Err(TarantoolError::last().into());
+    };
+    #[allow(clippy::type_complexity)]
+    let (_, _, mut name, mut index_type, mut index_opts, mut parts): (
+        SpaceId,
+        u32,
+        String,
+        String,
+        BTreeMap<String, rmpv::Value>,
+        rmpv::Value,
+    ) = tuple.decode()?;
+
+    if let Some(new_name) = &opts.name {
+        let name_idx = SystemSpace::VIndex.as_space().index("name").unwrap();
+        if let Some(t) = name_idx.get(&(space_id, new_name))? {
+            if t.field::<u32>(1)? != Some(index_id) {
+                set_error!(TarantoolErrorCode::IndexExists, "{}", new_name);
+                return Err(TarantoolError::last().into());
+            }
+        }
+        name = new_name.clone();
+    }
+
+    if let Some(t) = opts.r#type {
+        index_type = t.as_str().into();
+    }
+
+    let mut set_opt = |key: &str, value: Option<rmpv::Value>| {
+        if let Some(value) = value {
+            index_opts.insert(key.into(), value);
+        }
+    };
+    set_opt("unique", opts.unique.map(Into::into));
+    set_opt("dimension", opts.dimension.map(Into::into));
+    set_opt("distance", opts.distance.map(|d| d.as_str().into()));
+    set_opt("bloom_fpr", opts.bloom_fpr.map(|v| f64::from(v).into()));
+    set_opt("page_size", opts.page_size.map(Into::into));
+    set_opt("range_size", opts.range_size.map(Into::into));
+    set_opt(
+        "run_count_per_level",
+        opts.run_count_per_level.map(Into::into),
+    );
+    set_opt(
+        "run_size_ratio",
+        opts.run_size_ratio.map(|v| f64::from(v).into()),
+    );
+
+    if let Some(new_parts) = &opts.parts {
+        parts = resolve_parts(space_id, new_parts)?;
+    }
+
+    sys_index.replace(&(space_id, index_id, name, index_type, index_opts, parts))?;
+    Ok(())
+}
+
+/// Convert index parts into the representation stored in `_index`: field
+/// names are resolved into 0-based field numbers, missing types and
+/// nullability are taken from the space format and collation names are
+/// resolved into collation ids.
+pub(crate) fn resolve_parts(space_id: SpaceId, parts: &[Part]) -> Result<rmpv::Value, Error> {
+    if parts.is_empty() {
+        set_error!(
+            TarantoolErrorCode::IllegalParams,
+            "index parts must not be empty"
+        );
+        return Err(TarantoolError::last().into());
+    }
+
+    let space_meta = space_metadata(space_id)?;
+    let format = &space_meta.format;
+    let format_field = |field_no: usize, key: &str| format.get(field_no)?.get(key).cloned();
+
+    let mut res = Vec::with_capacity(parts.len());
+    for part in parts {
+        let (field_no, path) = match &part.field {
+            NumOrStr::Num(0) => {
+                set_error!(
+                    TarantoolErrorCode::IllegalParams,
+                    "field numbers in index parts start from 1"
+                );
+                return Err(TarantoolError::last().into());
+            }
+            NumOrStr::Num(n) => ((n - 1) as usize, None),
+            NumOrStr::Str(name) => {
+                let find = |name: &str| {
+                    format
+                        .iter()
+                        .position(|f| matches!(f.get("name"), Some(Value::Str(n)) if n == name))
+                };
+                // A field name can be followed by a json path, e.g. "map.key".
+                let with_path = || {
+                    let i = name.find(['.', '['])?;
+                    Some((find(&name[..i])?, Some(name[i..].to_string())))
+                };
+                let Some(res) = find(name).map(|i| (i, None)).or_else(with_path) else {
+                    set_error!(
+                        TarantoolErrorCode::NoSuchFieldNameInSpace,
+                        "Field '{}' was not found in space '{}' format",
+                        name,
+                        space_meta.name
+                    );
+                    return Err(TarantoolError::last().into());
+                };
+                res
+            }
+        };
+        let path = part.path.clone().or(path);
+
+        let field_type = match (part.r#type, format_field(field_no, "type")) {
+            (Some(t), _) => t.as_str().to_string(),
+            (None, Some(Value::Str(t))) => t.into_owned(),
+            (None, _) => {
+                set_error!(
+                    TarantoolErrorCode::WrongIndexParts,
+                    "field type is required for field {}",
+                    field_no + 1
+                );
+                return Err(TarantoolError::last().into());
+            }
+        };
+        let is_nullable = part.is_nullable.unwrap_or(matches!(
+            format_field(field_no, "is_nullable"),
+            Some(Value::Bool(true))
+        ));
+
+        let mut res_part = vec![
+            ("field".into(), (field_no as u32).into()),
+            ("type".into(), field_type.into()),
+        ];
+        if is_nullable {
+            res_part.push(("is_nullable".into(), true.into()));
+        }
+        if let Some(collation) = &part.collation {
+            let name_idx = SystemSpace::VCollation.as_space().index("name").unwrap();
+            let Some(t) = name_idx.get(&(collation,))? else {
+                set_error!(TarantoolErrorCode::NoSuchCollation, "{}", collation);
+                return Err(TarantoolError::last().into());
+            };
+            res_part.push(("collation".into(), t.field::<u32>(0)?.unwrap().into()));
+        }
+        if let Some(path) = path {
+            res_part.push(("path".into(), path.into()));
+        }
+        res.push(rmpv::Value::Map(res_part));
+    }
+
+    Ok(rmpv::Value::Array(res))
+}
@@ -0,0 +1,806 @@
+//! Declarative schema migrations.
+//!
+//! [`Migrations`] is an ordered list of named migration steps written in rust.
+//! Applied migrations are recorded in a dedicated space (`_migrations` by
+//! default), so that each step is applied only once. Every step is executed
+//! in a separate transaction together with the update of the migration
+//! records, so a step is either fully applied and recorded or not at all.
+//!
+//! [`SpaceDef`] describes the desired state of a space & its indexes, and can
+//! be compared with the actual schema via [`SpaceDef::diff`] to produce a list
+//! of [`AlterOp`]s which bring the space into the desired state.
+//!
+//! # Example
+//! ```no_run
+//! use tarantool::index::{IndexOptions, Part};
+//! use tarantool::schema::migrations::{Migrations, SpaceDef};
+//! use tarantool::space::{Field, SpaceCreateOptions};
+//!
+//! let users = || {
+//!     SpaceDef::new("users", SpaceCreateOptions {
+//!         format: Some(vec![Field::unsigned("id"), Field::string("name")]),
+//!         ..Default::default()
+//!     })
+//!     .index("primary", IndexOptions {
+//!         parts: Some(vec![Part::field("id")]),
+//!         ..Default::default()
+//!     })
+//! };
+//!
+//! let migrations = Migrations::new()
+//!     .add("create users", move || users().apply())
+//!     .add_reversible(
+//!         "add users by name",
+//!         move || {
+//!             users()
+//!                 .index("by_name", IndexOptions {
+//!                     unique: Some(false),
+//!                     parts: Some(vec![Part::field("name")]),
+//!                     ..Default::default()
+//!                 })
+//!                 .apply()
+//!         },
+//!         move || users().drop_undeclared_indexes(true).apply(),
+//!     );
+//!
+//! // Apply all of the pending migrations.
+//! migrations.up().unwrap();
+//! // Revert the last one.
+//! migrations.down().unwrap();
+//! ```
+//!
+//! **NOTE:** tarantool only supports DDL in multi-statement transactions if
+//! none of the statements yield. For example building a new index in a
+//! non-empty memtx space yields, so such a step must not do anything else in
+//! the same migration.
+
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, TarantoolErrorCode};
+use crate::index::{self, IndexAlterOptions, IndexOptions, IteratorType, Part};
+use crate::set_error;
+use crate::space::{Field, Space, SpaceAlterOptions, SpaceCreateOptions, SystemSpace};
+use crate::transaction;
+use crate::util::{NumOrStr, Value};
+
+/// Name of the space used by default to record applied migrations.
+pub const DEFAULT_MIGRATIONS_SPACE: &str = "_migrations";
+
+////////////////////////////////////////////////////////////////////////////////
+// Migrations
+////////////////////////////////////////////////////////////////////////////////
+
+type Step = Box<dyn Fn() -> crate::Result<()>>;
+
+/// A single named migration step.
+pub struct Migration {
+    name: String,
+    up: Step,
+    down: Option<Step>,
+}
+
+impl Migration {
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline(always)]
+    pub fn is_reversible(&self) -> bool {
+        self.down.is_some()
+    }
+}
+
+impl std::fmt::Debug for Migration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migration")
+            .field("name", &self.name)
+            .field("is_reversible", &self.is_reversible())
+            .finish()
+    }
+}
+
+/// An ordered list of migrations. See the [module level documentation](self)
+/// for details.
+#[derive(Debug)]
+pub struct Migrations {
+    space_name: String,
+    migrations: Vec<Migration>,
+}
+
+impl Default for Migrations {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrations {
+    /// Create an empty list of migrations which are recorded in the
+    /// [`DEFAULT_MIGRATIONS_SPACE`].
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::with_space_name(DEFAULT_MIGRATIONS_SPACE)
+    }
+
+    /// Create an empty list of migrations which are recorded in the space
+    /// `space_name`. The space is created on the first call to [`Self::up`].
+    #[inline(always)]
+    pub fn with_space_name(space_name: impl Into<String>) -> Self {
+        Self {
+            space_name: space_name.into(),
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Append an irreversible migration step.
+    #[inline(always)]
+    pub fn add<U>(mut self, name: impl Into<String>, up: U) -> Self
+    where
+        U: Fn() -> crate::Result<()> + 'static,
+    {
+        self.migrations.push(Migration {
+            name: name.into(),
+            up: Box::new(up),
+            down: None,
+        });
+        self
+    }
+
+    /// Append a migration step which can be reverted with [`Self::down`].
+    #[inline(always)]
+    pub fn add_reversible<U, D>(mut self, name: impl Into<String>, up: U, down: D) -> Self
+    where
+        U: Fn() -> crate::Result<()> + 'static,
+        D: Fn() -> crate::Result<()> + 'static,
+    {
+        self.migrations.push(Migration {
+            name: name.into(),
+            up: Box::new(up),
+            down: Some(Box::new(down)),
+        });
+        self
+    }
+
+    #[inline(always)]
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Returns the names of the applied migrations in the order they were
+    /// applied.
+    ///
+    /// Returns an error if the applied migrations don't match the declared
+    /// ones.
+    pub fn applied(&self) -> crate::Result<Vec<&str>> {
+        let Some(space) = Space::find(&self.space_name) else {
+            return Ok(vec![]);
+        };
+        let mut res = Vec::new();
+        for (i, tuple) in space.select(IteratorType::All, &())?.enumerate() {
+            let (_, name, _): (u32, String, u64) = tuple.decode()?;
+            let Some(declared) = self.migrations.get(i) else {
+                return Err(Error::other(MigrationError::NotDeclared { index: i, name }));
+            };
+            if declared.name != name {
+                return Err(Error::other(MigrationError::HistoryMismatch {
+                    index: i,
+                    applied: name,
+                    declared: declared.name.clone(),
+                }));
+            }
+            res.push(declared.name.as_str());
+        }
+        Ok(res)
+    }
+
+    /// Returns the names of the migrations which are not yet applied.
+    #[inline]
+    pub fn pending(&self) -> crate::Result<Vec<&str>> {
+        let n_applied = self.applied()?.len();
+        Ok(self.migrations[n_applied..]
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect())
+    }
+
+    /// Apply all of the pending migrations in order. Returns the number of
+    /// applied migrations.
+    ///
+    /// Each migration is applied in a separate transaction. If a migration
+    /// fails, its changes are rolled back and the error is returned, while the
+    /// previously applied migrations stay applied.
+    pub fn up(&self) -> crate::Result<usize> {
+        let space = self.records_space()?;
+        let n_applied = self.applied()?.len();
+        for (i, migration) in self.migrations.iter().enumerate().skip(n_applied) {
+            transaction::transaction(|| -> crate::Result<()> {
+                (migration.up)()?;
+                let applied_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                space.insert(&(i as u32, &migration.name, applied_at))?;
+                Ok(())
+            })?;
+            crate::say_info!("applied migration '{}'", migration.name);
+        }
+        Ok(self.migrations.len() - n_applied)
+    }
+
+    /// Revert the last applied migration. Returns the name of the reverted
+    /// migration or `None` if no migrations are applied.
+    ///
+    /// Returns an error if the migration isn't reversible.
+    pub fn down(&self) -> crate::Result<Option<&str>> {
+        let n_applied = self.applied()?.len();
+        if n_applied == 0 {
+            return Ok(None);
+        }
+        let i = n_applied - 1;
+        let migration = &self.migrations[i];
+        let Some(down) = &migration.down else {
+            return Err(Error::other(MigrationError::Irreversible(
+                migration.name.clone(),
+            )));
+        };
+        let space = self.records_space()?;
+        transaction::transaction(|| -> crate::Result<()> {
+            down()?;
+            space.delete(&(i as u32,))?;
+            Ok(())
+        })?;
+        crate::say_info!("reverted migration '{}'", migration.name);
+        Ok(Some(&migration.name))
+    }
+
+    fn records_space(&self) -> crate::Result<Space> {
+        let space = Space::builder(&self.space_name)
+            .if_not_exists(true)
+            .format([
+                Field::unsigned("id"),
+                Field::string("name"),
+                Field::unsigned("applied_at"),
+            ])
+            .create()?;
+        space
+            .index_builder("primary")
+            .part("id")
+            .if_not_exists(true)
+            .create()?;
+        Ok(space)
+    }
+}
+
+/// Migration specific error cases.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "applied migration #{index} '{applied}' doesn't match the declared migration '{declared}'"
+    )]
+    HistoryMismatch {
+        index: usize,
+        applied: String,
+        declared: String,
+    },
+
+    #[error("applied migration #{index} '{name}' is not declared")]
+    NotDeclared { index: usize, name: String },
+
+    #[error("migration '{0}' is irreversible")]
+    Irreversible(String),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SpaceDef
+////////////////////////////////////////////////////////////////////////////////
+
+/// Declared state of a space and its indexes.
+#[derive(Clone, Debug, Default)]
+pub struct SpaceDef {
+    pub name: String,
+    pub opts: SpaceCreateOptions,
+    pub indexes: Vec<(String, IndexOptions)>,
+    /// Drop the secondary indexes of the space which aren't declared in
+    /// `indexes`. `false` by default.
+    pub drop_undeclared_indexes: bool,
+}
+
+impl SpaceDef {
+    #[inline(always)]
+    pub fn new(name: impl Into<String>, opts: SpaceCreateOptions) -> Self {
+        Self {
+            name: name.into(),
+            opts,
+            indexes: Vec::new(),
+            drop_undeclared_indexes: false,
+        }
+    }
+
+    /// Add an index definition. The first index is the primary one.
+    #[inline(always)]
+    pub fn index(mut self, name: impl Into<String>, opts: IndexOptions) -> Self {
+        self.indexes.push((name.into(), opts));
+        self
+    }
+
+    /// Set whether the secondary indexes which aren't declared should be
+    /// dropped, see [`SpaceDef::diff`].
+    #[inline(always)]
+    pub fn drop_undeclared_indexes(mut self, value: bool) -> Self {
+        self.drop_undeclared_indexes = value;
+        self
+    }
+
+    /// Construct the definition of the space with rows of type `R`.
+    pub fn from_row<R: crate::space::typed::Row>() -> Self {
+        let opts = SpaceCreateOptions {
+            format: Some(R::format()),
+            ..Default::default()
+        };
+        let mut res = Self::new(R::SPACE_NAME, opts);
+        for index in R::indexes() {
+            let opts = IndexOptions {
+                unique: Some(index.unique),
+                parts: Some(index.parts),
+                ..Default::default()
+            };
+            res = res.index(index.name, opts);
+        }
+        res
+    }
+
+    /// Compare the declared state of the space with the actual one and return
+    /// the operations needed to bring the space into the declared state.
+    ///
+    /// The space format and the type, uniqueness and parts of the indexes are
+    /// compared, other options are only used when creating new objects.
+    /// Indexes which aren't declared are left as is, unless
+    /// [`drop_undeclared_indexes`](Self::drop_undeclared_indexes) is set, in
+    /// which case the undeclared secondary indexes are dropped. The primary
+    /// index is never dropped.
+    pub fn diff(&self) -> crate::Result<Vec<AlterOp>> {
+        let Some(space) = Space::find(&self.name) else {
+            let mut ops = Vec::with_capacity(1 + self.indexes.len());
+            ops.push(AlterOp::CreateSpace {
+                name: self.name.clone(),
+                opts: self.opts.clone(),
+            });
+            for (name, opts) in &self.indexes {
+                ops.push(AlterOp::CreateIndex {
+                    space: self.name.clone(),
+                    name: name.clone(),
+                    opts: opts.clone(),
+                });
+            }
+            return Ok(ops);
+        };
+
+        let meta = space.meta()?;
+        let actual_format = decode_format(&meta.format);
+        let format = self.opts.format.as_deref().or(actual_format.as_deref());
+
+        let mut drops = vec![];
+        let mut alters = vec![];
+        let mut creates = vec![];
+
+        let actual_indexes: Vec<index::Metadata> = SystemSpace::VIndex
+            .as_space()
+            .select(IteratorType::Eq, &(space.id(),))?
+            .map(|t| t.decode())
+            .collect::<crate::Result<_>>()?;
+        for actual in actual_indexes.iter().rev() {
+            if !self.drop_undeclared_indexes || actual.index_id == 0 {
+                continue;
+            }
+            if !self.indexes.iter().any(|(name, _)| *name == actual.name) {
+                drops.push(AlterOp::DropIndex {
+                    space: self.name.clone(),
+                    name: actual.name.to_string(),
+                });
+            }
+        }
+
+        for (name, opts) in &self.indexes {
+            match actual_indexes.iter().find(|i| i.name == name.as_str()) {
+                None => creates.push(AlterOp::CreateIndex {
+                    space: self.name.clone(),
+                    name: name.clone(),
+                    opts: opts.clone(),
+                }),
+                Some(actual) if !index_matches(opts, actual, format) => {
+                    let mut opts = opts.clone();
+                    opts.id = None;
+                    opts.if_not_exists = None;
+                    alters.push(AlterOp::AlterIndex {
+                        space: self.name.clone(),
+                        name: name.clone(),
+                        opts,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut ops = drops;
+        if let Some(format) = &self.opts.format {
+            if actual_format.as_ref() != Some(format) {
+                ops.push(AlterOp::SetFormat {
+                    space: self.name.clone(),
+                    format: format.clone(),
+                });
+            }
+        }
+        ops.extend(alters);
+        ops.extend(creates);
+        Ok(ops)
+    }
+
+    /// Compute the [`diff`](Self::diff) and apply the resulting operations.
+    pub fn apply(&self) -> crate::Result<()> {
+        for op in self.diff()? {
+            op.apply()?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode the space format from the `_space` metadata. Returns `None` if
+/// the format contains field types unknown to this library.
+fn decode_format(format: &[std::collections::BTreeMap<Cow<str>, Value>]) -> Option<Vec<Field>> {
+    let mut res = Vec::with_capacity(format.len());
+    for field in format {
+        let Some(Value::Str(name)) = field.get("name") else {
+            return None;
+        };
+        let Some(Value::Str(field_type)) = field.get("type") else {
+            return None;
+        };
+        let is_nullable = matches!(field.get("is_nullable"), Some(Value::Bool(true)));
+        res.push(Field {
+            name: name.to_string(),
+            field_type: field_type.parse().ok()?,
+            is_nullable,
+        });
+    }
+    Some(res)
+}
+
+/// Check if the declared index options match the `actual` index metadata.
+fn index_matches(
+    declared: &IndexOptions,
+    actual: &index::Metadata,
+    format: Option<&[Field]>,
+) -> bool {
+    if declared.r#type.unwrap_or_default() != actual.r#type {
+        return false;
+    }
+
+    let actual_unique = !matches!(actual.opts.get("unique"), Some(Value::Bool(false)));
+    if declared.unique.unwrap_or(true) != actual_unique {
+        return false;
+    }
+
+    let default_parts;
+    let declared_parts = match &declared.parts {
+        Some(parts) => parts,
+        None => {
+            default_parts = vec![Part::new(1, index::FieldType::Unsigned)];
+            &default_parts
+        }
+    };
+    if declared_parts.len() != actual.parts.len() {
+        return false;
+    }
+
+    declared_parts
+        .iter()
+        .zip(&actual.parts)
+        .all(|(declared, actual)| part_matches(declared, actual, format))
+}
+
+fn part_matches(declared: &Part, actual: &Part, format: Option<&[Field]>) -> bool {
+    // Field numbers are 1-based in index definitions, but 0-based in `_index`.
+    let (field_no, field) = match &declared.field {
+        NumOrStr::Num(n) => {
+            let field_no = n.saturating_sub(1);
+            (field_no, format.and_then(|f| f.get(field_no as usize)))
+        }
+        NumOrStr::Str(name) => {
+            let Some(format) = format else {
+                return false;
+            };
+            let Some(i) = format.iter().position(|f| f.name == *name) else {
+                return false;
+            };
+            (i as u32, Some(&format[i]))
+        }
+    };
+    if actual.field != NumOrStr::Num(field_no) {
+        return false;
+    }
+
+    let declared_type = declared
+        .r#type
+        .map(|t| t.as_str())
+        .or_else(|| field.map(|f| f.field_type.as_str()));
+    let actual_type = actual.r#type.map(|t| t.as_str());
+    if declared_type.is_some() && declared_type != actual_type {
+        return false;
+    }
+
+    let declared_nullable = declared
+        .is_nullable
+        .unwrap_or_else(|| field.map(|f| f.is_nullable).unwrap_or(false));
+    if declared_nullable != actual.is_nullable.unwrap_or(false) {
+        return false;
+    }
+
+    if declared.collation.is_some() && declared.collation != actual.collation {
+        return false;
+    }
+    if declared.path.is_some() && declared.path != actual.path {
+        return false;
+    }
+
+    true
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// AlterOp
+////////////////////////////////////////////////////////////////////////////////
+
+/// A schema change operation produced by [`SpaceDef::diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlterOp {
+    CreateSpace {
+        name: String,
+        opts: SpaceCreateOptions,
+    },
+    SetFormat {
+        space: String,
+        format: Vec<Field>,
+    },
+    CreateIndex {
+        space: String,
+        name: String,
+        opts: IndexOptions,
+    },
+    AlterIndex {
+        space: String,
+        name: String,
+        opts: IndexOptions,
+    },
+    DropIndex {
+        space: String,
+        name: String,
+    },
+}
+
+impl AlterOp {
+    /// Apply the operation.
+    pub fn apply(&self) -> crate::Result<()> {
+        match self {
+            Self::CreateSpace { name, opts } => {
+                crate::schema::space::create_space(name, opts)?;
+            }
+            Self::SetFormat { space, format } => {
+                find_space(space)?.alter(&SpaceAlterOptions {
+                    format: Some(format.clone()),
+                    ..Default::default()
+                })?;
+            }
+            Self::CreateIndex { space, name, opts } => {
+                let space = find_space(space)?;
+                crate::schema::index::create_index(space.id(), name, opts)?;
+            }
+            Self::AlterIndex { space, name, opts } => {
+                let index = find_index(&find_space(space)?, name)?;
+                // Options which aren't declared are reset to the defaults, the
+                // same way `SpaceDef::diff` treats them.
+                let mut opts = IndexAlterOptions::from(opts.clone());
+                opts.r#type.get_or_insert_with(Default::default);
+                opts.unique.get_or_insert(true);
+                opts.parts
+                    .get_or_insert_with(|| vec![Part::new(1, index::FieldType::Unsigned)]);
+                index.alter(&opts)?;
+            }
+            Self::DropIndex { space, name } => {
+                find_index(&find_space(space)?, name)?.drop()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find_index(space: &Space, name: &str) -> crate::Result<index::Index> {
+    let Some(index) = space.index(name) else {
+        set_error!(
+            TarantoolErrorCode::NoSuchIndexName,
+            "No index '{}' is defined in space '{}'",
+            name,
+            space.meta()?.name
+        );
+        return Err(crate::error::TarantoolError::last().into());
+    };
+    Ok(index)
+}
+
+fn find_space(name: &str) -> crate::Result<Space> {
+    let Some(space) = Space::find(name) else {
+        set_error!(
+            TarantoolErrorCode::NoSuchSpace,
+            "Space '{}' does not exist",
+            name
+        );
+        return Err(crate::error::TarantoolError::last().into());
+    };
+    Ok(space)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn users_def(space_name: &str) -> SpaceDef {
+        SpaceDef::new(
+            space_name,
+            SpaceCreateOptions {
+                format: Some(vec![Field::unsigned("id"), Field::string("name")]),
+                ..Default::default()
+            },
+        )
+        .index(
+            "primary",
+            IndexOptions {
+                parts: Some(vec![Part::field("id")]),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn diff_and_apply() {
+        let space_name = crate::temp_space_name!();
+        let def = users_def(&space_name);
+        let ops = def.diff().unwrap();
+        assert!(matches!(
+            &ops[..],
+            [AlterOp::CreateSpace { .. }, AlterOp::CreateIndex { .. }]
+        ));
+        def.apply().unwrap();
+        assert_eq!(def.diff().unwrap(), []);
+
+        let mut def = def.index(
+            "by_name",
+            IndexOptions {
+                unique: Some(false),
+                parts: Some(vec![Part::field("name")]),
+                ..Default::default()
+            },
+        );
+        def.opts
+            .format
+            .as_mut()
+            .unwrap()
+            .push(Field::unsigned("age").is_nullable(true));
+        let ops = def.diff().unwrap();
+        assert_eq!(
+            ops,
+            [
+                AlterOp::SetFormat {
+                    space: space_name.clone(),
+                    format: def.opts.format.clone().unwrap(),
+                },
+                AlterOp::CreateIndex {
+                    space: space_name.clone(),
+                    name: "by_name".into(),
+                    opts: def.indexes[1].1.clone(),
+                },
+            ]
+        );
+        def.apply().unwrap();
+        assert_eq!(def.diff().unwrap(), []);
+
+        def.indexes[1].1.unique = Some(true);
+        let ops = def.diff().unwrap();
+        assert!(matches!(&ops[..], [AlterOp::AlterIndex { name, .. }] if name == "by_name"));
+        def.apply().unwrap();
+        assert_eq!(def.diff().unwrap(), []);
+
+        // Undeclared indexes are only dropped if requested
+        def.indexes.pop();
+        assert_eq!(def.diff().unwrap(), []);
+
+        def.drop_undeclared_indexes = true;
+        let ops = def.diff().unwrap();
+        assert_eq!(
+            ops,
+            [AlterOp::DropIndex {
+                space: space_name.clone(),
+                name: "by_name".into(),
+            }]
+        );
+        def.apply().unwrap();
+        assert_eq!(def.diff().unwrap(), []);
+
+        // The primary index is never dropped
+        def.indexes.clear();
+        assert_eq!(def.diff().unwrap(), []);
+
+        Space::find(&space_name).unwrap().drop().unwrap();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn up_and_down() {
+        let records_space = crate::temp_space_name!();
+        let space_name = crate::temp_space_name!();
+        let counter = Rc::new(Cell::new(0));
+
+        let migrations = || {
+            let def = users_def(&space_name);
+            let def_2 = def.clone();
+            let name = space_name.clone();
+            let counter = counter.clone();
+            Migrations::with_space_name(&records_space)
+                .add("create space", move || def.apply())
+                .add_reversible(
+                    "insert data",
+                    move || {
+                        counter.set(counter.get() + 1);
+                        Space::find(&name).unwrap().insert(&(1, "Bob"))?;
+                        Ok(())
+                    },
+                    move || {
+                        Space::find(&def_2.name).unwrap().truncate()?;
+                        Ok(())
+                    },
+                )
+        };
+
+        let m = migrations();
+        assert_eq!(m.pending().unwrap(), ["create space", "insert data"]);
+        assert_eq!(m.up().unwrap(), 2);
+        assert_eq!(m.applied().unwrap(), ["create space", "insert data"]);
+        assert_eq!(m.up().unwrap(), 0);
+        assert_eq!(counter.get(), 1);
+        let space = Space::find(&space_name).unwrap();
+        assert_eq!(space.len().unwrap(), 1);
+
+        assert_eq!(m.down().unwrap(), Some("insert data"));
+        assert_eq!(space.len().unwrap(), 0);
+        assert_eq!(m.pending().unwrap(), ["insert data"]);
+        let e = m.down().unwrap_err();
+        assert_eq!(e.to_string(), "migration 'create space' is irreversible");
+
+        // A failed migration is rolled back and not recorded.
+        let name = space_name.clone();
+        let m = migrations().add("fail", move || {
+            Space::find(&name).unwrap().insert(&(2, "Alice"))?;
+            Err(Error::other("oops"))
+        });
+        let e = m.up().unwrap_err();
+        assert_eq!(e.to_string(), "oops");
+        assert_eq!(m.applied().unwrap(), ["create space", "insert data"]);
+        assert_eq!(counter.get(), 2);
+        assert_eq!(space.len().unwrap(), 1);
+
+        // History mismatch is detected.
+        let m = Migrations::with_space_name(&records_space).add("something else", || Ok(()));
+        let e = m.up().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "applied migration #0 'create space' doesn't match the declared migration 'something else'"
+        );
+
+        space.drop().unwrap();
+        Space::find(&records_space).unwrap().drop().unwrap();
+    }
+}
@@ -1,8 +1,9 @@
-#[cfg(feature = "picodata")]
 pub mod function;
 pub mod index;
+pub mod migrations;
 pub mod sequence;
 pub mod space;
+pub mod user;
 
 use crate::error::Error;
 use crate::index::IteratorType;
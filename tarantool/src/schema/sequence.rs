@@ -1,6 +1,100 @@
-use crate::error::Error;
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::index::IteratorType;
 use crate::schema;
-use crate::space::{Space, SystemSpace};
+use crate::sequence::{SequenceAlterOptions, SequenceCreateOptions};
+use crate::session;
+use crate::set_error;
+use crate::space::{Space, SpaceId, SystemSpace};
+
+/// Create new sequence.
+///
+/// - `name` - name of sequence, which should conform to the rules for object names.
+/// - `opts` - see SequenceCreateOptions struct.
+///
+/// Returns ID of the sequence.
+///
+/// For details see [box.schema.sequence.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema_sequence/create/)
+pub fn create_sequence(name: &str, opts: &SequenceCreateOptions) -> Result<u32, Error> {
+    let sys_sequence: Space = SystemSpace::Sequence.into();
+    if let Some(t) = sys_sequence.index("name").unwrap().get(&(name,))? {
+        if opts.if_not_exists {
+            return Ok(t.field::<u32>(0)?.unwrap());
+        }
+        set_error!(TarantoolErrorCode::SequenceExists, "{}", name);
+        return Err(TarantoolError::last().into());
+    }
+
+    let step = opts.step.unwrap_or(1);
+    let (default_min, default_max) = if step > 0 {
+        (1, i64::MAX)
+    } else {
+        (i64::MIN, -1)
+    };
+    let min = opts.min.unwrap_or(default_min);
+    let max = opts.max.unwrap_or(default_max);
+    let start = opts.start.unwrap_or(if step > 0 { min } else { max });
+
+    // Same as `_sequence:auto_increment` in lua.
+    let id = match sys_sequence
+        .primary_key()
+        .select(IteratorType::LE, &())?
+        .next()
+    {
+        Some(t) => t.field::<u32>(0)?.unwrap() + 1,
+        None => 1,
+    };
+
+    sys_sequence.insert(&(
+        id,
+        session::euid()?,
+        name,
+        step,
+        min,
+        max,
+        start,
+        opts.cache.unwrap_or(0),
+        opts.cycle,
+    ))?;
+
+    Ok(id)
+}
+
+/// Alter existing sequence.
+///
+/// - `seq_id` - ID of existing sequence.
+/// - `opts` - see SequenceAlterOptions struct. Only the specified options are changed.
+pub fn alter_sequence(seq_id: u32, opts: &SequenceAlterOptions) -> Result<(), Error> {
+    let sys_sequence: Space = SystemSpace::Sequence.into();
+    let Some(t) = sys_sequence.get(&(seq_id,))? else {
+        set_error!(TarantoolErrorCode::NoSuchSequence, "{}", seq_id);
+        return Err(TarantoolError::last().into());
+    };
+    let (id, owner, name, step, min, max, start, cache, cycle): (
+        u32,
+        u32,
+        String,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        bool,
+    ) = t.decode()?;
+
+    sys_sequence.replace(&(
+        id,
+        owner,
+        opts.name.as_deref().unwrap_or(&name),
+        opts.step.unwrap_or(step),
+        opts.min.unwrap_or(min),
+        opts.max.unwrap_or(max),
+        opts.start.unwrap_or(start),
+        opts.cache.unwrap_or(cache),
+        opts.cycle.unwrap_or(cycle),
+    ))?;
+
+    Ok(())
+}
 
 /// Drop existing sequence.
 ///
@@ -8,11 +102,40 @@ use crate::space::{Space, SystemSpace};
 pub fn drop_sequence(seq_id: u32) -> Result<(), Error> {
     schema::revoke_object_privileges("sequence", seq_id)?;
 
+    let sys_sequence_data: Space = SystemSpace::SequenceData.into();
+    sys_sequence_data.delete(&(seq_id,))?;
+
     let sys_sequence: Space = SystemSpace::Sequence.into();
     sys_sequence.delete(&(seq_id,))?;
 
-    let sys_sequence_data: Space = SystemSpace::SequenceData.into();
-    sys_sequence_data.delete(&(seq_id,))?;
+    Ok(())
+}
 
+/// Attach existing sequence to the primary key of a space.
+///
+/// - `space_id` - ID of existing space.
+/// - `seq_id` - ID of existing sequence.
+/// - `field` - number of the field (starting from 0) to be generated, must be
+///   a part of the primary key.
+///
+/// If the space already has a sequence attached, it is replaced.
+pub fn set_space_sequence(space_id: SpaceId, seq_id: u32, field: u32) -> Result<(), Error> {
+    let sys_space_sequence: Space = SystemSpace::SpaceSequence.into();
+    sys_space_sequence.replace(&(space_id, seq_id, false, field, ""))?;
+    Ok(())
+}
+
+/// Detach the sequence from the primary key of a space. Does nothing if the
+/// space doesn't have a sequence attached.
+///
+/// Note that unlike [`drop_space`] this doesn't drop the sequence even if it
+/// was generated automatically.
+///
+/// - `space_id` - ID of existing space.
+///
+/// [`drop_space`]: crate::schema::space::drop_space
+pub fn reset_space_sequence(space_id: SpaceId) -> Result<(), Error> {
+    let sys_space_sequence: Space = SystemSpace::SpaceSequence.into();
+    sys_space_sequence.delete(&(space_id,))?;
     Ok(())
 }
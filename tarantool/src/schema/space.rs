@@ -6,12 +6,13 @@ use crate::session;
 use crate::set_error;
 use crate::space;
 use crate::space::space_id_temporary_min;
-use crate::space::{Metadata, SpaceCreateOptions};
+use crate::space::{Metadata, SpaceAlterOptions, SpaceCreateOptions};
 use crate::space::{Space, SpaceId, SpaceType, SystemSpace};
 use crate::transaction;
 use crate::tuple::Tuple;
 use crate::unwrap_or;
 use crate::util::Value;
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 /// Create a space.
@@ -81,14 +82,7 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
         .format
         .iter()
         .flat_map(|f| f.iter())
-        .map(|f| {
-            IntoIterator::into_iter([
-                ("name".into(), Value::Str(f.name.as_str().into())),
-                ("type".into(), Value::Str(f.field_type.as_str().into())),
-                ("is_nullable".into(), Value::Bool(f.is_nullable)),
-            ])
-            .collect()
-        })
+        .map(field_to_metadata)
         .collect();
 
     let nested_transaction = transaction::is_in_transaction();
@@ -144,6 +138,82 @@ pub fn create_space(name: &str, opts: &SpaceCreateOptions) -> Result<Space, Erro
     Ok(space)
 }
 
+/// Alter a space.
+/// (for details see [space_object:alter()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/alter/)).
+///
+/// - `space_id` - ID of existing space.
+/// - `opts` - see SpaceAlterOptions struct.
+///
+/// The changes are written into `_space` as a single statement, so the rest
+/// of the checks (e.g. whether the new format is compatible with the data)
+/// are done by tarantool.
+pub fn alter_space(space_id: SpaceId, opts: &SpaceAlterOptions) -> Result<(), Error> {
+    let sys_space = SystemSpace::Space.as_space();
+    let Some(tuple) = sys_space.get(&(space_id,))? else {
+        set_error!(TarantoolErrorCode::NoSuchSpace, "{}", space_id);
+        return Err(TarantoolError::last().into());
+    };
+    let mut meta: Metadata = tuple.decode()?;
+
+    if let Some(name) = &opts.name {
+        if let Some(space) = Space::find(name) {
+            if space.id() != space_id {
+                set_error!(TarantoolErrorCode::SpaceExists, "{}", name);
+                return Err(TarantoolError::last().into());
+            }
+        }
+        meta.name = name.clone().into();
+    }
+
+    if let Some(user) = &opts.user {
+        let Some(uid) = schema::resolve_user_or_role(user)? else {
+            set_error!(TarantoolErrorCode::NoSuchUser, "{}", user);
+            return Err(TarantoolError::last().into());
+        };
+        meta.user_id = uid;
+    }
+
+    if let Some(is_sync) = opts.is_sync {
+        if is_sync {
+            meta.flags.insert("is_sync".into(), Value::Bool(true));
+        } else {
+            meta.flags.remove("is_sync");
+        }
+    }
+
+    if let Some(format) = &opts.format {
+        meta.format = format.iter().map(field_to_metadata).collect();
+    }
+    for field in &opts.add_fields {
+        let exists = meta
+            .format
+            .iter()
+            .any(|f| matches!(f.get("name"), Some(Value::Str(n)) if *n == field.name));
+        if exists {
+            set_error!(
+                TarantoolErrorCode::AlterSpace,
+                "{}: field '{}' already exists",
+                meta.name,
+                field.name
+            );
+            return Err(TarantoolError::last().into());
+        }
+        meta.format.push(field_to_metadata(field));
+    }
+
+    sys_space.replace(&meta)?;
+    Ok(())
+}
+
+fn field_to_metadata(field: &space::Field) -> BTreeMap<Cow<'static, str>, Value<'static>> {
+    IntoIterator::into_iter([
+        ("name".into(), Value::Str(field.name.clone().into())),
+        ("type".into(), Value::Str(field.field_type.as_str().into())),
+        ("is_nullable".into(), Value::Bool(field.is_nullable)),
+    ])
+    .collect()
+}
+
 #[deprecated = "use `tarantool::space::Metadata` instead"]
 pub type SpaceMetadata<'a> = Metadata<'a>;
 
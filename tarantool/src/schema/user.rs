@@ -0,0 +1,611 @@
+//! Users, roles and privileges.
+//!
+//! Functions in this module manage users and roles, and the privileges granted
+//! to them, by writing directly into the `_user` and `_priv` system spaces,
+//! the same way `box.schema.user.*` & `box.schema.role.*` lua functions do.
+//!
+//! Every function which modifies several system space records does so within a
+//! [`nested_transaction`], so either all of the changes are applied or none.
+//!
+//! # Example
+//! ```no_run
+//! use tarantool::access_control::{PrivType, SchemaObjectType};
+//! use tarantool::schema::user::{self, UserCreateOptions};
+//! use tarantool::space::Space;
+//!
+//! user::create_user("alice", &UserCreateOptions {
+//!     password: Some("secret".into()),
+//!     ..Default::default()
+//! })
+//! .unwrap();
+//! user::create_role("readers", false).unwrap();
+//! user::grant("readers", PrivType::Read, SchemaObjectType::EntitySpace, 0).unwrap();
+//! user::grant_role("alice", "readers").unwrap();
+//!
+//! let privileges = user::effective_privileges("alice").unwrap();
+//! assert!(privileges
+//!     .iter()
+//!     .any(|p| p.object_type == SchemaObjectType::EntitySpace && p.contains(PrivType::Read)));
+//! ```
+//!
+//! [`nested_transaction`]: crate::transaction::nested_transaction
+
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::access_control::{PrivType, SchemaObjectType};
+use crate::auth::{AuthData, AuthMethod};
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::index::IteratorType;
+use crate::schema;
+use crate::session::{self, UserId};
+use crate::set_error;
+use crate::space::{Space, SystemSpace, UpdateOps};
+use crate::transaction;
+use crate::tuple::Tuple;
+use crate::util::NumOrStr;
+
+/// Id of the builtin user `guest`.
+pub const GUEST_ID: UserId = 0;
+/// Id of the builtin user `admin`.
+pub const ADMIN_ID: UserId = 1;
+/// Id of the builtin role `public`, which is granted to every user.
+pub const PUBLIC_ROLE_ID: UserId = 2;
+/// Id of the builtin role `super`.
+pub const SUPER_ROLE_ID: UserId = 31;
+
+////////////////////////////////////////////////////////////////////////////////
+// UserCreateOptions
+////////////////////////////////////////////////////////////////////////////////
+
+/// User configuration, see [`create_user`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserCreateOptions {
+    /// If `true` and a user with the given name already exists, the existing
+    /// user's id is returned instead of an error.
+    pub if_not_exists: bool,
+    /// If `None` the user can only connect without a password (or not at all).
+    pub password: Option<String>,
+    /// Method used to compute the stored password digest.
+    pub auth_method: AuthMethod,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// users & roles
+////////////////////////////////////////////////////////////////////////////////
+
+/// Create a user.
+///
+/// Just like `box.schema.user.create` this grants the user role `public`,
+/// the `alter` privilege on itself and the `session` & `usage` privileges on
+/// the universe.
+///
+/// Returns the id of the new user.
+pub fn create_user(name: &str, opts: &UserCreateOptions) -> Result<UserId, Error> {
+    if let Some(id) = schema::resolve_user_or_role(name)? {
+        if opts.if_not_exists {
+            return Ok(id);
+        }
+        set_error!(TarantoolErrorCode::UserExists, "{}", name);
+        return Err(TarantoolError::last().into());
+    }
+
+    let mut auth = HashMap::new();
+    if let Some(password) = &opts.password {
+        let data = AuthData::new(&opts.auth_method, name, password);
+        auth.insert(opts.auth_method.as_str(), data.into_string());
+    }
+
+    let id = transaction::nested_transaction(|| -> Result<_, Error> {
+        let id = insert_user(name, "user", &auth)?;
+        grant_impl(
+            session::euid()?,
+            id,
+            PrivType::Execute as _,
+            SchemaObjectType::Role,
+            PUBLIC_ROLE_ID,
+        )?;
+        grant_impl(
+            session::euid()?,
+            id,
+            PrivType::Alter as _,
+            SchemaObjectType::User,
+            id,
+        )?;
+        // These are granted on behalf of admin, because the current user may
+        // not be allowed to do it, just like in `box.schema.user.create`.
+        session::with_su(ADMIN_ID, || {
+            grant_impl(
+                ADMIN_ID,
+                id,
+                PrivType::Session as u32 | PrivType::Usage as u32,
+                SchemaObjectType::Universe,
+                0,
+            )
+        })??;
+        Ok(id)
+    })?;
+
+    Ok(id)
+}
+
+/// Create a role.
+///
+/// If `if_not_exists` is `true` and a role with the given name already exists,
+/// the existing role's id is returned instead of an error.
+///
+/// Returns the id of the new role.
+pub fn create_role(name: &str, if_not_exists: bool) -> Result<UserId, Error> {
+    if let Some(id) = schema::resolve_user_or_role(name)? {
+        if if_not_exists {
+            return Ok(id);
+        }
+        set_error!(TarantoolErrorCode::RoleExists, "{}", name);
+        return Err(TarantoolError::last().into());
+    }
+
+    insert_user(name, "role", &HashMap::new())
+}
+
+/// Drop a user or a role.
+///
+/// All of the privileges granted to the user or role are revoked, and if it's
+/// a role it's revoked from everyone it was granted to. Note that tarantool
+/// doesn't allow dropping users which own any spaces, functions or sequences,
+/// or have granted any privileges to others.
+///
+/// If `if_exists` is `true` and there's no user or role with the given name,
+/// nothing is done.
+pub fn drop(name: &str, if_exists: bool) -> Result<(), Error> {
+    let Some(id) = schema::resolve_user_or_role(name)? else {
+        if if_exists {
+            return Ok(());
+        }
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", name);
+        return Err(TarantoolError::last().into());
+    };
+
+    if id == session::uid()? {
+        set_error!(
+            TarantoolErrorCode::DropUser,
+            "{}: the user is active in the current session",
+            name
+        );
+        return Err(TarantoolError::last().into());
+    }
+
+    transaction::nested_transaction(|| -> Result<_, Error> {
+        let sys_vpriv: Space = SystemSpace::VPriv.into();
+        let sys_priv: Space = SystemSpace::Priv.into();
+
+        let mut privs: Vec<Tuple> = sys_vpriv
+            .primary_key()
+            .select(IteratorType::Eq, &(id,))?
+            .collect();
+        let index_obj = sys_vpriv.index("object").unwrap();
+        for object_type in ["user", "role"] {
+            privs.extend(index_obj.select(IteratorType::Eq, &(object_type, id))?);
+        }
+
+        for t in privs {
+            let grantee = t.field::<UserId>(1)?.unwrap();
+            let object_type = t.field::<String>(2)?.unwrap();
+            let object_id = t.field::<NumOrStr>(3)?.unwrap();
+            sys_priv.delete(&(grantee, object_type, object_id))?;
+        }
+
+        let sys_user: Space = SystemSpace::User.into();
+        sys_user.delete(&(id,))?;
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Set the `password` of the user with the given `name`, the digest is
+/// computed using the given auth `method`.
+pub fn passwd(name: &str, password: &str, method: AuthMethod) -> Result<(), Error> {
+    let id = resolve(name, "user")?;
+    let data = AuthData::new(&method, name, password);
+    let auth = HashMap::from([(method.as_str(), data.into_string())]);
+
+    let mut ops = UpdateOps::new();
+    ops.assign("auth", auth)?;
+    let sys_user: Space = SystemSpace::User.into();
+    sys_user.update(&(id,), ops)?;
+    Ok(())
+}
+
+fn insert_user(name: &str, kind: &str, auth: &HashMap<&str, String>) -> Result<UserId, Error> {
+    let sys_user: Space = SystemSpace::User.into();
+    // Same as `_user:auto_increment` in lua.
+    let id = match sys_user.primary_key().select(IteratorType::LE, &())?.next() {
+        Some(t) => t.field::<UserId>(0)?.unwrap() + 1,
+        None => 0,
+    };
+    let last_modified = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let auth_history: [(); 0] = [];
+    sys_user.insert(&(
+        id,
+        session::euid()?,
+        name,
+        kind,
+        auth,
+        auth_history,
+        last_modified,
+    ))?;
+    Ok(id)
+}
+
+/// Returns id of the user (if `kind` is "user") or the role (if `kind` is
+/// "role") with the given `name`.
+fn resolve(name: &str, kind: &str) -> Result<UserId, Error> {
+    let sys_vuser: Space = SystemSpace::VUser.into();
+    let name_idx = sys_vuser.index("name").unwrap();
+    if let Some(t) = name_idx.get(&(name,))? {
+        if t.field::<&str>(3)? == Some(kind) {
+            return Ok(t.field::<UserId>(0)?.unwrap());
+        }
+    }
+
+    if kind == "role" {
+        set_error!(TarantoolErrorCode::NoSuchRole, "{}", name);
+    } else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", name);
+    }
+    Err(TarantoolError::last().into())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// privileges
+////////////////////////////////////////////////////////////////////////////////
+
+/// A record of the `_priv` system space.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Privilege {
+    /// Id of the user who granted the privileges.
+    pub grantor: UserId,
+    /// Id of the user or role the privileges are granted to.
+    pub grantee: UserId,
+    pub object_type: SchemaObjectType,
+    /// Id of the object. Always `0` for [`SchemaObjectType::Universe`] and the
+    /// entity types.
+    pub object_id: u32,
+    /// Bitmask of [`PrivType`]s.
+    pub privileges: u32,
+}
+
+impl Privilege {
+    /// Returns `true` if `privilege` is set in [`Self::privileges`].
+    #[inline(always)]
+    pub fn contains(&self, privilege: PrivType) -> bool {
+        let privilege = privilege as u32;
+        self.privileges & privilege == privilege
+    }
+
+    fn from_tuple(t: &Tuple) -> Result<Self, Error> {
+        let object_type = t.field::<String>(2)?.unwrap();
+        let object_id = t.field::<NumOrStr>(3)?.unwrap();
+        let (object_type, object_id) = match object_id {
+            NumOrStr::Num(id) => (object_type_from_name(&object_type, false), id),
+            NumOrStr::Str(_) => (object_type_from_name(&object_type, true), 0),
+        };
+        Ok(Self {
+            grantor: t.field(0)?.unwrap(),
+            grantee: t.field(1)?.unwrap(),
+            object_type,
+            object_id,
+            privileges: t.field(4)?.unwrap(),
+        })
+    }
+}
+
+fn object_type_from_name(name: &str, is_entity: bool) -> SchemaObjectType {
+    use SchemaObjectType::*;
+    match (name, is_entity) {
+        ("universe", _) => Universe,
+        ("space", false) => Space,
+        ("space", true) => EntitySpace,
+        ("function", false) => Function,
+        ("function", true) => EntityFunction,
+        ("user", false) => User,
+        ("user", true) => EntityUser,
+        ("role", false) => Role,
+        ("role", true) => EntityRole,
+        ("sequence", false) => Sequence,
+        ("sequence", true) => EntitySequence,
+        ("collation", false) => Collation,
+        ("collation", true) => EntityCollation,
+        _ => Unknown,
+    }
+}
+
+/// Returns the value of the `object_id` field of a `_priv` record.
+fn priv_object_id(object_type: SchemaObjectType, object_id: u32) -> Result<NumOrStr, Error> {
+    match object_type {
+        SchemaObjectType::Unknown
+        | SchemaObjectType::ObjectTypeMax
+        | SchemaObjectType::EntityTypeMax => {
+            set_error!(
+                TarantoolErrorCode::UnknownSchemaObject,
+                "Unknown object type '{:?}'",
+                object_type
+            );
+            Err(TarantoolError::last().into())
+        }
+        SchemaObjectType::Universe => Ok(NumOrStr::Num(0)),
+        t if t.is_entity() => Ok(NumOrStr::Str("".into())),
+        _ => Ok(NumOrStr::Num(object_id)),
+    }
+}
+
+/// Grant the `privilege` on the object to the user or role `grantee`.
+///
+/// `object_id` is ignored for [`SchemaObjectType::Universe`] and the entity
+/// types, e.g. [`SchemaObjectType::EntitySpace`] means all spaces.
+///
+/// Granting a privilege which is already granted is not an error.
+pub fn grant(
+    grantee: &str,
+    privilege: PrivType,
+    object_type: SchemaObjectType,
+    object_id: u32,
+) -> Result<(), Error> {
+    let Some(grantee) = schema::resolve_user_or_role(grantee)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", grantee);
+        return Err(TarantoolError::last().into());
+    };
+    grant_impl(
+        session::euid()?,
+        grantee,
+        privilege as _,
+        object_type,
+        object_id,
+    )
+}
+
+/// Revoke the `privilege` on the object from the user or role `grantee`.
+///
+/// See [`grant`] for the meaning of `object_id`.
+///
+/// Revoking a privilege which isn't granted is not an error.
+pub fn revoke(
+    grantee: &str,
+    privilege: PrivType,
+    object_type: SchemaObjectType,
+    object_id: u32,
+) -> Result<(), Error> {
+    let Some(grantee) = schema::resolve_user_or_role(grantee)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", grantee);
+        return Err(TarantoolError::last().into());
+    };
+    revoke_impl(grantee, privilege as _, object_type, object_id)
+}
+
+/// Grant the `role` to the user or role `grantee`.
+pub fn grant_role(grantee: &str, role: &str) -> Result<(), Error> {
+    let role = resolve(role, "role")?;
+    let Some(grantee) = schema::resolve_user_or_role(grantee)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", grantee);
+        return Err(TarantoolError::last().into());
+    };
+    grant_impl(
+        session::euid()?,
+        grantee,
+        PrivType::Execute as _,
+        SchemaObjectType::Role,
+        role,
+    )
+}
+
+/// Revoke the `role` from the user or role `grantee`.
+pub fn revoke_role(grantee: &str, role: &str) -> Result<(), Error> {
+    let role = resolve(role, "role")?;
+    let Some(grantee) = schema::resolve_user_or_role(grantee)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", grantee);
+        return Err(TarantoolError::last().into());
+    };
+    revoke_impl(
+        grantee,
+        PrivType::Execute as _,
+        SchemaObjectType::Role,
+        role,
+    )
+}
+
+fn grant_impl(
+    grantor: UserId,
+    grantee: UserId,
+    privileges: u32,
+    object_type: SchemaObjectType,
+    object_id: u32,
+) -> Result<(), Error> {
+    let object_id = priv_object_id(object_type, object_id)?;
+    let key = (grantee, object_type.name(), object_id);
+    let sys_priv: Space = SystemSpace::Priv.into();
+    let old = match SystemSpace::VPriv.as_space().get(&key)? {
+        Some(t) => t.field::<u32>(4)?.unwrap(),
+        None => 0,
+    };
+    let (grantee, object_type, object_id) = key;
+    sys_priv.replace(&(grantor, grantee, object_type, object_id, old | privileges))?;
+    Ok(())
+}
+
+fn revoke_impl(
+    grantee: UserId,
+    privileges: u32,
+    object_type: SchemaObjectType,
+    object_id: u32,
+) -> Result<(), Error> {
+    let object_id = priv_object_id(object_type, object_id)?;
+    let key = (grantee, object_type.name(), object_id);
+    let Some(t) = SystemSpace::VPriv.as_space().get(&key)? else {
+        return Ok(());
+    };
+    let old = Privilege::from_tuple(&t)?;
+    let new = old.privileges & !privileges;
+
+    let sys_priv: Space = SystemSpace::Priv.into();
+    if new == 0 {
+        sys_priv.delete(&key)?;
+    } else {
+        let (grantee, object_type, object_id) = key;
+        sys_priv.replace(&(old.grantor, grantee, object_type, object_id, new))?;
+    }
+    Ok(())
+}
+
+/// Returns the privileges granted directly to the user or role with the given
+/// `name`.
+pub fn privileges(name: &str) -> Result<Vec<Privilege>, Error> {
+    let Some(id) = schema::resolve_user_or_role(name)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", name);
+        return Err(TarantoolError::last().into());
+    };
+    privileges_by_id(id)
+}
+
+/// Returns the privileges of the user or role with the given `name`,
+/// including the ones it has through the roles granted to it (recursively).
+///
+/// The [`Privilege::grantee`] field of each record specifies which role (or
+/// the user itself) the privileges were granted to.
+pub fn effective_privileges(name: &str) -> Result<Vec<Privilege>, Error> {
+    let Some(id) = schema::resolve_user_or_role(name)? else {
+        set_error!(TarantoolErrorCode::NoSuchUser, "{}", name);
+        return Err(TarantoolError::last().into());
+    };
+
+    let mut res = vec![];
+    let mut visited = HashSet::from([id]);
+    let mut queue = vec![id];
+    while let Some(id) = queue.pop() {
+        for privilege in privileges_by_id(id)? {
+            if privilege.object_type == SchemaObjectType::Role
+                && privilege.contains(PrivType::Execute)
+                && visited.insert(privilege.object_id)
+            {
+                queue.push(privilege.object_id);
+            }
+            res.push(privilege);
+        }
+    }
+    Ok(res)
+}
+
+fn privileges_by_id(id: UserId) -> Result<Vec<Privilege>, Error> {
+    let sys_vpriv: Space = SystemSpace::VPriv.into();
+    let mut res = vec![];
+    for t in sys_vpriv.primary_key().select(IteratorType::Eq, &(id,))? {
+        res.push(Privilege::from_tuple(&t)?);
+    }
+    Ok(res)
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+
+    #[crate::test(tarantool = "crate")]
+    fn user_and_role_lifecycle() {
+        let user = crate::temp_space_name!();
+        let role = crate::temp_space_name!();
+        let space = Space::builder(&crate::temp_space_name!()).create().unwrap();
+
+        let opts = UserCreateOptions {
+            password: Some("password".into()),
+            ..Default::default()
+        };
+        let user_id = create_user(&user, &opts).unwrap();
+        let e = create_user(&user, &opts).unwrap_err();
+        assert_eq!(e.to_string(), format!("box error: UserExists: {user}"));
+        let opts = UserCreateOptions {
+            if_not_exists: true,
+            ..opts
+        };
+        assert_eq!(create_user(&user, &opts).unwrap(), user_id);
+
+        // Same as in lua
+        let auth: String = crate::global_lua()
+            .eval_with(
+                "return box.space._user.index.name:get(...)[5]['chap-sha1']",
+                &user,
+            )
+            .unwrap();
+        assert_eq!(auth, "JHDAwG3uQv0WGLuZAFrcouydHhk=");
+        passwd(&user, "other", AuthMethod::ChapSha1).unwrap();
+        let auth: String = crate::global_lua()
+            .eval_with(
+                "return box.space._user.index.name:get(...)[5]['chap-sha1']",
+                &user,
+            )
+            .unwrap();
+        let expected: String = crate::global_lua()
+            .eval("return box.schema.user.password('other')")
+            .unwrap();
+        assert_eq!(auth, expected);
+
+        let privs = privileges(&user).unwrap();
+        assert!(privs
+            .iter()
+            .any(|p| p.object_type == SchemaObjectType::Universe
+                && p.contains(PrivType::Session)
+                && p.contains(PrivType::Usage)));
+        assert!(privs
+            .iter()
+            .any(|p| p.object_type == SchemaObjectType::Role && p.object_id == PUBLIC_ROLE_ID));
+
+        create_role(&role, false).unwrap();
+        grant(&role, PrivType::Read, SchemaObjectType::Space, space.id()).unwrap();
+        grant(&role, PrivType::Write, SchemaObjectType::Space, space.id()).unwrap();
+        grant_role(&user, &role).unwrap();
+
+        let can_write: bool = crate::global_lua()
+            .eval_with(
+                "local user, space = ...
+                return box.session.su(user, function()
+                    return pcall(box.space[space].replace, box.space[space], {1})
+                end)",
+                (&user, space.meta().unwrap().name.as_ref()),
+            )
+            .unwrap();
+        assert!(can_write);
+
+        let effective = effective_privileges(&user).unwrap();
+        let on_space: Vec<_> = effective
+            .iter()
+            .filter(|p| p.object_type == SchemaObjectType::Space && p.object_id == space.id())
+            .collect();
+        assert_eq!(on_space.len(), 1);
+        assert!(on_space[0].contains(PrivType::Read));
+        assert!(on_space[0].contains(PrivType::Write));
+
+        revoke(&role, PrivType::Write, SchemaObjectType::Space, space.id()).unwrap();
+        let privs = privileges(&role).unwrap();
+        assert_eq!(privs.len(), 1);
+        assert!(privs[0].contains(PrivType::Read));
+        assert!(!privs[0].contains(PrivType::Write));
+        revoke(&role, PrivType::Read, SchemaObjectType::Space, space.id()).unwrap();
+        assert_eq!(privileges(&role).unwrap(), []);
+
+        revoke_role(&user, &role).unwrap();
+        assert!(!effective_privileges(&user)
+            .unwrap()
+            .iter()
+            .any(|p| p.object_type == SchemaObjectType::Role && p.object_id != PUBLIC_ROLE_ID));
+
+        grant_role(&user, &role).unwrap();
+        drop(&role, false).unwrap();
+        assert_eq!(schema::resolve_user_or_role(&role).unwrap(), None);
+        drop(&user, false).unwrap();
+        assert_eq!(schema::resolve_user_or_role(&user).unwrap(), None);
+        drop(&user, true).unwrap();
+        let e = drop(&user, false).unwrap_err();
+        assert_eq!(e.to_string(), format!("box error: NoSuchUser: {user}"));
+
+        space.drop().unwrap();
+    }
+}
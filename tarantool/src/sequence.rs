@@ -1,6 +1,7 @@
 //! Box: sequences
 use crate::error::{Error, TarantoolError};
 use crate::ffi::tarantool as ffi;
+use crate::schema::sequence as schema_seq;
 use crate::space::{Space, SystemSpace};
 
 /// A sequence is a generator of ordered integer values.
@@ -9,6 +10,36 @@ pub struct Sequence {
 }
 
 impl Sequence {
+    /// Return a sequence builder.
+    ///
+    /// - `name` - name of sequence to be created
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::sequence::Sequence;
+    ///
+    /// let mut seq = Sequence::builder("ids").start(100).step(10).create().unwrap();
+    /// assert_eq!(seq.next().unwrap(), 100);
+    /// assert_eq!(seq.next().unwrap(), 110);
+    /// ```
+    #[inline(always)]
+    pub fn builder(name: &str) -> Builder {
+        Builder::new(name)
+    }
+
+    /// Create a sequence.
+    /// (for details see [box.schema.sequence.create()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema_sequence/create/)).
+    ///
+    /// - `name` - name of sequence, which should conform to the rules for object names.
+    /// - `opts` - see SequenceCreateOptions struct.
+    ///
+    /// Returns a new sequence.
+    #[inline]
+    pub fn create(name: &str, opts: &SequenceCreateOptions) -> Result<Self, Error> {
+        let seq_id = schema_seq::create_sequence(name, opts)?;
+        Ok(Self { seq_id })
+    }
+
     /// Find sequence by name.
     pub fn find(name: &str) -> Result<Option<Self>, Error> {
         let space: Space = SystemSpace::Sequence.into();
@@ -52,6 +83,17 @@ impl Sequence {
         }
     }
 
+    /// Return the last value generated by the sequence or `None` if
+    /// [`next`](#method.next) hasn't been called yet since the sequence was
+    /// created or [reset](#method.reset).
+    pub fn current(&self) -> Result<Option<i64>, Error> {
+        let space: Space = SystemSpace::SequenceData.into();
+        match space.get(&(self.seq_id,))? {
+            None => Ok(None),
+            Some(row_tuple) => Ok(row_tuple.field(1)?),
+        }
+    }
+
     /// Set the sequence back to its original state.
     ///
     /// The effect is that a subsequent [next](#method.next) will return the start value.
@@ -63,4 +105,138 @@ impl Sequence {
             Ok(())
         }
     }
+
+    /// Get sequence ID.
+    #[inline(always)]
+    pub fn id(&self) -> u32 {
+        self.seq_id
+    }
+
+    /// Change the sequence's options. Only the options specified in `opts`
+    /// are changed.
+    ///
+    /// Note that this doesn't change the current value of the sequence, use
+    /// [reset](#method.reset) for that.
+    #[inline(always)]
+    pub fn alter(&self, opts: &SequenceAlterOptions) -> Result<(), Error> {
+        schema_seq::alter_sequence(self.seq_id, opts)
+    }
+
+    /// Drop the sequence.
+    ///
+    /// A sequence attached to a space can't be dropped, use
+    /// [`detach`](#method.detach) first.
+    #[inline(always)]
+    pub fn drop(&self) -> Result<(), Error> {
+        schema_seq::drop_sequence(self.seq_id)
+    }
+
+    /// Attach the sequence to the primary key of the `space`, so that the
+    /// value of the field `field` (starting from 0) is generated
+    /// automatically if it's `nil` in the inserted tuple. The field must be a
+    /// part of the primary key.
+    ///
+    /// Same as specifying `sequence` option in [`index::Builder`](crate::index::Builder)
+    /// for the primary index.
+    #[inline(always)]
+    pub fn attach(&self, space: &Space, field: u32) -> Result<(), Error> {
+        schema_seq::set_space_sequence(space.id(), self.seq_id, field)
+    }
+
+    /// Detach any sequence from the primary key of the `space`.
+    #[inline(always)]
+    pub fn detach(space: &Space) -> Result<(), Error> {
+        schema_seq::reset_space_sequence(space.id())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SequenceCreateOptions
+////////////////////////////////////////////////////////////////////////////////
+
+/// List of options for a new sequence.
+///
+/// Options which are `None` are set to the same defaults as in lua:
+/// - `step` defaults to `1`,
+/// - `min` and `max` default to `1` and `i64::MAX` if `step` is positive or to
+///   `i64::MIN` and `-1` otherwise,
+/// - `start` defaults to `min` if `step` is positive or to `max` otherwise,
+/// - `cache` defaults to `0`.
+///
+/// For details see [box.schema.sequence.create() - options](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema_sequence/create/).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SequenceCreateOptions {
+    pub if_not_exists: bool,
+    pub start: Option<i64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub step: Option<i64>,
+    pub cycle: bool,
+    pub cache: Option<i64>,
+}
+
+/// List of options for [`Sequence::alter`]. Options which are `None` are not
+/// changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SequenceAlterOptions {
+    pub name: Option<String>,
+    pub start: Option<i64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub step: Option<i64>,
+    pub cycle: Option<bool>,
+    pub cache: Option<i64>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Builder
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Builder<'a> {
+    name: &'a str,
+    opts: SequenceCreateOptions,
+}
+
+macro_rules! define_setters {
+    ($( $setter:ident ( $field:ident : $ty:ty ) )+) => {
+        $(
+            #[inline(always)]
+            pub fn $setter(mut self, $field: $ty) -> Self {
+                self.opts.$field = $field.into();
+                self
+            }
+        )+
+    }
+}
+
+impl<'a> Builder<'a> {
+    #[inline(always)]
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            opts: Default::default(),
+        }
+    }
+
+    define_setters! {
+        if_not_exists(if_not_exists: bool)
+        start(start: i64)
+        min(min: i64)
+        max(max: i64)
+        step(step: i64)
+        cycle(cycle: bool)
+        cache(cache: i64)
+    }
+
+    /// Create a sequence with the current configuration.
+    #[inline(always)]
+    pub fn create(self) -> crate::Result<Sequence> {
+        Sequence::create(self.name, &self.opts)
+    }
+
+    /// Destructure the builder struct into a tuple of name and sequence options.
+    #[inline(always)]
+    pub fn into_parts(self) -> (&'a str, SequenceCreateOptions) {
+        (self.name, self.opts)
+    }
 }
@@ -3,11 +3,46 @@
 //! A session is an object associated with each client connection.
 //! box.session submodule provides functions to query session state.
 //!
+//! The current session is represented by [`Session::current`]. It provides
+//! access to the session's [id](Session::id), [peer](Session::peer),
+//! [type](Session::type_) and [user](Session::user), allows to
+//! [push](Session::push) data into the session's channel and to attach values
+//! of arbitrary types to the session (see [`Session::set`]).
+//!
+//! Use [`on_connect`], [`on_disconnect`] and [`on_auth`] to register session
+//! triggers.
+//!
+//! # Picodata vs vanilla tarantool
+//!
+//! With the `picodata` feature the session id, user ids and [`su`] are
+//! implemented via the ffi api directly.
+//!
+//! Vanilla tarantool only exports this api in newer versions. If the current
+//! executable doesn't support it (see [`crate::ffi::has_session_api`]) the
+//! same functions fall back to a less efficient implementation based on the
+//! lua interface (`box.session.id`, `box.session.uid`, etc.).
+//!
+//! In both builds [`Session::peer`], [`Session::type_`], [`Session::exists`]
+//! and the triggers use the lua api, because there's no ffi equivalent for
+//! them. [`Session::user`] reads the user name from the `_vuser` system space.
+//!
 //! See also:
 //! - [Lua reference: Submodule box.session](https://www.tarantool.io/en/doc/1.10/reference/reference_lua/box_session/)
 
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::error::{Error, TarantoolError, TarantoolErrorCode};
+use crate::set_error;
+use crate::space::{Space, SystemSpace};
+use crate::trigger::LuaTrigger;
+use crate::tuple::ToTupleBuffer;
+
 pub type UserId = u32;
 
+pub type SessionId = u64;
+
 #[cfg(not(feature = "picodata"))]
 mod vanilla {
     use std::convert::TryFrom;
@@ -15,10 +50,13 @@ mod vanilla {
     use tlua::{AsLua as _, LuaError};
 
     use crate::error::{Error, TarantoolError};
+    use crate::ffi::has_session_api;
     use crate::ffi::lua as ffi_lua;
-    use crate::ffi::tarantool::luaT_call;
+    use crate::ffi::tarantool::{
+        box_effective_user_id, box_session_id, box_session_su, box_session_user_id, luaT_call,
+    };
 
-    use super::UserId;
+    use super::{SessionId, UserId};
 
     fn user_id_from_lua(id: isize) -> UserId {
         // id in box.space._user has type unsigned
@@ -26,8 +64,38 @@ mod vanilla {
         u32::try_from(id).expect("user id is always valid u32")
     }
 
+    /// Get the id of the current session.
+    ///
+    /// NOTE: if [`has_session_api`] returns `false` this function uses an
+    /// inefficient implementation based on the lua api.
+    #[inline]
+    pub fn id() -> SessionId {
+        // SAFETY: this is safe as long as we only call this from the tx thread.
+        if unsafe { has_session_api() } {
+            // SAFETY: always safe
+            return unsafe { box_session_id() };
+        }
+
+        crate::global_lua()
+            .eval("return box.session.id()")
+            .expect("lua error")
+    }
+
     /// Get the user ID from the current session.
+    ///
+    /// NOTE: if [`has_session_api`] returns `false` this function uses an
+    /// inefficient implementation based on the lua api.
     pub fn uid() -> Result<UserId, Error> {
+        // SAFETY: this is safe as long as we only call this from the tx thread.
+        if unsafe { has_session_api() } {
+            let mut ret: u32 = 0;
+            // SAFETY: always safe
+            if unsafe { box_session_user_id(&mut ret) } < 0 {
+                return Err(TarantoolError::last().into());
+            }
+            return Ok(ret);
+        }
+
         unsafe {
             // Create new stack (just in case - in order no to mess things
             // in current stack).
@@ -53,7 +121,16 @@ mod vanilla {
     }
 
     /// Get the effective user ID of the current session.
+    ///
+    /// NOTE: if [`has_session_api`] returns `false` this function uses an
+    /// inefficient implementation based on the lua api.
     pub fn euid() -> Result<UserId, Error> {
+        // SAFETY: this is safe as long as we only call this from the tx thread.
+        if unsafe { has_session_api() } {
+            // SAFETY: always safe
+            return Ok(unsafe { box_effective_user_id() });
+        }
+
         unsafe {
             // Create new stack (just in case - in order no to mess things
             // in current stack).
@@ -79,6 +156,15 @@ mod vanilla {
     }
 
     pub(super) fn su_impl(uid: UserId) -> Result<(), Error> {
+        // SAFETY: this is safe as long as we only call this from the tx thread.
+        if unsafe { has_session_api() } {
+            // SAFETY: always safe
+            if unsafe { box_session_su(uid) } < 0 {
+                return Err(TarantoolError::last().into());
+            }
+            return Ok(());
+        }
+
         let lua = crate::lua_state();
         lua.exec_with("box.session.su(...)", uid)
             .map_err(LuaError::from)?;
@@ -92,11 +178,19 @@ mod picodata {
     use crate::{
         error::{Error, TarantoolError},
         ffi::tarantool::{
-            box_effective_user_id, box_session_su, box_session_user_id, box_user_id_by_name,
+            box_effective_user_id, box_session_id, box_session_su, box_session_user_id,
+            box_user_id_by_name,
         },
     };
 
-    use super::UserId;
+    use super::{SessionId, UserId};
+
+    /// Get the id of the current session.
+    #[inline]
+    pub fn id() -> SessionId {
+        // SAFETY: always safe
+        unsafe { box_session_id() }
+    }
 
     /// Get the user ID of the current user.
     #[inline]
@@ -140,8 +234,6 @@ mod picodata {
     }
 }
 
-use crate::error::Error;
-
 #[cfg(feature = "picodata")]
 pub use picodata::*;
 
@@ -171,3 +263,512 @@ pub fn with_su<T>(uid: UserId, f: impl FnOnce() -> T) -> Result<T, Error> {
     let _su = su(uid)?;
     Ok(f())
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// Session
+////////////////////////////////////////////////////////////////////////////////
+
+crate::define_str_enum! {
+    /// Type of a session, see [`Session::type_`].
+    pub enum SessionType {
+        /// A binary protocol (iproto) connection.
+        Binary = "binary",
+        /// An admin console connection.
+        Console = "console",
+        /// The interactive console of the instance.
+        Repl = "repl",
+        /// A replication connection.
+        Applier = "applier",
+        /// A session of a background fiber, e.g. the one running `init.lua`.
+        Background = "background",
+    }
+}
+
+/// A handle to a session identified by its id.
+///
+/// Most of the information is only available for the session of the
+/// current fiber, see [`Session::current`]. Note that session triggers
+/// ([`on_connect`], [`on_auth`], [`on_disconnect`]) are run in the context of
+/// the session they were fired for, so there [`Session::current`] is the
+/// session in question.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Session {
+    id: SessionId,
+}
+
+impl Session {
+    /// Returns the session of the current fiber.
+    #[inline]
+    pub fn current() -> Self {
+        Self { id: id() }
+    }
+
+    /// Returns a handle to the session with the given `id`. The session may
+    /// not exist, see [`Session::exists`].
+    #[inline(always)]
+    pub fn from_id(id: SessionId) -> Self {
+        Self { id }
+    }
+
+    /// Returns the session's id.
+    #[inline(always)]
+    pub fn id(&self) -> SessionId {
+        self.id
+    }
+
+    /// Returns `true` if the session with this id exists.
+    ///
+    /// Same as `box.session.exists` in lua.
+    #[inline]
+    pub fn exists(&self) -> Result<bool, Error> {
+        let res = crate::global_lua()
+            .eval_with("return box.session.exists(...)", self.id)
+            .map_err(tlua::LuaError::from)?;
+        Ok(res)
+    }
+
+    /// Returns the address of the remote peer (e.g. `"127.0.0.1:47354"`), or
+    /// `None` if the session isn't a network connection.
+    ///
+    /// Same as `box.session.peer` in lua.
+    #[inline]
+    pub fn peer(&self) -> Result<Option<String>, Error> {
+        let res = crate::global_lua()
+            .eval_with("return box.session.peer(...)", self.id)
+            .map_err(tlua::LuaError::from)?;
+        Ok(res)
+    }
+
+    /// Returns the type of the session.
+    ///
+    /// Returns an error if this is not the current session.
+    ///
+    /// Same as `box.session.type` in lua.
+    #[inline]
+    pub fn type_(&self) -> Result<SessionType, Error> {
+        self.check_is_current("session type")?;
+        let res = crate::global_lua().eval("return box.session.type()")?;
+        Ok(res)
+    }
+
+    /// Returns the name of the session's user.
+    ///
+    /// Returns an error if this is not the current session.
+    ///
+    /// Same as `box.session.user` in lua.
+    pub fn user(&self) -> Result<String, Error> {
+        self.check_is_current("session user")?;
+        let uid = uid()?;
+        let sys_vuser: Space = SystemSpace::VUser.into();
+        let Some(t) = sys_vuser.get(&(uid,))? else {
+            set_error!(TarantoolErrorCode::NoSuchUser, "{}", uid);
+            return Err(TarantoolError::last().into());
+        };
+        Ok(t.field::<String>(2)?.expect("user name is not nullable"))
+    }
+
+    /// Push MessagePack data into the session's channel - socket, console or
+    /// whatever is behind the session. The data is sent with the `sync` of
+    /// the request being currently processed, so the client can tell which
+    /// request the out-of-band message belongs to. Note, that successful push
+    /// does not guarantee delivery in case it was sent into the network.
+    ///
+    /// Returns an error if this is not the current session.
+    ///
+    /// Same as `box.session.push` in lua. See also [`crate::tuple::session_push`].
+    #[inline]
+    pub fn push<T>(&self, value: &T) -> Result<(), Error>
+    where
+        T: ToTupleBuffer + ?Sized,
+    {
+        self.check_is_current("session push")?;
+        crate::tuple::session_push(value)
+    }
+
+    fn check_is_current(&self, what: &str) -> Result<(), Error> {
+        if self.id != id() {
+            set_error!(
+                TarantoolErrorCode::IllegalParams,
+                "{} is only available for the current session",
+                what
+            );
+            return Err(TarantoolError::last().into());
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Session storage
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    static STORAGE: RefCell<HashMap<(SessionId, TypeId), Box<dyn Any>>> =
+        RefCell::new(HashMap::new());
+    static STORAGE_CLEANUP_REGISTERED: Cell<bool> = Cell::new(false);
+}
+
+/// Per-session storage.
+///
+/// Every session can store at most one value of each type `T`. Use newtype
+/// wrappers to store several values of the same type.
+///
+/// Values are stored in rust memory, so unlike `box.session.storage` in lua
+/// they are not visible from lua. The values of a session are dropped once
+/// the session is disconnected. This is done via an internal
+/// [`on_disconnect`] trigger, which is registered the first time a value is
+/// stored, so the values may already be gone in [`on_disconnect`] triggers
+/// registered before that moment.
+impl Session {
+    /// Stores `value` in the session's storage. Returns the previous value of
+    /// type `T` if there was one.
+    pub fn set<T: 'static>(&self, value: T) -> Result<Option<T>, Error> {
+        register_storage_cleanup()?;
+        let old = STORAGE.with(|s| {
+            s.borrow_mut()
+                .insert((self.id, TypeId::of::<T>()), Box::new(value))
+        });
+        Ok(old.map(downcast))
+    }
+
+    /// Returns a copy of the value of type `T` from the session's storage.
+    #[inline]
+    pub fn get<T: Clone + 'static>(&self) -> Option<T> {
+        self.with_storage(|v: &mut Option<T>| v.clone())
+    }
+
+    /// Removes the value of type `T` from the session's storage and returns
+    /// it.
+    #[inline]
+    pub fn take<T: 'static>(&self) -> Option<T> {
+        STORAGE
+            .with(|s| s.borrow_mut().remove(&(self.id, TypeId::of::<T>())))
+            .map(downcast)
+    }
+
+    /// Calls `f` with a mutable reference to the slot for the value of type
+    /// `T` in the session's storage. `f` can modify the value in place, insert
+    /// a new one or remove it by setting the slot to `None`.
+    ///
+    /// It's safe to access the storage from `f`, but the slot itself is empty
+    /// for the duration of the call.
+    pub fn with_storage<T: 'static, R>(&self, f: impl FnOnce(&mut Option<T>) -> R) -> R {
+        let key = (self.id, TypeId::of::<T>());
+        let mut slot = STORAGE.with(|s| s.borrow_mut().remove(&key)).map(downcast);
+        let res = f(&mut slot);
+        if let Some(value) = slot {
+            // If the cleanup trigger can't be registered the value is stored
+            // anyway, it just won't be dropped on disconnect.
+            _ = register_storage_cleanup();
+            STORAGE.with(|s| s.borrow_mut().insert(key, Box::new(value)));
+        }
+        res
+    }
+}
+
+#[inline]
+fn downcast<T: 'static>(value: Box<dyn Any>) -> T {
+    *value.downcast().expect("storage is keyed by the type id")
+}
+
+/// Drops all the values stored for the session with the given `id`.
+fn clear_storage(id: SessionId) {
+    let values: Vec<_> = STORAGE.with(|s| {
+        let mut s = s.borrow_mut();
+        let keys: Vec<_> = s.keys().filter(|(sid, _)| *sid == id).copied().collect();
+        keys.into_iter().filter_map(|k| s.remove(&k)).collect()
+    });
+    // Values are dropped outside of the borrow, because their destructors
+    // may access the storage.
+    drop(values);
+}
+
+fn register_storage_cleanup() -> Result<(), Error> {
+    if STORAGE_CLEANUP_REGISTERED.with(Cell::get) {
+        return Ok(());
+    }
+    on_disconnect(|session| clear_storage(session.id()))?;
+    STORAGE_CLEANUP_REGISTERED.with(|r| r.set(true));
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Triggers
+////////////////////////////////////////////////////////////////////////////////
+
+/// Set a callback to be called when a new client connects to the instance
+/// (binary protocol or admin console). The callback is run in the context of
+/// the new session.
+///
+/// If the callback returns an error the connection is closed.
+///
+/// Returns a handle which can be used to remove the trigger.
+///
+/// Same as `box.session.on_connect` in lua.
+pub fn on_connect<F>(cb: F) -> Result<SessionTrigger, Error>
+where
+    F: Fn(Session) -> Result<(), Error> + 'static,
+{
+    let cb = tlua::function1(move |id: SessionId| -> Option<String> {
+        cb(Session::from_id(id)).err().map(|e| e.to_string())
+    });
+    SessionTrigger::set(
+        "on_connect",
+        "function()
+            local err = cb(box.session.id())
+            if err ~= nil then error(err) end
+        end",
+        cb,
+    )
+}
+
+/// Set a callback to be called when a client disconnects. The callback is run
+/// in the context of the session being closed.
+///
+/// Returns a handle which can be used to remove the trigger.
+///
+/// Same as `box.session.on_disconnect` in lua.
+pub fn on_disconnect<F>(cb: F) -> Result<SessionTrigger, Error>
+where
+    F: Fn(Session) + 'static,
+{
+    let cb = tlua::function1(move |id: SessionId| cb(Session::from_id(id)));
+    SessionTrigger::set(
+        "on_disconnect",
+        "function()
+            cb(box.session.id())
+        end",
+        cb,
+    )
+}
+
+/// Set a callback to be called on an authentication attempt. The callback
+/// receives the session, the name of the user and whether the authentication
+/// succeeded. The callback is called both on successful and failed attempts.
+///
+/// If the callback returns an error the authentication fails.
+///
+/// Returns a handle which can be used to remove the trigger.
+///
+/// Same as `box.session.on_auth` in lua.
+pub fn on_auth<F>(cb: F) -> Result<SessionTrigger, Error>
+where
+    F: Fn(Session, &str, bool) -> Result<(), Error> + 'static,
+{
+    let cb = tlua::function3(
+        move |id: SessionId, user: String, success: bool| -> Option<String> {
+            cb(Session::from_id(id), &user, success)
+                .err()
+                .map(|e| e.to_string())
+        },
+    );
+    SessionTrigger::set(
+        "on_auth",
+        "function(user, success)
+            local err = cb(box.session.id(), user, success)
+            if err ~= nil then error(err) end
+        end",
+        cb,
+    )
+}
+
+/// A handle to a trigger set with [`on_connect`], [`on_disconnect`] or
+/// [`on_auth`].
+///
+/// Dropping the handle doesn't remove the trigger, use
+/// [`SessionTrigger::remove`] for that. If the trigger is removed from lua, the
+/// callback is released the next time any trigger is set from rust.
+#[derive(Debug)]
+pub struct SessionTrigger(LuaTrigger);
+
+impl SessionTrigger {
+    /// Set the lua function `trigger` as the `box.session.{kind}` trigger.
+    /// `trigger` can refer to `cb` as an upvalue.
+    #[inline(always)]
+    fn set<F>(kind: &'static str, trigger: &str, cb: F) -> Result<Self, Error>
+    where
+        F: tlua::PushOneInto<tlua::LuaState, Err = tlua::Void>,
+    {
+        LuaTrigger::set(format!("box.session.{kind}"), trigger, cb).map(Self)
+    }
+
+    /// Remove the trigger.
+    #[inline(always)]
+    pub fn remove(self) -> Result<(), Error> {
+        self.0.remove()
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::fiber;
+    use crate::net_box::{Conn, ConnOptions, Options};
+    use crate::test::util::{listen_port, on_scope_exit};
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    #[crate::test(tarantool = "crate")]
+    fn current_session() {
+        let lua = crate::lua_state();
+        let session = Session::current();
+        assert_eq!(
+            session.id(),
+            lua.eval::<SessionId>("return box.session.id()").unwrap()
+        );
+        assert!(session.exists().unwrap());
+        assert_eq!(session.peer().unwrap(), None);
+        assert_eq!(
+            session.type_().unwrap(),
+            lua.eval::<SessionType>("return box.session.type()")
+                .unwrap()
+        );
+        assert_eq!(
+            session.user().unwrap(),
+            lua.eval::<String>("return box.session.user()").unwrap()
+        );
+        assert_eq!(
+            uid().unwrap(),
+            lua.eval::<UserId>("return box.session.uid()").unwrap()
+        );
+        assert_eq!(
+            euid().unwrap(),
+            lua.eval::<UserId>("return box.session.euid()").unwrap()
+        );
+
+        let other = Session::from_id(session.id() + 1000);
+        assert!(!other.exists().unwrap());
+        let e = other.type_().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "box error: IllegalParams: session type is only available for the current session"
+        );
+        other.user().unwrap_err();
+        other.push(&(1,)).unwrap_err();
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn session_storage() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Counter(u32);
+
+        let session = Session::current();
+        let other = Session::from_id(session.id() + 1000);
+
+        assert_eq!(session.get::<Counter>(), None);
+        assert_eq!(session.set(Counter(1)).unwrap(), None);
+        assert_eq!(session.set(String::from("foo")).unwrap(), None);
+        assert_eq!(session.get::<Counter>(), Some(Counter(1)));
+        assert_eq!(session.get::<String>().as_deref(), Some("foo"));
+        assert_eq!(other.get::<Counter>(), None);
+
+        session.with_storage(|c: &mut Option<Counter>| {
+            c.as_mut().unwrap().0 += 1;
+            // The storage is accessible from inside the callback.
+            assert_eq!(session.get::<String>().as_deref(), Some("foo"));
+        });
+        assert_eq!(session.set(Counter(10)).unwrap(), Some(Counter(2)));
+
+        assert_eq!(session.take::<Counter>(), Some(Counter(10)));
+        assert_eq!(session.take::<Counter>(), None);
+        session.with_storage(|s: &mut Option<String>| *s = None);
+        assert_eq!(session.get::<String>(), None);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn session_triggers() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Marker(SessionId);
+
+        // (session, is current, type, has peer)
+        let connected = Rc::new(RefCell::new(vec![]));
+        let authenticated = Rc::new(RefCell::new(vec![]));
+        let disconnected = Rc::new(RefCell::new(vec![]));
+
+        // Callbacks must not panic, so they only record what they see and
+        // the checks are done outside of them.
+        let on_connect = on_connect({
+            let connected = connected.clone();
+            move |session| {
+                let is_current = session == Session::current();
+                let type_ = session.type_()?;
+                let has_peer = session.peer()?.is_some();
+                session.set(Marker(session.id()))?;
+                connected
+                    .borrow_mut()
+                    .push((session, is_current, type_, has_peer));
+                Ok(())
+            }
+        })
+        .unwrap();
+        let on_auth = on_auth({
+            let authenticated = authenticated.clone();
+            move |session, user, success| {
+                authenticated
+                    .borrow_mut()
+                    .push((session, user.to_owned(), success));
+                Ok(())
+            }
+        })
+        .unwrap();
+        let on_disconnect = on_disconnect({
+            let disconnected = disconnected.clone();
+            move |session| disconnected.borrow_mut().push(session)
+        })
+        .unwrap();
+        let _guard = on_scope_exit(|| {
+            on_connect.remove().unwrap();
+            on_auth.remove().unwrap();
+            on_disconnect.remove().unwrap();
+        });
+
+        let conn = Conn::new(
+            ("localhost", listen_port()),
+            ConnOptions {
+                user: "test_user".into(),
+                password: "password".into(),
+                ..ConnOptions::default()
+            },
+            None,
+        )
+        .unwrap();
+        conn.ping(&Options::default()).unwrap();
+
+        let (session, is_current, type_, has_peer) = connected.borrow()[0];
+        assert!(is_current);
+        assert_eq!(type_, SessionType::Binary);
+        assert!(has_peer);
+        assert_eq!(session.get::<Marker>(), Some(Marker(session.id())));
+        assert_eq!(
+            authenticated.borrow()[..],
+            [(session, "test_user".to_owned(), true)]
+        );
+
+        conn.close();
+        for _ in 0..100 {
+            if !disconnected.borrow().is_empty() {
+                break;
+            }
+            fiber::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(disconnected.borrow()[..], [session]);
+        // Session storage is cleared once the session is disconnected.
+        assert_eq!(session.get::<Marker>(), None);
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn remove_session_trigger() {
+        let marker = Rc::new(());
+        let trigger = on_disconnect({
+            let marker = marker.clone();
+            move |_| {
+                let _ = &marker;
+            }
+        })
+        .unwrap();
+        assert_eq!(Rc::strong_count(&marker), 2);
+        trigger.remove().unwrap();
+        crate::lua_state().exec("collectgarbage()").unwrap();
+        assert_eq!(Rc::strong_count(&marker), 1);
+    }
+}
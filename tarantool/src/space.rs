@@ -9,6 +9,7 @@
 use crate::error::{Error, TarantoolError};
 use crate::ffi::tarantool as ffi;
 use crate::index::{Index, IndexIterator, IteratorType};
+use crate::trigger::LuaTrigger;
 use crate::tuple::{Encode, ToTupleBuffer, Tuple, TupleBuffer};
 use crate::unwrap_or;
 use crate::util::Value;
@@ -22,6 +23,8 @@ use std::collections::HashMap;
 use std::ops::Range;
 use std::os::raw::c_char;
 
+pub mod typed;
+
 /// End of the reserved range of system spaces.
 pub const SYSTEM_ID_MAX: SpaceId = 511;
 
@@ -172,7 +175,7 @@ impl Default for SpaceEngineType {
 
 /// Options for new space, used by Space::create.
 /// (for details see [Options for box.schema.space.create](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_schema/space_create/)).
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq)]
 pub struct SpaceCreateOptions {
     pub if_not_exists: bool,
     pub engine: SpaceEngineType,
@@ -183,6 +186,24 @@ pub struct SpaceCreateOptions {
     pub format: Option<Vec<Field>>,
 }
 
+/// Options for [`Space::alter`]. Options which are `None` are not changed.
+/// (for details see [space_object:alter()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/alter/)).
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct SpaceAlterOptions {
+    /// New name of the space.
+    pub name: Option<String>,
+    /// New format of the space, replaces the current one.
+    pub format: Option<Vec<Field>>,
+    /// Fields appended to the end of the format (the new one, if
+    /// [`Self::format`] is specified, or the current one otherwise).
+    ///
+    /// Note that if the space isn't empty, the added fields must be nullable.
+    pub add_fields: Vec<Field>,
+    pub is_sync: Option<bool>,
+    /// Name of the new owner of the space.
+    pub user: Option<String>,
+}
+
 /// Possible values for the [`SpaceCreateOptions::space_type`] field.
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub enum SpaceType {
@@ -556,6 +577,26 @@ impl Space {
         crate::schema::space::drop_space(self.id)
     }
 
+    /// Alter the space.
+    /// (for details see [space_object:alter()](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_space/alter/)).
+    ///
+    /// ```no_run
+    /// use tarantool::space::{Field, Space, SpaceAlterOptions};
+    ///
+    /// let space = Space::find("users").unwrap();
+    /// space
+    ///     .alter(&SpaceAlterOptions {
+    ///         name: Some("people".into()),
+    ///         add_fields: vec![Field::string("nickname").is_nullable(true)],
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn alter(&self, opts: &SpaceAlterOptions) -> Result<(), Error> {
+        crate::schema::space::alter_space(self.id, opts)
+    }
+
     /// Find space by name.
     ///
     /// This function performs SELECT request to `_vspace` system space.
@@ -968,6 +1009,139 @@ impl Space {
         let tuple = sys_space.get(&(self.id,))?.ok_or(Error::MetaNotFound)?;
         tuple.decode::<Metadata>()
     }
+
+    /// Set a callback to be called after a tuple in the space is inserted,
+    /// replaced, updated or deleted. The callback receives the old and the
+    /// new tuple, `old` is `None` for an insert and `new` is `None` for a
+    /// delete.
+    ///
+    /// The callback is called inside the transaction which changes the space,
+    /// so it must not yield.
+    ///
+    /// Returns a handle which can be used to remove the trigger.
+    ///
+    /// Same as `box.space[id]:on_replace(cb)` in lua.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::space::Space;
+    ///
+    /// let space = Space::find("accounts").unwrap();
+    /// let trigger = space
+    ///     .on_replace(|old, new| println!("{:?} -> {:?}", old, new))
+    ///     .unwrap();
+    /// space.replace(&(1, "Alice")).unwrap();
+    /// trigger.remove().unwrap();
+    /// ```
+    pub fn on_replace<F>(&self, mut cb: F) -> Result<SpaceTrigger, Error>
+    where
+        F: FnMut(Option<Tuple>, Option<Tuple>) + 'static,
+    {
+        let cb = tlua::function2(move |old: Option<Tuple>, new: Option<Tuple>| cb(old, new));
+        SpaceTrigger::set(self.id, "on_replace", cb)
+    }
+
+    /// Set a callback to be called before a tuple in the space is inserted,
+    /// replaced, updated or deleted. The callback receives the old and the
+    /// new tuple and decides what is actually written to the space, see
+    /// [`BeforeReplace`].
+    ///
+    /// The callback is called inside the transaction which changes the space,
+    /// so it must not yield.
+    ///
+    /// Returns a handle which can be used to remove the trigger.
+    ///
+    /// Same as `box.space[id]:before_replace(cb)` in lua.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tarantool::space::{BeforeReplace, Space};
+    ///
+    /// let space = Space::find("accounts").unwrap();
+    /// // Forbid deleting the accounts
+    /// space
+    ///     .before_replace(|_old, new| match new {
+    ///         Some(_) => BeforeReplace::Keep,
+    ///         None => BeforeReplace::Skip,
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn before_replace<F>(&self, mut cb: F) -> Result<SpaceTrigger, Error>
+    where
+        F: FnMut(Option<Tuple>, Option<Tuple>) -> BeforeReplace + 'static,
+    {
+        let cb = tlua::function2(move |old: Option<Tuple>, new: Option<Tuple>| {
+            match cb(old.clone(), new.clone()) {
+                BeforeReplace::Keep => new,
+                BeforeReplace::Replace(tuple) => Some(tuple),
+                BeforeReplace::Skip => old,
+                BeforeReplace::Delete => None,
+            }
+        });
+        SpaceTrigger::set(self.id, "before_replace", cb)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Triggers
+////////////////////////////////////////////////////////////////////////////////
+
+/// The result of a [`Space::before_replace`] callback.
+#[derive(Debug, Clone)]
+pub enum BeforeReplace {
+    /// Write the new tuple as is.
+    Keep,
+    /// Write the given tuple instead of the new one.
+    Replace(Tuple),
+    /// Skip the operation, the old tuple stays in the space.
+    Skip,
+    /// Delete the old tuple instead.
+    Delete,
+}
+
+/// A handle to a trigger set with [`Space::on_replace`] or
+/// [`Space::before_replace`].
+///
+/// Dropping the handle doesn't remove the trigger, use [`SpaceTrigger::remove`]
+/// for that. If the space is dropped or the trigger is removed from lua, the
+/// callback is released the next time any trigger is set from rust.
+#[derive(Debug)]
+pub struct SpaceTrigger {
+    space_id: SpaceId,
+    inner: LuaTrigger,
+}
+
+impl SpaceTrigger {
+    fn set<F>(space_id: SpaceId, kind: &'static str, cb: F) -> Result<Self, Error>
+    where
+        F: tlua::PushOneInto<tlua::LuaState, Err = tlua::Void>,
+    {
+        let list = format!(
+            "function(...)
+                local space = box.space[{space_id}]
+                if space == nil then
+                    box.error(box.error.NO_SUCH_SPACE, {space_id})
+                end
+                return space:{kind}(...)
+            end"
+        );
+        let trigger = "function(old, new) return cb(old, new) end";
+        let inner = LuaTrigger::set(list, trigger, cb)?;
+        Ok(Self { space_id, inner })
+    }
+
+    /// Id of the space the trigger is set on.
+    #[inline(always)]
+    pub fn space_id(&self) -> SpaceId {
+        self.space_id
+    }
+
+    /// Remove the trigger from the space. Returns an error if the space no
+    /// longer exists.
+    #[inline(always)]
+    pub fn remove(self) -> Result<(), Error> {
+        self.inner.remove()
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -1559,4 +1733,67 @@ mod test {
             space.drop().unwrap();
         }
     }
+
+    #[crate::test(tarantool = "crate")]
+    fn alter_space() {
+        let space_name = crate::temp_space_name!();
+        let space = Space::builder(&space_name)
+            .field(("id", FieldType::Unsigned))
+            .create()
+            .unwrap();
+        space.index_builder("pk").create().unwrap();
+        space.insert(&(1,)).unwrap();
+
+        let new_name = crate::temp_space_name!();
+        space
+            .alter(&SpaceAlterOptions {
+                name: Some(new_name.clone()),
+                add_fields: vec![Field::string("name").is_nullable(true)],
+                is_sync: Some(true),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(Space::find(&space_name).is_none());
+        assert_eq!(Space::find(&new_name).unwrap().id(), space.id());
+        let meta = space.meta().unwrap();
+        assert_eq!(meta.name, new_name);
+        assert_eq!(meta.flags.get("is_sync"), Some(&Value::Bool(true)));
+        assert_eq!(meta.format.len(), 2);
+        assert_eq!(meta.format[1].get("name"), Some(&Value::Str("name".into())));
+        space.insert(&(2, "Bob")).unwrap();
+
+        // Duplicate field.
+        let e = space
+            .alter(&SpaceAlterOptions {
+                add_fields: vec![Field::string("name").is_nullable(true)],
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            format!("box error: AlterSpace: {new_name}: field 'name' already exists")
+        );
+
+        // Format incompatible with the data is rejected by tarantool.
+        let e = space
+            .alter(&SpaceAlterOptions {
+                format: Some(vec![Field::unsigned("id"), Field::unsigned("name")]),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(e.to_string().contains("FieldType"), "{}", e);
+
+        space
+            .alter(&SpaceAlterOptions {
+                is_sync: Some(false),
+                user: Some("guest".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        let meta = space.meta().unwrap();
+        assert_eq!(meta.flags.get("is_sync"), None);
+        assert_eq!(meta.user_id, 0);
+
+        space.drop().unwrap();
+    }
 }
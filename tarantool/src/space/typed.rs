@@ -0,0 +1,615 @@
+//! Spaces with a statically known schema.
+//!
+//! A struct describing a row of a space can derive [`Row`] via
+//! `#[derive(tarantool::Space)]`. The derive macro generates the space format,
+//! the primary & secondary index definitions and typed index specifiers, so
+//! that the rows can be stored and retrieved with a [`TypedSpace`] without
+//! re-specifying the row & key types at each call site.
+//!
+//! # Example
+//! ```no_run
+//! use serde::{Deserialize, Serialize};
+//! use tarantool::index::IteratorType;
+//! use tarantool::space::typed::TypedSpace;
+//!
+//! #[derive(Debug, Serialize, Deserialize, tarantool::Space)]
+//! #[space(name = "users")]
+//! pub struct User {
+//!     #[space(primary_key)]
+//!     id: u64,
+//!     #[space(unique_index = "by_email")]
+//!     email: String,
+//!     #[space(index = "by_name")]
+//!     name: String,
+//!     nickname: Option<String>,
+//! }
+//!
+//! let users = TypedSpace::<User>::create().unwrap();
+//! users
+//!     .insert(&User {
+//!         id: 1,
+//!         email: "bob@example.com".into(),
+//!         name: "Bob".into(),
+//!         nickname: None,
+//!     })
+//!     .unwrap();
+//!
+//! let bob: Option<User> = users.get(&(1,)).unwrap();
+//!
+//! let by_email = users.index(User::BY_EMAIL).unwrap();
+//! let bob: Option<User> = by_email.get(&("bob@example.com".into(),)).unwrap();
+//!
+//! let by_name = users.index(User::BY_NAME).unwrap();
+//! for user in by_name.select(IteratorType::Eq, Some(&("Bob".into(),))).unwrap() {
+//!     let user: User = user.unwrap();
+//! }
+//! ```
+//!
+//! # Attributes
+//!
+//! - `#[space(name = "...")]` on the struct (required): name of the space.
+//! - `#[space(primary_key)]` on a field: the field is a part of the primary
+//!   index named `"primary"`. At least one field must be marked.
+//! - `#[space(index = "...")]` & `#[space(unique_index = "...")]` on a field:
+//!   the field is a part of a non-unique or a unique secondary index with the
+//!   given name. Can be specified multiple times. For each secondary index an
+//!   associated constant with the uppercased index name is generated, which
+//!   can be passed to [`TypedSpace::index`], so the name must be a valid rust
+//!   identifier:
+//!   ```compile_fail
+//!   #[derive(serde::Serialize, serde::Deserialize, tarantool::Space)]
+//!   #[space(name = "users")]
+//!   pub struct User {
+//!       #[space(primary_key)]
+//!       id: u64,
+//!       // error: index name 'by-email' must be a valid rust identifier
+//!       #[space(unique_index = "by-email")]
+//!       email: String,
+//!   }
+//!   ```
+//! - `#[space(field_type = "...")]` on a field: overrides the field type in the
+//!   space format, otherwise the type is deduced via [`KnownFieldType`]. Add
+//!   `#[space(is_nullable)]` if the field can be null.
+//!
+//! The parts of each index are listed in the order of the struct's fields and
+//! the index key is a tuple of the types of those fields. The space field names
+//! are taken from the struct field names, so `#[serde(rename)]` must not be
+//! used.
+//!
+//! **NOTE:** the row is encoded as a msgpack array, so the struct fields must
+//! be declared in the same order as the space format.
+
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::datetime::{Datetime, Interval};
+use crate::error::{BoxError, TarantoolErrorCode};
+use crate::index::{Index, IndexIterator, IteratorType, Part};
+use crate::space::{Builder, Field, FieldType, Space};
+use crate::tuple::{ToTupleBuffer, TupleBuffer};
+use crate::uuid::Uuid;
+use crate::Result;
+
+////////////////////////////////////////////////////////////////////////////////
+// Row
+////////////////////////////////////////////////////////////////////////////////
+
+/// A row of a space with a statically known schema.
+///
+/// Use `#[derive(tarantool::Space)]` to implement this trait, see the
+/// [module level documentation](self) for details.
+pub trait Row: Serialize + DeserializeOwned {
+    /// Name of the space.
+    const SPACE_NAME: &'static str;
+
+    /// Type of the primary key, a tuple of types of primary key parts.
+    type PrimaryKey: ToTupleBuffer;
+
+    /// Format of the space.
+    fn format() -> Vec<Field>;
+
+    /// Definitions of the space's indexes. The first one is the primary index.
+    fn indexes() -> Vec<IndexDef>;
+}
+
+/// Definition of an index of a space with a statically known schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexDef {
+    pub name: &'static str,
+    pub unique: bool,
+    pub parts: Vec<Part>,
+}
+
+/// A typed specifier of a secondary index of a space with rows of type `R`
+/// and index keys of type `K`.
+///
+/// These are generated by `#[derive(tarantool::Space)]` as associated
+/// constants of the row type.
+pub struct IndexSpec<R, K> {
+    name: &'static str,
+    marker: PhantomData<fn() -> (R, K)>,
+}
+
+impl<R, K> IndexSpec<R, K> {
+    #[inline(always)]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<R, K> Clone for IndexSpec<R, K> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R, K> Copy for IndexSpec<R, K> {}
+
+impl<R, K> std::fmt::Debug for IndexSpec<R, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IndexSpec").field(&self.name).finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// KnownFieldType
+////////////////////////////////////////////////////////////////////////////////
+
+/// Types which correspond to a specific [`FieldType`] in the space format.
+///
+/// This is used by `#[derive(tarantool::Space)]` to deduce the space format
+/// from the struct field types.
+pub trait KnownFieldType {
+    const FIELD_TYPE: FieldType;
+    const IS_NULLABLE: bool = false;
+}
+
+impl<T> KnownFieldType for Option<T>
+where
+    T: KnownFieldType,
+{
+    const FIELD_TYPE: FieldType = T::FIELD_TYPE;
+    const IS_NULLABLE: bool = true;
+}
+
+macro_rules! impl_known_field_type {
+    ($($field_type:ident: $($t:ty),+;)+) => {
+        $($(
+            impl KnownFieldType for $t {
+                const FIELD_TYPE: FieldType = FieldType::$field_type;
+            }
+        )+)+
+    }
+}
+
+impl_known_field_type! {
+    Unsigned: u8, u16, u32, u64, usize;
+    Integer: i8, i16, i32, i64, isize;
+    Double: f32, f64;
+    Boolean: bool;
+    String: String, Box<str>, std::borrow::Cow<'static, str>;
+    Varbinary: serde_bytes::ByteBuf;
+    Uuid: Uuid;
+    Datetime: Datetime;
+    Interval: Interval;
+    Any: rmpv::Value;
+}
+
+#[cfg(any(feature = "picodata", feature = "standalone_decimal"))]
+impl KnownFieldType for crate::decimal::Decimal {
+    const FIELD_TYPE: FieldType = FieldType::Decimal;
+}
+
+impl<T> KnownFieldType for Vec<T> {
+    const FIELD_TYPE: FieldType = FieldType::Array;
+}
+
+impl<K, V, S> KnownFieldType for HashMap<K, V, S> {
+    const FIELD_TYPE: FieldType = FieldType::Map;
+}
+
+impl<K, V> KnownFieldType for BTreeMap<K, V> {
+    const FIELD_TYPE: FieldType = FieldType::Map;
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TypedSpace
+////////////////////////////////////////////////////////////////////////////////
+
+/// A wrapper around a [`Space`] which stores rows of type `R`.
+pub struct TypedSpace<R> {
+    space: Space,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for TypedSpace<R> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self::from_space(self.space.clone())
+    }
+}
+
+impl<R> std::fmt::Debug for TypedSpace<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypedSpace").field(&self.space).finish()
+    }
+}
+
+impl<R> TypedSpace<R> {
+    /// Wrap a `space`. The space's format is not checked, so it's up to the
+    /// caller to make sure it matches `R`.
+    #[inline(always)]
+    pub fn from_space(space: Space) -> Self {
+        Self {
+            space,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn as_space(&self) -> &Space {
+        &self.space
+    }
+
+    #[inline(always)]
+    pub fn into_space(self) -> Space {
+        self.space
+    }
+}
+
+impl<R> TypedSpace<R>
+where
+    R: Row,
+{
+    /// Returns a space builder with the name and the format of the space
+    /// already set. Use it with [`Self::create_with`] to specify additional
+    /// space options.
+    #[inline]
+    pub fn builder() -> Builder<'static> {
+        Space::builder(R::SPACE_NAME).format(R::format())
+    }
+
+    /// Create the space and all of its indexes, unless they already exist.
+    #[inline]
+    pub fn create() -> Result<Self> {
+        Self::create_with(Self::builder())
+    }
+
+    /// Create the space using the given `builder`, then create all of its
+    /// indexes. Missing space & indexes are created, existing ones are left as
+    /// is.
+    pub fn create_with(builder: Builder<'_>) -> Result<Self> {
+        let space = builder.if_not_exists(true).create()?;
+        for index in R::indexes() {
+            space
+                .index_builder(index.name)
+                .unique(index.unique)
+                .parts(index.parts)
+                .if_not_exists(true)
+                .create()?;
+        }
+        Ok(Self::from_space(space))
+    }
+
+    /// Find the space by [`Row::SPACE_NAME`].
+    #[inline]
+    pub fn find() -> Option<Self> {
+        Space::find(R::SPACE_NAME).map(Self::from_space)
+    }
+
+    /// Returns the primary index.
+    #[inline(always)]
+    pub fn primary_key(&self) -> TypedIndex<R, R::PrimaryKey> {
+        TypedIndex::from_index(self.space.primary_key())
+    }
+
+    /// Returns the secondary index specified by `spec`, which is one of the
+    /// associated constants generated by `#[derive(tarantool::Space)]`.
+    ///
+    /// Returns an error with code [`NoSuchIndexName`] if there's no such
+    /// index in the space.
+    ///
+    /// [`NoSuchIndexName`]: TarantoolErrorCode::NoSuchIndexName
+    pub fn index<K>(&self, spec: IndexSpec<R, K>) -> Result<TypedIndex<R, K>> {
+        let index = self.space.index(spec.name).ok_or_else(|| {
+            BoxError::new(
+                TarantoolErrorCode::NoSuchIndexName,
+                format!(
+                    "No index '{}' is defined in space '{}'",
+                    spec.name,
+                    R::SPACE_NAME
+                ),
+            )
+        })?;
+        Ok(TypedIndex::from_index(index))
+    }
+
+    /// Insert a `row` into the space. Returns the inserted row.
+    ///
+    /// See also: [`Space::insert`]
+    #[inline]
+    pub fn insert(&self, row: &R) -> Result<R> {
+        self.space.insert(&encode_row(row)?)?.decode()
+    }
+
+    /// Insert a `row` into the space, replacing an existing one with the same
+    /// primary key if any. Returns the inserted row.
+    ///
+    /// See also: [`Space::replace`]
+    #[inline]
+    pub fn replace(&self, row: &R) -> Result<R> {
+        self.space.replace(&encode_row(row)?)?.decode()
+    }
+
+    /// Get a row by the primary `key`.
+    ///
+    /// See also: [`Space::get`]
+    #[inline]
+    pub fn get(&self, key: &R::PrimaryKey) -> Result<Option<R>> {
+        self.primary_key().get(key)
+    }
+
+    /// Delete a row by the primary `key`. Returns the deleted row if any.
+    ///
+    /// See also: [`Space::delete`]
+    #[inline]
+    pub fn delete(&self, key: &R::PrimaryKey) -> Result<Option<R>> {
+        self.space.delete(key)?.map(|t| t.decode()).transpose()
+    }
+
+    /// Search for rows via the primary index. `None` key means iterate from
+    /// the first or the last row depending on the `iterator_type`.
+    ///
+    /// See also: [`Space::select`]
+    #[inline]
+    pub fn select(
+        &self,
+        iterator_type: IteratorType,
+        key: Option<&R::PrimaryKey>,
+    ) -> Result<TypedIter<R>> {
+        self.primary_key().select(iterator_type, key)
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> Result<usize> {
+        self.space.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> Result<bool> {
+        self.space.is_empty()
+    }
+}
+
+#[inline]
+fn encode_row<R: Serialize>(row: &R) -> Result<TupleBuffer> {
+    TupleBuffer::try_from_vec(rmp_serde::to_vec(row)?)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TypedIndex
+////////////////////////////////////////////////////////////////////////////////
+
+/// A wrapper around an [`Index`] of a space with rows of type `R` and index
+/// keys of type `K`.
+pub struct TypedIndex<R, K> {
+    index: Index,
+    marker: PhantomData<fn() -> (R, K)>,
+}
+
+impl<R, K> Clone for TypedIndex<R, K> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self::from_index(self.index.clone())
+    }
+}
+
+impl<R, K> std::fmt::Debug for TypedIndex<R, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TypedIndex").field(&self.index).finish()
+    }
+}
+
+impl<R, K> TypedIndex<R, K> {
+    /// Wrap an `index`. The index parts are not checked, so it's up to the
+    /// caller to make sure they match `K`.
+    #[inline(always)]
+    pub fn from_index(index: Index) -> Self {
+        Self {
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn as_index(&self) -> &Index {
+        &self.index
+    }
+}
+
+impl<R, K> TypedIndex<R, K>
+where
+    R: Row,
+    K: ToTupleBuffer,
+{
+    /// Get a row by the `key`. Only works for unique indexes.
+    ///
+    /// See also: [`Index::get`]
+    #[inline]
+    pub fn get(&self, key: &K) -> Result<Option<R>> {
+        self.index.get(key)?.map(|t| t.decode()).transpose()
+    }
+
+    /// Search for rows via this index. `None` key means iterate from the
+    /// first or the last row depending on the `iterator_type`.
+    ///
+    /// See also: [`Index::select`]
+    #[inline]
+    pub fn select(&self, iterator_type: IteratorType, key: Option<&K>) -> Result<TypedIter<R>> {
+        let inner = match key {
+            Some(key) => self.index.select(iterator_type, key)?,
+            None => self.index.select(iterator_type, &())?,
+        };
+        Ok(TypedIter {
+            inner,
+            marker: PhantomData,
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TypedIter
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over rows of type `R` returned by [`TypedSpace::select`] and
+/// [`TypedIndex::select`].
+pub struct TypedIter<R> {
+    inner: IndexIterator,
+    marker: PhantomData<fn() -> R>,
+}
+
+impl<R> Iterator for TypedIter<R>
+where
+    R: DeserializeOwned,
+{
+    type Item = Result<R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|t| t.decode())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// tests
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, crate::Space)]
+    #[space(name = "typed_space_test_users", tarantool = "crate")]
+    pub struct User {
+        #[space(primary_key)]
+        id: u64,
+        #[space(unique_index = "by_email")]
+        email: String,
+        #[space(index = "by_name_and_age")]
+        name: String,
+        #[space(index = "by_name_and_age")]
+        age: u32,
+        nickname: Option<String>,
+        #[space(field_type = "any", is_nullable)]
+        extra: Option<serde_json::Value>,
+    }
+
+    fn user(id: u64, email: &str, name: &str, age: u32) -> User {
+        User {
+            id,
+            email: email.into(),
+            name: name.into(),
+            age,
+            nickname: None,
+            extra: None,
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn derive_schema() {
+        assert_eq!(
+            User::format(),
+            vec![
+                Field::unsigned("id"),
+                Field::string("email"),
+                Field::string("name"),
+                Field::unsigned("age"),
+                Field::string("nickname").is_nullable(true),
+                Field::any("extra").is_nullable(true),
+            ]
+        );
+
+        let indexes = User::indexes();
+        let names: Vec<_> = indexes.iter().map(|i| (i.name, i.unique)).collect();
+        assert_eq!(
+            names,
+            [
+                ("primary", true),
+                ("by_email", true),
+                ("by_name_and_age", false),
+            ]
+        );
+        assert_eq!(
+            indexes[2].parts,
+            [
+                Part::field("name".to_string()).is_nullable(false),
+                Part::field("age".to_string()).is_nullable(false),
+            ]
+        );
+        assert_eq!(User::BY_NAME_AND_AGE.name(), "by_name_and_age");
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn typed_space() {
+        let users = TypedSpace::<User>::create().unwrap();
+        assert_eq!(users.as_space().meta().unwrap().name, User::SPACE_NAME);
+
+        let bob = user(1, "bob@example.com", "Bob", 30);
+        assert_eq!(users.insert(&bob).unwrap(), bob);
+        users.insert(&bob).unwrap_err();
+        let alice = user(2, "alice@example.com", "Alice", 25);
+        users.insert(&alice).unwrap();
+        let mut old_bob = user(3, "bob@old.example.com", "Bob", 70);
+        old_bob.nickname = Some("Bobby".into());
+        old_bob.extra = Some(serde_json::json!({"hobby": "fishing"}));
+        users.insert(&old_bob).unwrap();
+        assert_eq!(users.len().unwrap(), 3);
+
+        assert_eq!(users.get(&(1,)).unwrap(), Some(bob));
+        assert_eq!(users.get(&(4,)).unwrap(), None);
+
+        let by_email = users.index(User::BY_EMAIL).unwrap();
+        let found = by_email.get(&("alice@example.com".into(),)).unwrap();
+        assert_eq!(found, Some(alice));
+
+        let by_name_and_age = users.index(User::BY_NAME_AND_AGE).unwrap();
+        let bobs: Vec<User> = by_name_and_age
+            .select(IteratorType::Req, Some(&("Bob".into(), 70)))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(bobs, [old_bob]);
+
+        let ids: Vec<u64> = users
+            .select(IteratorType::All, None)
+            .unwrap()
+            .map(|u| u.unwrap().id)
+            .collect();
+        assert_eq!(ids, [1, 2, 3]);
+
+        let mut new_bob = user(1, "bob@new.example.com", "Bob", 31);
+        new_bob.nickname = Some("B".into());
+        assert_eq!(users.replace(&new_bob).unwrap(), new_bob);
+        assert_eq!(users.delete(&(1,)).unwrap(), Some(new_bob));
+        assert_eq!(users.delete(&(1,)).unwrap(), None);
+
+        let found = TypedSpace::<User>::find().unwrap();
+        assert_eq!(found.len().unwrap(), 2);
+
+        users.into_space().drop().unwrap();
+        assert!(TypedSpace::<User>::find().is_none());
+    }
+}
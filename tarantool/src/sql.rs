@@ -1,20 +1,31 @@
-#![cfg(any(feature = "picodata", doc))]
+//! SQL prepared statements and query results.
+//!
+//! Local execution of SQL queries is only available with the `picodata`
+//! feature. [`SqlResult`] can also be used with the results of remote queries,
+//! see [`AsClient::execute`].
+//!
+//! [`AsClient::execute`]: crate::network::client::AsClient::execute
 
-use crate::error::TarantoolError;
-use crate::ffi;
-use crate::ffi::sql::ObufWrapper;
-use serde::Serialize;
-use std::io::Read;
-use std::os::raw::c_char;
-use std::str;
+use crate::network::protocol::codec;
+use crate::tuple::DecodeOwned;
+use std::ops::Range;
+#[cfg(any(feature = "picodata", doc))]
+use {
+    crate::error::TarantoolError, crate::ffi, crate::ffi::sql::ObufWrapper, serde::Serialize,
+    std::io::Read, std::os::raw::c_char,
+};
 
 /// Returns the hash, used as the statement ID, generated from the SQL query text.
+#[cfg(any(feature = "picodata", doc))]
 pub fn calculate_hash(sql: &str) -> u32 {
     unsafe { ffi::sql::sql_stmt_calculate_id(sql.as_ptr() as *const c_char, sql.len()) }
 }
 
 /// Executes an SQL query without storing the prepared statement in the instance
 /// cache and returns a wrapper around the raw msgpack bytes.
+///
+/// See also [`prepare_and_execute`] which decodes the result.
+#[cfg(any(feature = "picodata", doc))]
 pub fn prepare_and_execute_raw<IN>(
     query: &str,
     bind_params: &IN,
@@ -46,6 +57,21 @@ where
     Ok(buf)
 }
 
+/// Executes an SQL query without storing the prepared statement in the instance
+/// cache and returns the decoded result.
+#[cfg(any(feature = "picodata", doc))]
+pub fn prepare_and_execute<IN>(
+    query: &str,
+    bind_params: &IN,
+    vdbe_max_steps: u64,
+) -> crate::Result<SqlResult>
+where
+    IN: Serialize,
+{
+    let buf = prepare_and_execute_raw(query, bind_params, vdbe_max_steps)?;
+    SqlResult::read_from(buf)
+}
+
 /// Creates new SQL prepared statement and stores it in the session.
 /// query - SQL query.
 ///
@@ -55,7 +81,7 @@ where
 /// already existing statement within the same session does not increase the
 /// instance cache counter. However, calling prepare on the statement in a
 /// different session without the statement does increase the counter.
-
+#[cfg(any(feature = "picodata", doc))]
 pub fn prepare(query: String) -> crate::Result<Statement> {
     let mut stmt_id: u32 = 0;
     let mut session_id: u64 = 0;
@@ -84,6 +110,7 @@ pub fn prepare(query: String) -> crate::Result<Statement> {
 /// The statement is removed from the session, and its reference counter in
 /// the instance cache is decremented. If the counter reaches zero, the
 /// statement is removed from the instance cache.
+#[cfg(any(feature = "picodata", doc))]
 pub fn unprepare(stmt: Statement) -> crate::Result<()> {
     if unsafe { ffi::sql::sql_unprepare_ext(stmt.id(), stmt.session_id()) } < 0 {
         return Err(TarantoolError::last().into());
@@ -92,6 +119,7 @@ pub fn unprepare(stmt: Statement) -> crate::Result<()> {
 }
 
 /// SQL prepared statement.
+#[cfg(any(feature = "picodata", doc))]
 #[derive(Default, Debug)]
 pub struct Statement {
     query: String,
@@ -99,6 +127,7 @@ pub struct Statement {
     session_id: u64,
 }
 
+#[cfg(any(feature = "picodata", doc))]
 impl Statement {
     /// Returns original query.
     pub fn source(&self) -> &str {
@@ -116,6 +145,8 @@ impl Statement {
     }
 
     /// Executes prepared statement and returns a wrapper over the raw msgpack bytes.
+    ///
+    /// See also [`Self::execute`] which decodes the result.
     pub fn execute_raw<IN>(&self, bind_params: &IN, vdbe_max_steps: u64) -> crate::Result<impl Read>
     where
         IN: Serialize,
@@ -139,4 +170,219 @@ impl Statement {
         }
         Ok(buf)
     }
+
+    /// Executes prepared statement and returns the decoded result.
+    pub fn execute<IN>(&self, bind_params: &IN, vdbe_max_steps: u64) -> crate::Result<SqlResult>
+    where
+        IN: Serialize,
+    {
+        let buf = self.execute_raw(bind_params, vdbe_max_steps)?;
+        SqlResult::read_from(buf)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SqlResult
+////////////////////////////////////////////////////////////////////////////////
+
+/// Metadata of a column in the result of an SQL query.
+///
+/// `collation`, `is_nullable`, `is_autoincrement` and `span` are only sent by
+/// tarantool if `sql_full_metadata` session setting is enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMetadata {
+    /// Column name.
+    pub name: String,
+    /// Column type, e.g. `"integer"`, `"string"`, `"any"`, etc.
+    pub field_type: String,
+    /// Collation of the column.
+    pub collation: Option<String>,
+    /// Whether the column is nullable.
+    pub is_nullable: Option<bool>,
+    /// Whether the column is autoincremented.
+    pub is_autoincrement: Option<bool>,
+    /// Original expression of the column in the query.
+    pub span: Option<String>,
+}
+
+/// Decoded result of an SQL query.
+///
+/// For queries returning data (e.g. `SELECT`) contains the column
+/// [`metadata`] and the [`rows`]. For DML queries (e.g. `INSERT`) contains the
+/// number of affected rows ([`row_count`]) and the ids generated by
+/// autoincrement columns ([`autoincrement_ids`]).
+///
+/// [`metadata`]: Self::metadata
+/// [`rows`]: Self::rows
+/// [`row_count`]: Self::row_count
+/// [`autoincrement_ids`]: Self::autoincrement_ids
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqlResult {
+    pub(crate) metadata: Vec<ColumnMetadata>,
+    /// Raw msgpack of the rows.
+    pub(crate) data: Vec<u8>,
+    /// Ranges of each of the rows in `data`.
+    pub(crate) rows: Vec<Range<usize>>,
+    pub(crate) row_count: Option<u64>,
+    pub(crate) autoincrement_ids: Vec<i64>,
+}
+
+impl SqlResult {
+    /// Decodes the result from a msgpack map with `IPROTO_METADATA`,
+    /// `IPROTO_DATA` and `IPROTO_SQL_INFO` keys, i.e. the body of an
+    /// `IPROTO_EXECUTE` response.
+    #[inline]
+    pub fn decode(data: &[u8]) -> crate::Result<Self> {
+        codec::decode_sql_result(&mut std::io::Cursor::new(data))
+    }
+
+    #[cfg(any(feature = "picodata", doc))]
+    fn read_from(mut r: impl Read) -> crate::Result<Self> {
+        let mut data = Vec::new();
+        r.read_to_end(&mut data)?;
+        Self::decode(&data)
+    }
+
+    /// Returns metadata of the result columns. Is empty for DML queries.
+    #[inline(always)]
+    pub fn metadata(&self) -> &[ColumnMetadata] {
+        &self.metadata
+    }
+
+    /// Returns the number of rows in the result.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if there are no rows in the result.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns an iterator over the raw msgpack of the result rows.
+    #[inline]
+    pub fn raw_rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.rows.iter().map(move |r| &self.data[r.clone()])
+    }
+
+    /// Returns an iterator decoding each of the result rows into `T`.
+    ///
+    /// A row is a msgpack array, so it can be decoded into a tuple or a struct
+    /// with the fields in the same order as the columns.
+    #[inline]
+    pub fn rows<'a, T>(&'a self) -> impl Iterator<Item = crate::Result<T>> + 'a
+    where
+        T: DecodeOwned + 'a,
+    {
+        self.raw_rows().map(T::decode)
+    }
+
+    /// Decodes all of the result rows into a `Vec<T>`.
+    ///
+    /// See [`Self::rows`].
+    #[inline]
+    pub fn decode_rows<T>(&self) -> crate::Result<Vec<T>>
+    where
+        T: DecodeOwned,
+    {
+        self.rows().collect()
+    }
+
+    /// Returns the number of rows affected by a DML query. Is `None` for
+    /// queries returning data.
+    #[inline(always)]
+    pub fn row_count(&self) -> Option<u64> {
+        self.row_count
+    }
+
+    /// Returns the ids generated by autoincrement columns during a DML query.
+    #[inline(always)]
+    pub fn autoincrement_ids(&self) -> &[i64] {
+        &self.autoincrement_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::iproto_key::{DATA, METADATA, SQL_INFO};
+    use rmpv::Value;
+
+    fn encode(body: Vec<(u8, Value)>) -> Vec<u8> {
+        let body = body.into_iter().map(|(k, v)| (k.into(), v)).collect();
+        let mut data = vec![];
+        rmpv::encode::write_value(&mut data, &Value::Map(body)).unwrap();
+        data
+    }
+
+    #[test]
+    fn decode_dql() {
+        let data = encode(vec![
+            (
+                METADATA,
+                Value::Array(vec![
+                    Value::Map(vec![(0.into(), "ID".into()), (1.into(), "integer".into())]),
+                    Value::Map(vec![
+                        (0.into(), "NAME".into()),
+                        (1.into(), "string".into()),
+                        (2.into(), "unicode_ci".into()),
+                        (3.into(), true.into()),
+                    ]),
+                ]),
+            ),
+            (
+                DATA,
+                Value::Array(vec![
+                    Value::Array(vec![1.into(), "one".into()]),
+                    Value::Array(vec![2.into(), "two".into()]),
+                ]),
+            ),
+        ]);
+        let result = SqlResult::decode(&data).unwrap();
+
+        assert_eq!(
+            result.metadata(),
+            [
+                ColumnMetadata {
+                    name: "ID".into(),
+                    field_type: "integer".into(),
+                    ..Default::default()
+                },
+                ColumnMetadata {
+                    name: "NAME".into(),
+                    field_type: "string".into(),
+                    collation: Some("unicode_ci".into()),
+                    is_nullable: Some(true),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result.decode_rows::<(u64, String)>().unwrap(),
+            [(1, "one".into()), (2, "two".into())]
+        );
+        assert_eq!(result.raw_rows().next().unwrap(), b"\x92\x01\xa3one");
+        assert_eq!(result.row_count(), None);
+        assert!(result.autoincrement_ids().is_empty());
+    }
+
+    #[test]
+    fn decode_dml() {
+        let data = encode(vec![(
+            SQL_INFO,
+            Value::Map(vec![
+                (0.into(), 3.into()),
+                (1.into(), Value::Array(vec![7.into(), (-8).into()])),
+            ]),
+        )]);
+        let result = SqlResult::decode(&data).unwrap();
+
+        assert!(result.metadata().is_empty());
+        assert!(result.is_empty());
+        assert_eq!(result.row_count(), Some(3));
+        assert_eq!(result.autoincrement_ids(), [7, -8]);
+    }
 }
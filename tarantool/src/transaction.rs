@@ -27,8 +27,69 @@
 //! - [Lua reference: Functions for transaction management](https://www.tarantool.io/en/doc/latest/reference/reference_lua/box_txn_management/)
 //! - [C API reference: Module txn](https://www.tarantool.io/en/doc/latest/dev_guide/reference_capi/txn/)
 
-use crate::error::TarantoolError;
+use crate::error::{BoxError, IntoBoxError, TarantoolError, TarantoolErrorCode};
 use crate::ffi::tarantool as ffi;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::time::Duration;
+
+crate::define_str_enum! {
+    /// Transaction isolation level.
+    ///
+    /// See [Transaction isolation levels](https://www.tarantool.io/en/doc/latest/concepts/atomic/txn_mode_mvcc/#txn-mode-mvcc-options)
+    /// for details.
+    pub enum IsolationLevel {
+        /// Use the isolation level from the `box.cfg.txn_isolation` option.
+        Default = "default",
+        /// Read confirmed changes if the transaction is read-only, otherwise
+        /// read all changes including the unconfirmed ones.
+        BestEffort = "best-effort",
+        /// Read all committed changes including the ones not yet confirmed by
+        /// the synchronous replication.
+        ReadCommitted = "read-committed",
+        /// Only read changes confirmed by the synchronous replication.
+        ReadConfirmed = "read-confirmed",
+        /// Only read changes which have been confirmed and are guaranteed to
+        /// be visible on every node of the replicaset.
+        Linearizable = "linearizable",
+    }
+}
+
+impl IsolationLevel {
+    /// Returns the numeric value of the isolation level as defined in
+    /// `enum txn_isolation_level` in \<tarantool>/src/box/txn.h.
+    #[inline]
+    pub const fn as_raw(&self) -> u32 {
+        match self {
+            Self::Default => 0,
+            Self::ReadCommitted => 1,
+            Self::ReadConfirmed => 2,
+            Self::BestEffort => 3,
+            Self::Linearizable => 4,
+        }
+    }
+}
+
+impl Default for IsolationLevel {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+/// Options of a transaction, see [`begin_with`] and [`transaction_with`].
+///
+/// Same as the options of `box.begin` in lua.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxnOptions {
+    /// Isolation level of the transaction. Only makes sense with the MVCC
+    /// engine enabled (`box.cfg.memtx_use_mvcc_engine`).
+    pub isolation: IsolationLevel,
+    /// If the transaction isn't committed within the `timeout`, it's rolled
+    /// back. If not set, `box.cfg.txn_timeout` is used.
+    pub timeout: Option<Duration>,
+}
 
 /// Transaction-related error cases
 #[derive(Debug, thiserror::Error)]
@@ -44,6 +105,12 @@ pub enum TransactionError<E> {
 
     #[error("transaction rolled-back: {0}")]
     RolledBack(E),
+
+    #[error("failed to create savepoint: {0}")]
+    FailedToCreateSavepoint(TarantoolError),
+
+    #[error("failed to begin: {0}")]
+    FailedToBegin(TarantoolError),
 }
 
 /// Executes a transaction in the current fiber.
@@ -56,25 +123,51 @@ pub enum TransactionError<E> {
 /// Returns result of function `f` execution. Depending on the function result:
 /// - will **commit** - if function completes successfully
 /// - will **rollback** - if function completes with any error
+#[inline(always)]
 pub fn transaction<T, E, F>(f: F) -> Result<T, TransactionError<E>>
 where
     F: FnOnce() -> Result<T, E>,
 {
-    if unsafe { ffi::box_txn_begin() } < 0 {
+    transaction_with(TxnOptions::default(), f)
+}
+
+/// Executes a transaction with the given `options` in the current fiber.
+///
+/// See [`transaction`] for details.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use tarantool::transaction::{transaction_with, IsolationLevel, TxnOptions};
+///
+/// let options = TxnOptions {
+///     isolation: IsolationLevel::ReadCommitted,
+///     timeout: Some(Duration::from_secs(1)),
+/// };
+/// transaction_with(options, || -> tarantool::Result<()> {
+///     // ...
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+pub fn transaction_with<T, E, F>(options: TxnOptions, f: F) -> Result<T, TransactionError<E>>
+where
+    F: FnOnce() -> Result<T, E>,
+{
+    if is_in_transaction() {
         return Err(TransactionError::AlreadyStarted);
     }
+    begin_with(options).map_err(TransactionError::FailedToBegin)?;
 
     let result = f();
     match &result {
         Ok(_) => {
-            if unsafe { ffi::box_txn_commit() } < 0 {
-                let error = TarantoolError::last();
+            if let Err(error) = commit() {
                 return Err(TransactionError::FailedToCommit(error));
             }
         }
         Err(_) => {
-            if unsafe { ffi::box_txn_rollback() } < 0 {
-                let error = TarantoolError::last();
+            if let Err(error) = rollback() {
                 return Err(TransactionError::FailedToRollback(error));
             }
         }
@@ -82,12 +175,94 @@ where
     result.map_err(TransactionError::RolledBack)
 }
 
+/// Executes `f` in a transaction, which may be nested into the active one.
+///
+/// If there's no active transaction, this is equivalent to [`transaction`].
+/// Otherwise a [`Savepoint`] is created before calling `f`, and if `f` returns
+/// an error only the changes made by `f` are rolled back. The outer
+/// transaction is neither committed nor rolled back in this case, so it's up
+/// to the caller to decide what to do with it.
+///
+/// This allows library code to be composed into the caller's transactions.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::space::Space;
+/// use tarantool::transaction::{nested_transaction, transaction};
+/// use tarantool::error::Error;
+///
+/// let space = Space::find("accounts").unwrap();
+/// transaction(|| -> Result<(), Error> {
+///     space.insert(&(1, "Alice"))?;
+///     // The inner insert fails with a duplicate key error, which only rolls
+///     // back the changes of the inner closure
+///     let res = nested_transaction(|| -> Result<(), Error> {
+///         space.insert(&(2, "Bob"))?;
+///         space.insert(&(1, "Alice"))?;
+///         Ok(())
+///     });
+///     assert!(res.is_err());
+///     Ok(())
+/// })
+/// .unwrap();
+/// // Only Alice is inserted
+/// ```
+pub fn nested_transaction<T, E, F>(f: F) -> Result<T, TransactionError<E>>
+where
+    F: FnOnce() -> Result<T, E>,
+{
+    if !is_in_transaction() {
+        return transaction(f);
+    }
+
+    let savepoint = Savepoint::new().map_err(TransactionError::FailedToCreateSavepoint)?;
+    match f() {
+        Ok(v) => {
+            savepoint.release();
+            Ok(v)
+        }
+        Err(e) => {
+            savepoint
+                .rollback()
+                .map_err(TransactionError::FailedToRollback)?;
+            Err(TransactionError::RolledBack(e))
+        }
+    }
+}
+
 /// Returns `true` if there's an active transaction.
 #[inline(always)]
 pub fn is_in_transaction() -> bool {
     unsafe { ffi::box_txn() }
 }
 
+/// Returns the id of the active transaction or `None` if there's no active
+/// transaction.
+#[inline(always)]
+pub fn id() -> Option<u64> {
+    // SAFETY: always safe
+    let id = unsafe { ffi::box_txn_id() };
+    (id >= 0).then_some(id as _)
+}
+
+thread_local! {
+    /// Isolation levels of the transactions started via [`begin_with`].
+    static ISOLATION_LEVELS: RefCell<HashMap<u64, IsolationLevel>> = Default::default();
+}
+
+/// Returns the isolation level of the active transaction or `None` if there's
+/// no active transaction.
+///
+/// Only the isolation levels set via [`begin_with`] or [`transaction_with`]
+/// are known, for other transactions (e.g. the ones started from lua with
+/// `box.begin{txn_isolation = ...}`) [`IsolationLevel::Default`] is returned.
+#[inline]
+pub fn isolation() -> Option<IsolationLevel> {
+    let id = id()?;
+    let level = ISOLATION_LEVELS.with(|levels| levels.borrow().get(&id).copied());
+    Some(level.unwrap_or_default())
+}
+
 /// Begin a transaction in the current fiber.
 ///
 /// One fiber can have at most one active transaction.
@@ -105,6 +280,46 @@ pub fn begin() -> Result<(), TarantoolError> {
     Ok(())
 }
 
+/// Begin a transaction with the given `options` in the current fiber.
+///
+/// See [`begin`] for details.
+pub fn begin_with(options: TxnOptions) -> Result<(), TarantoolError> {
+    begin()?;
+
+    let res = (|| {
+        if let Some(timeout) = options.timeout {
+            // SAFETY: always safe
+            if unsafe { ffi::box_txn_set_timeout(timeout.as_secs_f64()) } < 0 {
+                return Err(TarantoolError::last());
+            }
+        }
+        if options.isolation != IsolationLevel::Default {
+            // SAFETY: always safe
+            if unsafe { ffi::box_txn_set_isolation(options.isolation.as_raw()) } < 0 {
+                return Err(TarantoolError::last());
+            }
+            let id = id().expect("transaction is active");
+            // The entry is removed however the transaction ends, including
+            // `box.commit()` or `box.rollback()` called from lua.
+            let forget = move |_| {
+                ISOLATION_LEVELS.with(|levels| levels.borrow_mut().remove(&id));
+            };
+            crate::trigger::on_commit(forget)
+                .and_then(|()| crate::trigger::on_rollback(forget))
+                .map_err(IntoBoxError::into_box_error)?;
+            ISOLATION_LEVELS.with(|levels| levels.borrow_mut().insert(id, options.isolation));
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = res {
+        // The options are set before any statements, so this can't fail
+        let _ = rollback();
+        return Err(e);
+    }
+    Ok(())
+}
+
 /// Commit the active transaction.
 ///
 /// Returns `Ok(())` if there is no active transaction.
@@ -132,3 +347,126 @@ pub fn rollback() -> Result<(), TarantoolError> {
     }
     Ok(())
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// Savepoint
+////////////////////////////////////////////////////////////////////////////////
+
+/// A savepoint in the active transaction.
+///
+/// The changes made after the savepoint was created are rolled back when the
+/// savepoint is dropped, unless [`Savepoint::release`] is called.
+///
+/// The savepoint is only valid within the transaction it was created in. Once
+/// the transaction is committed or rolled back, the savepoint does nothing.
+///
+/// See also [`nested_transaction`].
+///
+/// # Example
+/// ```no_run
+/// use tarantool::space::Space;
+/// use tarantool::transaction::{self, Savepoint};
+///
+/// let space = Space::find("accounts").unwrap();
+/// transaction::begin().unwrap();
+/// space.insert(&(1, "Alice")).unwrap();
+/// let savepoint = Savepoint::new().unwrap();
+/// space.insert(&(2, "Bob")).unwrap();
+/// // Bob's insert is undone, Alice's stays
+/// savepoint.rollback().unwrap();
+/// transaction::commit().unwrap();
+/// ```
+#[derive(Debug)]
+#[must_use = "the savepoint is rolled back immediately if dropped"]
+pub struct Savepoint {
+    inner: NonNull<ffi::BoxTxnSavepoint>,
+    /// Id of the transaction the savepoint belongs to.
+    txn_id: i64,
+    is_released: bool,
+}
+
+impl Savepoint {
+    /// Creates a savepoint in the active transaction.
+    ///
+    /// Returns an error if there's no active transaction.
+    #[inline]
+    pub fn new() -> Result<Self, TarantoolError> {
+        // SAFETY: always safe
+        let inner = unsafe { ffi::box_txn_savepoint() };
+        let Some(inner) = NonNull::new(inner) else {
+            return Err(TarantoolError::last());
+        };
+        Ok(Self {
+            inner,
+            // SAFETY: always safe
+            txn_id: unsafe { ffi::box_txn_id() },
+            is_released: false,
+        })
+    }
+
+    /// Rolls back the changes made after the savepoint was created. The
+    /// transaction stays active.
+    ///
+    /// Returns an error if the transaction the savepoint was created in is no
+    /// longer active.
+    #[inline]
+    pub fn rollback(mut self) -> Result<(), TarantoolError> {
+        self.is_released = true;
+        self.rollback_impl()
+    }
+
+    /// Keeps the changes made after the savepoint was created.
+    #[inline(always)]
+    pub fn release(mut self) {
+        self.is_released = true;
+    }
+
+    fn rollback_impl(&self) -> Result<(), TarantoolError> {
+        // SAFETY: always safe
+        if unsafe { ffi::box_txn_id() } != self.txn_id {
+            // The savepoint's memory has been released along with the transaction
+            return Err(BoxError::new(
+                TarantoolErrorCode::NoSuchSavepoint,
+                "Can not rollback to savepoint: the savepoint does not exist",
+            ));
+        }
+        // SAFETY: the savepoint belongs to the active transaction
+        if unsafe { ffi::box_txn_rollback_to_savepoint(self.inner.as_ptr()) } < 0 {
+            return Err(TarantoolError::last());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Savepoint {
+    fn drop(&mut self) {
+        if self.is_released {
+            return;
+        }
+        if let Err(e) = self.rollback_impl() {
+            crate::say_verbose!("failed to rollback to savepoint: {e}");
+        }
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+
+    #[crate::test(tarantool = "crate")]
+    fn isolation_forgotten_when_finished_from_lua() {
+        let options = TxnOptions {
+            isolation: IsolationLevel::ReadCommitted,
+            ..Default::default()
+        };
+        let lua = crate::lua_state();
+        for finish in ["box.commit()", "box.rollback()"] {
+            begin_with(options).unwrap();
+            let id = id().unwrap();
+            assert_eq!(isolation(), Some(IsolationLevel::ReadCommitted));
+            lua.exec(finish).unwrap();
+            assert!(!is_in_transaction());
+            ISOLATION_LEVELS.with(|levels| assert!(!levels.borrow().contains_key(&id)));
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use crate::error::{TarantoolError, TarantoolErrorCode};
+use crate::error::{BoxError, Error, TarantoolError, TarantoolErrorCode};
 use crate::ffi::tarantool as ffi;
 use crate::set_error;
+use crate::space::SpaceId;
+use crate::transaction::is_in_transaction;
+use crate::tuple::Tuple;
+use std::cell::Cell;
 use std::io;
 
 /// Set a callback to be called on Tarantool shutdown.
@@ -25,3 +29,305 @@ pub fn on_shutdown<F: FnOnce() + 'static>(cb: F) -> Result<(), TarantoolError> {
         0
     }
 }
+
+/// A change made by a transaction, see [`on_commit`] and [`on_rollback`].
+#[derive(Debug, tlua::LuaRead)]
+pub struct TxnChange {
+    /// The tuple before the change, `None` if the tuple was inserted.
+    pub old: Option<Tuple>,
+    /// The tuple after the change, `None` if the tuple was deleted.
+    pub new: Option<Tuple>,
+    /// Id of the space the tuple belongs to.
+    pub space_id: SpaceId,
+}
+
+/// Iterator over the changes made by a transaction, passed to the callbacks
+/// of [`on_commit`] and [`on_rollback`].
+#[derive(Debug)]
+pub struct TxnChanges(std::vec::IntoIter<TxnChange>);
+
+impl Iterator for TxnChanges {
+    type Item = TxnChange;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<TxnChange> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for TxnChanges {}
+
+/// Set a callback to be called once the active transaction is committed. The
+/// callback receives the changes made by the transaction in the order they
+/// were made.
+///
+/// The callback is called once per committed transaction and receives all of
+/// its changes. It's called after the changes are written to WAL, although
+/// with `wal_mode = 'none'` nothing is actually persisted. It must not
+/// yield.
+///
+/// Returns an error if there's no active transaction.
+///
+/// Same as `box.on_commit` in lua.
+///
+/// # Example
+/// ```no_run
+/// use tarantool::transaction::transaction;
+/// use tarantool::trigger::on_commit;
+/// use tarantool::space::Space;
+///
+/// let space = Space::find("accounts").unwrap();
+/// transaction(|| -> tarantool::Result<()> {
+///     space.replace(&(1, "Alice"))?;
+///     on_commit(|changes| {
+///         for change in changes {
+///             println!("{:?} -> {:?}", change.old, change.new);
+///         }
+///     })?;
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+#[inline(always)]
+pub fn on_commit<F>(cb: F) -> Result<(), Error>
+where
+    F: FnOnce(TxnChanges) + 'static,
+{
+    set_txn_trigger("on_commit", cb)
+}
+
+/// Set a callback to be called once the active transaction is rolled back.
+/// The callback receives the changes made by the transaction in the order
+/// they were made.
+///
+/// The callback must not yield.
+///
+/// Returns an error if there's no active transaction.
+///
+/// Same as `box.on_rollback` in lua.
+#[inline(always)]
+pub fn on_rollback<F>(cb: F) -> Result<(), Error>
+where
+    F: FnOnce(TxnChanges) + 'static,
+{
+    set_txn_trigger("on_rollback", cb)
+}
+
+fn set_txn_trigger<F>(trigger: &str, cb: F) -> Result<(), Error>
+where
+    F: FnOnce(TxnChanges) + 'static,
+{
+    if !is_in_transaction() {
+        return Err(
+            BoxError::new(TarantoolErrorCode::NoTransaction, "No active transaction").into(),
+        );
+    }
+
+    let mut cb = Some(cb);
+    let cb = tlua::function1(move |changes: Vec<TxnChange>| {
+        // The trigger is called at most once
+        if let Some(cb) = cb.take() {
+            cb(TxnChanges(changes.into_iter()))
+        }
+    });
+    let code = format!(
+        "local cb = ...
+        box.{trigger}(function(iterator)
+            local changes = {{}}
+            for _, old, new, space_id in iterator() do
+                table.insert(changes, {{ old = old, new = new, space_id = space_id }})
+            end
+            cb(changes)
+        end)"
+    );
+    crate::global_lua()
+        .exec_with(&code, cb)
+        .map_err(tlua::LuaError::from)?;
+    Ok(())
+}
+
+/// A rust callback set as a lua trigger, see [`SpaceTrigger`] and
+/// [`SessionTrigger`].
+///
+/// The trigger functions are kept in a lua registry table so that they can be
+/// found when the trigger is removed. Entries for triggers which were removed
+/// from lua or whose trigger list no longer exists (e.g. the space was dropped)
+/// are forgotten the next time a trigger is set, releasing the callback.
+///
+/// [`SpaceTrigger`]: crate::space::SpaceTrigger
+/// [`SessionTrigger`]: crate::session::SessionTrigger
+#[derive(Debug)]
+pub(crate) struct LuaTrigger {
+    list: String,
+    id: u64,
+}
+
+/// Name of the lua registry table where the trigger functions are kept.
+const TRIGGERS_REGISTRY_KEY: &str = "tarantool.triggers";
+
+thread_local! {
+    static NEXT_TRIGGER_ID: Cell<u64> = Cell::new(1);
+}
+
+impl LuaTrigger {
+    /// Set the lua function `trigger` on the trigger list `list`.
+    ///
+    /// `list` is a lua expression evaluating to a function which works like
+    /// `box.session.on_connect`, i.e. sets a trigger if called with one, removes
+    /// it if called with `nil` and the trigger, and returns the triggers if
+    /// called with no arguments. It must raise an error if the trigger list no
+    /// longer exists.
+    ///
+    /// `trigger` can refer to `cb` as an upvalue.
+    pub(crate) fn set<F>(list: String, trigger: &str, cb: F) -> Result<Self, Error>
+    where
+        F: tlua::PushOneInto<tlua::LuaState, Err = tlua::Void>,
+    {
+        let id = NEXT_TRIGGER_ID.with(|next| next.replace(next.get() + 1));
+        crate::global_lua()
+            .exec_with(
+                &format!(
+                    "local id, cb = ...
+                    local registry = debug.getregistry()
+                    local triggers = registry['{TRIGGERS_REGISTRY_KEY}'] or {{}}
+                    registry['{TRIGGERS_REGISTRY_KEY}'] = triggers
+                    -- Forget the triggers which were removed from lua or whose
+                    -- trigger list no longer exists
+                    for tid, t in pairs(triggers) do
+                        local ok, fs = pcall(t.list)
+                        local alive = false
+                        if ok then
+                            for _, f in ipairs(fs) do
+                                if f == t.f then
+                                    alive = true
+                                    break
+                                end
+                            end
+                        end
+                        if not alive then
+                            triggers[tid] = nil
+                        end
+                    end
+                    local list = {list}
+                    local f = list({trigger})
+                    triggers[id] = {{ list = list, f = f }}"
+                ),
+                (id, cb),
+            )
+            .map_err(tlua::LuaError::from)?;
+        Ok(Self { list, id })
+    }
+
+    /// Remove the trigger. Returns an error if the trigger list no longer
+    /// exists.
+    pub(crate) fn remove(self) -> Result<(), Error> {
+        crate::global_lua()
+            .exec_with(
+                &format!(
+                    "local id = ...
+                    local list = {list}
+                    local triggers = debug.getregistry()['{TRIGGERS_REGISTRY_KEY}']
+                    local t = triggers[id]
+                    triggers[id] = nil
+                    if t ~= nil then
+                        list(nil, t.f)
+                    else
+                        -- The trigger has already been removed from lua, but
+                        -- the error is still reported
+                        list()
+                    end",
+                    list = self.list,
+                ),
+                self.id,
+            )
+            .map_err(tlua::LuaError::from)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "internal_test")]
+mod tests {
+    use super::*;
+    use crate::space::Space;
+    use crate::transaction;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Changes = Rc<RefCell<Option<Vec<(Option<(u32, String)>, Option<(u32, String)>)>>>>;
+
+    fn collect(changes: &Changes, space_id: SpaceId) -> impl FnOnce(TxnChanges) + 'static {
+        let changes = changes.clone();
+        move |iter| {
+            let decode = |t: Option<Tuple>| t.map(|t| t.decode().unwrap());
+            let res = iter
+                .inspect(|c| assert_eq!(c.space_id, space_id))
+                .map(|c| (decode(c.old), decode(c.new)))
+                .collect();
+            *changes.borrow_mut() = Some(res);
+        }
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn commit() {
+        let space = Space::find("test_s1").unwrap();
+        space.truncate().unwrap();
+        space.insert(&(1, "one")).unwrap();
+
+        let committed = Changes::default();
+        let rolled_back = Changes::default();
+        transaction::transaction(|| -> crate::Result<()> {
+            space.insert(&(2, "two"))?;
+            space.replace(&(1, "uno"))?;
+            space.delete(&(2,))?;
+            on_commit(collect(&committed, space.id()))?;
+            on_rollback(collect(&rolled_back, space.id()))?;
+            assert!(committed.borrow().is_none());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            committed.take().unwrap(),
+            [
+                (None, Some((2, "two".into()))),
+                (Some((1, "one".into())), Some((1, "uno".into()))),
+                (Some((2, "two".into())), None),
+            ]
+        );
+        assert!(rolled_back.take().is_none());
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn rollback() {
+        let space = Space::find("test_s1").unwrap();
+        space.truncate().unwrap();
+
+        let committed = Changes::default();
+        let rolled_back = Changes::default();
+        transaction::begin().unwrap();
+        space.insert(&(1, "one")).unwrap();
+        on_commit(collect(&committed, space.id())).unwrap();
+        on_rollback(collect(&rolled_back, space.id())).unwrap();
+        transaction::rollback().unwrap();
+
+        assert!(committed.take().is_none());
+        assert_eq!(
+            rolled_back.take().unwrap(),
+            [(None, Some((1, "one".into())))]
+        );
+    }
+
+    #[crate::test(tarantool = "crate")]
+    fn no_transaction() {
+        let err = on_commit(|_| {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "box error: NoTransaction: No active transaction"
+        );
+    }
+}
@@ -250,6 +250,33 @@ impl<'de> serde::Deserialize<'de> for Uuid {
     }
 }
 
+impl crate::msgpack::Encode for Uuid {
+    #[inline]
+    fn encode(
+        &self,
+        w: &mut impl std::io::Write,
+        _context: &crate::msgpack::Context,
+    ) -> Result<(), crate::msgpack::EncodeError> {
+        crate::msgpack::write_ext(w, ffi::MP_UUID, self.as_bytes())
+    }
+}
+
+impl<'de> crate::msgpack::Decode<'de> for Uuid {
+    #[inline]
+    fn decode(
+        r: &mut &'de [u8],
+        _context: &crate::msgpack::Context,
+    ) -> Result<Self, crate::msgpack::DecodeError> {
+        let data = crate::msgpack::read_ext::<Self>(r, ffi::MP_UUID)?;
+        Self::try_from_slice(data).ok_or_else(|| {
+            crate::msgpack::DecodeError::new::<Self>(format!(
+                "Not enough bytes for UUID: expected 16, got {}",
+                data.len()
+            ))
+        })
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 /// Lua
 ////////////////////////////////////////////////////////////////////////////////
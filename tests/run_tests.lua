@@ -21,6 +21,8 @@ box.cfg{
     wal_mode = 'none',
     memtx_dir = tmpdir,
     wal_dir = tmpdir,
+    -- Transactions must survive yields for the transaction timeout tests
+    memtx_use_mvcc_engine = true,
 }
 
 log.info("version: " .. box.info.version)
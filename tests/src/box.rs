@@ -1,11 +1,13 @@
 use rand::Rng;
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::rc::Rc;
 
 use tarantool::index::{self, IndexOptions, IteratorType};
-use tarantool::sequence::Sequence;
-use tarantool::space::UpdateOps;
+use tarantool::sequence::{Sequence, SequenceAlterOptions};
 use tarantool::space::{self, Field, Space, SystemSpace};
+use tarantool::space::{BeforeReplace, UpdateOps};
 use tarantool::space::{SpaceCreateOptions, SpaceEngineType, SpaceType};
 use tarantool::test::util::on_scope_exit;
 use tarantool::tuple::Tuple;
@@ -733,6 +735,123 @@ pub fn truncate() {
     assert_eq!(space.len().unwrap(), 0_usize);
 }
 
+pub fn on_replace() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    type Change = (Option<(u32, String)>, Option<(u32, String)>);
+    let changes: Rc<RefCell<Vec<Change>>> = Default::default();
+    let trigger = space
+        .on_replace({
+            let changes = changes.clone();
+            move |old, new| {
+                let decode = |t: Option<Tuple>| t.map(|t| t.decode().unwrap());
+                changes.borrow_mut().push((decode(old), decode(new)));
+            }
+        })
+        .unwrap();
+    assert_eq!(trigger.space_id(), space.id());
+
+    {
+        let _guard = on_scope_exit(|| trigger.remove().unwrap());
+
+        space.insert(&(1, "one")).unwrap();
+        space.replace(&(1, "uno")).unwrap();
+        space.delete(&(1,)).unwrap();
+        assert_eq!(
+            changes.take(),
+            [
+                (None, Some((1, "one".into()))),
+                (Some((1, "one".into())), Some((1, "uno".into()))),
+                (Some((1, "uno".into())), None),
+            ]
+        );
+    }
+
+    space.insert(&(2, "two")).unwrap();
+    assert!(changes.borrow().is_empty());
+}
+
+pub fn before_replace() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    let trigger = space
+        .before_replace(|old, new| match (old, new) {
+            // Forbid deletes
+            (Some(_), None) => BeforeReplace::Skip,
+            // Convert the text of the new tuples to upper case
+            (_, Some(new)) => {
+                let (id, text): (u32, String) = new.decode().unwrap();
+                if text == "remove me" {
+                    return BeforeReplace::Delete;
+                }
+                let new = Tuple::new(&(id, text.to_uppercase())).unwrap();
+                BeforeReplace::Replace(new)
+            }
+            (None, None) => BeforeReplace::Keep,
+        })
+        .unwrap();
+
+    {
+        let _guard = on_scope_exit(|| trigger.remove().unwrap());
+
+        let tuple = space.insert(&(1, "one")).unwrap();
+        assert_eq!(tuple.decode::<(u32, String)>().unwrap(), (1, "ONE".into()));
+
+        space.delete(&(1,)).unwrap();
+        let tuple = space.get(&(1,)).unwrap().unwrap();
+        assert_eq!(tuple.decode::<(u32, String)>().unwrap(), (1, "ONE".into()));
+
+        space.replace(&(1, "remove me")).unwrap();
+        assert!(space.get(&(1,)).unwrap().is_none());
+    }
+
+    space.insert(&(2, "two")).unwrap();
+    let tuple = space.get(&(2,)).unwrap().unwrap();
+    assert_eq!(tuple.decode::<(u32, String)>().unwrap(), (2, "two".into()));
+    space.delete(&(2,)).unwrap();
+    assert!(space.get(&(2,)).unwrap().is_none());
+}
+
+pub fn space_trigger_released() {
+    let lua = tarantool::lua_state();
+    let space = Space::builder("test_trigger_release_space")
+        .create()
+        .unwrap();
+    space.index_builder("pk").part(1).create().unwrap();
+    let marker = Rc::new(());
+    let cb = || {
+        let marker = marker.clone();
+        move |_: Option<Tuple>, _: Option<Tuple>| {
+            let _ = &marker;
+        }
+    };
+    let release_stale = || {
+        let s1 = Space::find("test_s1").unwrap();
+        s1.on_replace(|_, _| {}).unwrap().remove().unwrap();
+        lua.exec("collectgarbage()").unwrap();
+    };
+
+    // The trigger is removed from lua
+    space.on_replace(cb()).unwrap();
+    lua.exec_with(
+        "local space = box.space[...]
+        space:on_replace(nil, space:on_replace()[1])",
+        space.id(),
+    )
+    .unwrap();
+    release_stale();
+    assert_eq!(Rc::strong_count(&marker), 1);
+
+    // The space is dropped
+    space.on_replace(cb()).unwrap();
+    assert_eq!(Rc::strong_count(&marker), 2);
+    space.drop().unwrap();
+    release_stale();
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
 pub fn sequence_get_by_name() {
     assert!(Sequence::find("test_seq").unwrap().is_some());
     assert!(Sequence::find("test_seq_invalid").unwrap().is_none());
@@ -754,6 +873,67 @@ pub fn sequence_set() {
     assert_eq!(seq.next().unwrap(), 100);
 }
 
+pub fn sequence_create_alter_drop() {
+    let mut seq = Sequence::builder("test_seq_create")
+        .start(10)
+        .step(5)
+        .max(20)
+        .create()
+        .unwrap();
+    assert_eq!(seq.current().unwrap(), None);
+    assert_eq!(seq.next().unwrap(), 10);
+    assert_eq!(seq.next().unwrap(), 15);
+    assert_eq!(seq.current().unwrap(), Some(15));
+    assert_eq!(seq.next().unwrap(), 20);
+    assert!(seq.next().is_err());
+
+    // Already exists.
+    assert!(Sequence::builder("test_seq_create").create().is_err());
+    let same = Sequence::builder("test_seq_create")
+        .if_not_exists(true)
+        .create()
+        .unwrap();
+    assert_eq!(same.id(), seq.id());
+
+    seq.alter(&SequenceAlterOptions {
+        cycle: Some(true),
+        min: Some(1),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(seq.next().unwrap(), 1);
+    assert_eq!(seq.current().unwrap(), Some(1));
+    seq.reset().unwrap();
+    assert_eq!(seq.current().unwrap(), None);
+
+    seq.drop().unwrap();
+    assert!(Sequence::find("test_seq_create").unwrap().is_none());
+}
+
+pub fn sequence_attach_to_space() {
+    let space = Space::builder("test_seq_attach_space").create().unwrap();
+    space.index_builder("pk").part(1).create().unwrap();
+    let seq = Sequence::builder("test_seq_attach").create().unwrap();
+
+    seq.attach(&space, 0).unwrap();
+    space.insert(&(None::<u64>, "a")).unwrap();
+    space.insert(&(None::<u64>, "b")).unwrap();
+    let ids: Vec<u64> = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .map(|t| t.field(0).unwrap().unwrap())
+        .collect();
+    assert_eq!(ids, [1, 2]);
+
+    // A sequence attached to a space can't be dropped.
+    assert!(seq.drop().is_err());
+
+    Sequence::detach(&space).unwrap();
+    assert!(space.insert(&(None::<u64>, "c")).is_err());
+    seq.drop().unwrap();
+    space.drop().unwrap();
+}
+
 pub fn space_create_opt_default() {
     let opts = SpaceCreateOptions::default();
 
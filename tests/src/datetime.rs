@@ -1,5 +1,6 @@
-use tarantool::{datetime::Datetime, tuple::Tuple};
-use time_macros::datetime;
+use tarantool::datetime::{tz_index, tz_name, Adjust, Datetime, Format, Interval};
+use tarantool::{msgpack, tuple::Tuple};
+use time_macros::{datetime, offset};
 
 pub fn to_tuple() {
     let dt: Datetime = datetime!(2023-11-11 6:10:20.10010 -7).into();
@@ -34,3 +35,207 @@ pub fn from_lua() {
 
     assert_eq!(d.to_string(), "2023-11-11 10:11:12.10142 +05:00:00");
 }
+
+pub fn msgpack_round_trip() {
+    #[derive(Debug, PartialEq, msgpack::Encode, msgpack::Decode)]
+    struct Record {
+        id: u32,
+        created: Datetime,
+        expires: Datetime,
+    }
+
+    let record = Record {
+        id: 1,
+        created: datetime!(2023-11-11 10:11:12.10142 +5).into(),
+        expires: datetime!(2024-01-01 0:00:00 +0).into(),
+    };
+    let data = msgpack::encode(&record);
+
+    let t: Tuple = tarantool::lua_state()
+        .eval(
+            "local datetime = require('datetime')
+            return box.tuple.new(
+                1,
+                datetime.parse('2023-11-11T10:11:12.10142+0500'),
+                datetime.parse('2024-01-01T00:00:00Z')
+            )",
+        )
+        .unwrap();
+    assert_eq!(data, t.to_vec());
+    assert_eq!(msgpack::decode::<Record>(&t.to_vec()).unwrap(), record);
+
+    let (_, created, expires): (u32, Datetime, Datetime) =
+        Tuple::try_from_slice(&data).unwrap().decode().unwrap();
+    assert_eq!(created, record.created);
+    assert_eq!(expires, record.expires);
+}
+
+pub fn interval_from_lua() {
+    let interval: Interval = tarantool::lua_state()
+        .eval(
+            "return require('datetime').interval.new {
+                year = 1, month = -2, week = 3, day = 4,
+                hour = 5, min = 6, sec = 7, nsec = 8,
+                adjust = 'last',
+            }",
+        )
+        .unwrap();
+    assert_eq!(
+        interval,
+        Interval {
+            year: 1,
+            month: -2,
+            week: 3,
+            day: 4,
+            hour: 5,
+            min: 6,
+            sec: 7,
+            nsec: 8,
+            adjust: Adjust::Last,
+        }
+    );
+}
+
+pub fn interval_to_lua() {
+    let interval = Interval {
+        month: 1,
+        hour: -3,
+        adjust: Adjust::Excess,
+        ..Default::default()
+    };
+    let (month, hour, same): (i32, i64, Interval) = tarantool::lua_state()
+        .eval_with(
+            "local i = ...
+            return i.month, i.hour, i",
+            interval,
+        )
+        .unwrap();
+    assert_eq!((month, hour), (1, -3));
+    assert_eq!(same, interval);
+}
+
+pub fn interval_msgpack() {
+    let t: Tuple = tarantool::lua_state()
+        .eval(
+            "return box.tuple.new(require('datetime').interval.new {
+                year = 1, month = -1, day = 300, adjust = 'excess',
+            })",
+        )
+        .unwrap();
+    let interval = Interval {
+        year: 1,
+        month: -1,
+        day: 300,
+        adjust: Adjust::Excess,
+        ..Default::default()
+    };
+    assert_eq!(t.to_vec(), msgpack::encode(&(interval,)));
+    let (decoded,): (Interval,) = t.decode().unwrap();
+    assert_eq!(decoded, interval);
+}
+
+pub fn interval_arithmetic() {
+    let lua = tarantool::lua_state();
+    let dt: Datetime = datetime!(2024-01-31 10:20:30.5 +3).into();
+    for adjust in [Adjust::None, Adjust::Last, Adjust::Excess] {
+        let interval = Interval {
+            year: 1,
+            month: 1,
+            day: 1,
+            hour: 14,
+            nsec: 600_000_000,
+            adjust,
+            ..Default::default()
+        };
+        let (sum, diff): (Datetime, Datetime) = lua
+            .eval_with("local dt, i = ... return dt + i, dt - i", (dt, interval))
+            .unwrap();
+        assert_eq!(dt + interval, sum);
+        assert_eq!(dt - interval, diff);
+    }
+
+    let other: Datetime = datetime!(2023-02-28 23:00:00.75 UTC).into();
+    let diff: Interval = lua
+        .eval_with("local lhs, rhs = ... return lhs - rhs", (dt, other))
+        .unwrap();
+    assert_eq!(other + diff, dt);
+    assert_eq!(other + (dt - other), dt);
+}
+
+pub fn interval_arithmetic_dst() {
+    // Daylight saving time starts on 2023-03-12 in New York
+    let dt = Datetime::parse("2023-03-11T12:00:00 America/New_York").unwrap();
+    assert_eq!(dt.into_inner().offset(), offset!(-5));
+    let interval = Interval {
+        day: 1,
+        ..Default::default()
+    };
+
+    let next = dt + interval;
+    assert_eq!(next.tzindex(), dt.tzindex());
+    assert_eq!(next.into_inner().offset(), offset!(-4));
+    // The offset agrees with the timezone
+    let reparsed = Datetime::parse(&next.to_tarantool_string()).unwrap();
+    assert_eq!(reparsed.into_inner(), next.into_inner());
+    assert_eq!(reparsed.into_inner().offset(), next.into_inner().offset());
+
+    let prev = next - interval;
+    assert_eq!(prev.into_inner().offset(), offset!(-5));
+
+    let lua = tarantool::lua_state();
+    let sum: Datetime = lua
+        .eval_with("local dt, i = ... return dt + i", (dt, interval))
+        .unwrap();
+    assert_eq!(sum.into_inner(), next.into_inner());
+}
+
+pub fn tz_from_lua() {
+    let dt: Datetime = tarantool::lua_state()
+        .eval("return require('datetime').parse('2023-11-11T10:11:12 Europe/Moscow')")
+        .unwrap();
+    assert_ne!(dt.tzindex(), 0);
+    assert_eq!(dt.tz().as_deref(), Some("Europe/Moscow"));
+    assert_eq!(dt.into_inner(), datetime!(2023-11-11 10:11:12 +3));
+
+    // The timezone survives a round trip through a tuple
+    let t = Tuple::new(&(dt,)).unwrap();
+    let (tz, tzindex): (String, i16) = tarantool::lua_state()
+        .eval_with("local dt = (...):unpack() return dt.tz, dt.tzindex", &t)
+        .unwrap();
+    assert_eq!(tz, "Europe/Moscow");
+    assert_eq!(tzindex, dt.tzindex());
+    let [decoded]: [Datetime; 1] = msgpack::decode(&t.to_vec()).unwrap();
+    assert_eq!(decoded.tzindex(), dt.tzindex());
+}
+
+pub fn tz_lookup() {
+    let index = tz_index("Europe/Moscow").unwrap();
+    assert_ne!(index, 0);
+    assert_eq!(tz_name(index).as_deref(), Some("Europe/Moscow"));
+    assert_eq!(tz_index("Not/A_Timezone"), None);
+    assert_eq!(tz_name(0), None);
+}
+
+pub fn parse_and_format() {
+    let dt = Datetime::parse("2023-11-11T10:11:12.10142+0500").unwrap();
+    assert_eq!(dt.into_inner(), datetime!(2023-11-11 10:11:12.10142 +5));
+    assert_eq!(dt.tzindex(), 0);
+    assert_eq!(dt.to_tarantool_string(), "2023-11-11T10:11:12.10142+0500");
+
+    let dt = Datetime::parse("2023-11-11T10:11:12 Europe/Moscow").unwrap();
+    assert_eq!(dt.tz().as_deref(), Some("Europe/Moscow"));
+    assert_eq!(
+        dt.to_tarantool_string(),
+        "2023-11-11T10:11:12 Europe/Moscow"
+    );
+
+    let dt = Datetime::parse_with_format("2023-11-11T10:11:12.5+03:00", Format::Rfc3339).unwrap();
+    assert_eq!(dt.into_inner(), datetime!(2023-11-11 10:11:12.5 +3));
+
+    let dt = Datetime::parse_with_format("11.11.2023 10:11", Format::Strftime("%d.%m.%Y %H:%M"))
+        .unwrap();
+    assert_eq!(dt.into_inner(), datetime!(2023-11-11 10:11 UTC));
+    assert_eq!(dt.format("%Y/%m/%d %H-%M").unwrap(), "2023/11/11 10-11");
+
+    Datetime::parse("definitely not a date").unwrap_err();
+}
@@ -418,6 +418,9 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 r#box::upsert,
                 r#box::upsert_macro,
                 r#box::truncate,
+                r#box::on_replace,
+                r#box::before_replace,
+                r#box::space_trigger_released,
                 r#box::get,
                 r#box::select,
                 r#box::select_composite_key,
@@ -429,6 +432,8 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 r#box::sequence_get_by_name,
                 r#box::sequence_iterate,
                 r#box::sequence_set,
+                r#box::sequence_create_alter_drop,
+                r#box::sequence_attach_to_space,
                 r#box::space_create_opt_default,
                 r#box::space_create_opt_if_not_exists,
                 r#box::space_create_id_increment,
@@ -473,6 +478,11 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 coio::channel_tx_closed,
                 transaction::transaction_commit,
                 transaction::transaction_rollback,
+                transaction::savepoint_rollback,
+                transaction::savepoint_outside_transaction,
+                transaction::nested_transaction_rollback,
+                transaction::transaction_with_options,
+                transaction::transaction_timeout,
                 latch::latch_lock,
                 latch::latch_try_lock,
                 net_box::immediate_close,
@@ -509,6 +519,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 uuid::from_tuple,
                 uuid::to_lua,
                 uuid::from_lua,
+                uuid::msgpack_round_trip,
                 enums::space_engine_type,
                 enums::space_field_type,
                 enums::index_type,
@@ -522,6 +533,15 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                 datetime::from_tuple,
                 datetime::to_lua,
                 datetime::from_lua,
+                datetime::msgpack_round_trip,
+                datetime::interval_from_lua,
+                datetime::interval_to_lua,
+                datetime::interval_msgpack,
+                datetime::interval_arithmetic,
+                datetime::interval_arithmetic_dst,
+                datetime::tz_from_lua,
+                datetime::tz_lookup,
+                datetime::parse_and_format,
             ]);
 
             #[cfg(not(feature = "picodata"))]
@@ -549,6 +569,7 @@ fn run_tests(cfg: TestConfig) -> Result<bool, io::Error> {
                     sql::prepared_with_unnamed_params,
                     sql::prepared_with_named_params,
                     sql::prepared_invalid_params,
+                    sql::prepared_typed_result,
                     tuple_picodata::tuple_format_get_names,
                     tuple_picodata::tuple_as_named_buffer,
                     tuple_picodata::tuple_hash,
@@ -252,3 +252,31 @@ pub fn prepared_with_named_params() {
 
     drop_sql_test_space(sp).unwrap();
 }
+
+pub fn prepared_typed_result() {
+    let sp = create_sql_test_space("SQL_TEST").unwrap();
+
+    let result = tarantool::sql::prepare_and_execute(
+        "INSERT INTO SQL_TEST VALUES (1, 'one'), (2, 'two')",
+        &(),
+        0,
+    )
+    .unwrap();
+    assert!(result.metadata().is_empty());
+    assert!(result.is_empty());
+    assert_eq!(result.row_count(), Some(2));
+
+    let stmt = tarantool::sql::prepare("SELECT * FROM SQL_TEST WHERE ID > ?".to_string()).unwrap();
+    let result = stmt.execute(&(0,), 0).unwrap();
+    let columns: Vec<_> = result
+        .metadata()
+        .iter()
+        .map(|c| (c.name.as_str(), c.field_type.as_str()))
+        .collect();
+    assert_eq!(columns, [("ID", "unsigned"), ("VALUE", "string")]);
+    assert_eq!(result.row_count(), None);
+    let rows = result.decode_rows::<(u64, String)>().unwrap();
+    assert_eq!(rows, [(1, "one".to_string()), (2, "two".to_string())]);
+
+    drop_sql_test_space(sp).unwrap();
+}
@@ -1,8 +1,14 @@
 use std::io;
+use std::time::Duration;
 
-use tarantool::error::Error;
+use tarantool::error::{Error, TarantoolErrorCode};
+use tarantool::fiber;
+use tarantool::index::IteratorType;
 use tarantool::space::Space;
-use tarantool::transaction::transaction;
+use tarantool::transaction::{
+    self, nested_transaction, transaction, transaction_with, IsolationLevel, Savepoint,
+    TransactionError, TxnOptions,
+};
 
 use crate::common::S1Record;
 
@@ -45,3 +51,168 @@ pub fn transaction_rollback() {
     let output = space.get(&(1,)).unwrap();
     assert!(output.is_none());
 }
+
+pub fn savepoint_rollback() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    transaction(|| -> Result<(), Error> {
+        space.insert(&(1, "one"))?;
+        let savepoint = Savepoint::new()?;
+        space.insert(&(2, "two"))?;
+        savepoint.rollback()?;
+        space.insert(&(3, "three"))?;
+
+        // Changes made after an unreleased savepoint are rolled back on drop
+        {
+            let _savepoint = Savepoint::new()?;
+            space.insert(&(4, "four"))?;
+        }
+
+        // Changes made after a released savepoint are kept
+        let savepoint = Savepoint::new()?;
+        space.insert(&(5, "five"))?;
+        savepoint.release();
+        Ok(())
+    })
+    .unwrap();
+
+    let ids: Vec<u32> = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .map(|t| t.get(0).unwrap())
+        .collect();
+    assert_eq!(ids, [1, 3, 5]);
+}
+
+pub fn savepoint_outside_transaction() {
+    let err = Savepoint::new().unwrap_err();
+    assert_eq!(err.error_code(), TarantoolErrorCode::NoTransaction as u32);
+
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    let mut savepoint = None;
+    transaction(|| -> Result<(), Error> {
+        savepoint = Some(Savepoint::new()?);
+        space.insert(&(1, "one"))?;
+        Ok(())
+    })
+    .unwrap();
+
+    // The savepoint doesn't outlive its transaction
+    transaction::begin().unwrap();
+    let err = savepoint.unwrap().rollback().unwrap_err();
+    assert_eq!(err.error_code(), TarantoolErrorCode::NoSuchSavepoint as u32);
+    transaction::rollback().unwrap();
+    assert_eq!(space.len().unwrap(), 1);
+}
+
+pub fn nested_transaction_rollback() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    transaction(|| -> Result<(), Error> {
+        space.insert(&(1, "one"))?;
+
+        let res = nested_transaction(|| -> Result<(), Error> {
+            space.insert(&(2, "two"))?;
+            // Duplicate key
+            space.insert(&(1, "one"))?;
+            Ok(())
+        });
+        assert!(matches!(res, Err(TransactionError::RolledBack(_))));
+        assert!(transaction::is_in_transaction());
+
+        nested_transaction(|| -> Result<(), Error> {
+            space.insert(&(3, "three"))?;
+            Ok(())
+        })?;
+        Ok(())
+    })
+    .unwrap();
+
+    let ids: Vec<u32> = space
+        .select(IteratorType::All, &())
+        .unwrap()
+        .map(|t| t.get(0).unwrap())
+        .collect();
+    assert_eq!(ids, [1, 3]);
+
+    // Without an active transaction it's a regular transaction
+    nested_transaction(|| -> Result<(), Error> {
+        space.insert(&(4, "four"))?;
+        Err(Error::IO(io::ErrorKind::Interrupted.into()))
+    })
+    .unwrap_err();
+    assert!(!transaction::is_in_transaction());
+    assert!(space.get(&(4,)).unwrap().is_none());
+}
+
+pub fn transaction_with_options() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+    assert_eq!(transaction::id(), None);
+    assert_eq!(transaction::isolation(), None);
+
+    let options = TxnOptions {
+        isolation: IsolationLevel::ReadCommitted,
+        timeout: Some(Duration::from_secs(10)),
+    };
+    let id = transaction_with(options, || -> Result<u64, Error> {
+        space.insert(&(1, "one"))?;
+        assert_eq!(
+            transaction::isolation(),
+            Some(IsolationLevel::ReadCommitted)
+        );
+        Ok(transaction::id().unwrap())
+    })
+    .unwrap();
+    assert_eq!(transaction::id(), None);
+    assert_eq!(transaction::isolation(), None);
+    assert_eq!(space.len().unwrap(), 1);
+
+    transaction::begin().unwrap();
+    assert!(transaction::id().unwrap() > id);
+    assert_eq!(transaction::isolation(), Some(IsolationLevel::Default));
+    transaction::rollback().unwrap();
+
+    // Already started
+    transaction::begin().unwrap();
+    let err = transaction_with(options, || -> Result<(), Error> { Ok(()) }).unwrap_err();
+    assert!(matches!(err, TransactionError::AlreadyStarted));
+    transaction::rollback().unwrap();
+
+    // Invalid timeout
+    let options = TxnOptions {
+        timeout: Some(Duration::ZERO),
+        ..Default::default()
+    };
+    let err = transaction::begin_with(options).unwrap_err();
+    assert_eq!(err.error_code(), TarantoolErrorCode::IllegalParams as u32);
+    assert!(!transaction::is_in_transaction());
+}
+
+pub fn transaction_timeout() {
+    let space = Space::find("test_s1").unwrap();
+    space.truncate().unwrap();
+
+    let options = TxnOptions {
+        timeout: Some(Duration::from_millis(10)),
+        ..Default::default()
+    };
+    let err = transaction_with(options, || -> Result<(), Error> {
+        space.insert(&(1, "one"))?;
+        fiber::sleep(Duration::from_millis(100));
+        Ok(())
+    })
+    .unwrap_err();
+    let TransactionError::FailedToCommit(err) = err else {
+        panic!("unexpected error: {}", err);
+    };
+    assert_eq!(
+        err.error_code(),
+        TarantoolErrorCode::TransactionTimeout as u32
+    );
+    assert!(space.get(&(1,)).unwrap().is_none());
+}
@@ -1,4 +1,4 @@
-use tarantool::{tlua::LuaFunction, tuple::Tuple, uuid::Uuid};
+use tarantool::{msgpack, tlua::LuaFunction, tuple::Tuple, uuid::Uuid};
 
 const UUID_STR: &str = "30de7784-33e2-4393-a8cd-b67534db2432";
 
@@ -36,3 +36,30 @@ pub fn from_lua() {
     let s: String = tostring.call_with_args(uuid).unwrap();
     assert_eq!(s, UUID_STR);
 }
+
+pub fn msgpack_round_trip() {
+    #[derive(Debug, PartialEq, msgpack::Encode, msgpack::Decode)]
+    struct Record {
+        id: u32,
+        uuid: Uuid,
+    }
+
+    let record = Record {
+        id: 1,
+        uuid: Uuid::parse_str(UUID_STR).unwrap(),
+    };
+    let data = msgpack::encode(&record);
+
+    let t: Tuple = tarantool::lua_state()
+        .eval_with(
+            "return box.tuple.new(1, require('uuid').fromstr(...))",
+            UUID_STR,
+        )
+        .unwrap();
+    assert_eq!(data, t.to_vec());
+    assert_eq!(msgpack::decode::<Record>(&t.to_vec()).unwrap(), record);
+
+    let (id, uuid): (u32, Uuid) = Tuple::try_from_slice(&data).unwrap().decode().unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(uuid, record.uuid);
+}